@@ -0,0 +1,31 @@
+/* menucli C API. Build with: cargo build --release --features ffi */
+#ifndef MENUCLI_H
+#define MENUCLI_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes: 0 = success, otherwise the CLI exit code for the failure
+ * (1 = runtime error, 2 = invalid argument, 3 = permission denied,
+ * 4 = app/item not found or ambiguous, 5 = nothing to print,
+ * 6 = assertion failed).
+ *
+ * `*out` always receives a JSON string (result, or error envelope) that must be
+ * released with menucli_free_string(). `opts` is an optional JSON object. */
+
+/* opts keys: extras, depth, enabled_only, alternates */
+int32_t menucli_list_json(int32_t pid, const char *opts, char **out);
+
+/* opts keys: extras, dry_run, alternates */
+int32_t menucli_click(int32_t pid, const char *path, const char *opts, char **out);
+
+void menucli_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MENUCLI_H */
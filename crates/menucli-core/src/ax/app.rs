@@ -0,0 +1,457 @@
+/// App PID resolution via NSWorkspace.
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use objc2_app_kit::{
+    NSApplicationActivationOptions, NSApplicationActivationPolicy, NSRunningApplication,
+    NSWorkspace,
+};
+use objc2_foundation::{NSString, NSURL};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::element::{AXElement, DEFAULT_MESSAGING_TIMEOUT_SECS};
+use super::errors::AXError;
+use super::locale::load_strings;
+use crate::pattern;
+
+/// Resolve an app identifier string (name, bundle ID, `.app` path, pattern, or
+/// PID integer) to a PID.
+///
+/// Resolution order:
+/// 1. If the string is a valid integer → treat as PID, if a running
+///    application has it (see [`running_pid`]).
+/// 2. If the string is a bundle ID (see [`is_bundle_id`]) or an `.app` path
+///    (see [`is_bundle_path`]) → exact match, among all running processes.
+/// 3. Otherwise → treat as app name or pattern among named apps (see
+///    [`match_app`]).
+///
+/// # Errors
+///
+/// Returns `Err(AXError::AppNotFound)` if no running application matches,
+/// `Err(AXError::AmbiguousApp)` if several match equally well, or
+/// `Err(AXError::InvalidAppPattern)` for a malformed `glob:` or `re:` pattern.
+pub fn resolve_app_pid(identifier: &str) -> Result<i32, AXError> {
+    if let Ok(pid) = identifier.parse::<i32>() {
+        return running_pid(pid, is_running);
+    }
+    let apps = if is_bundle_id(identifier) || is_bundle_path(identifier) {
+        list_all_running_apps()
+    } else {
+        list_running_apps()
+    };
+    match_app(&apps, identifier, |pid| {
+        window_titles(pid).is_ok_and(|titles| !titles.is_empty())
+    })
+}
+
+/// Check a PID given as an app identifier up front, so a stale one fails here
+/// rather than with a confusing AX error once its menus are read. `running`
+/// tells whether an application (not just any process) has the PID.
+///
+/// # Errors
+///
+/// Returns `AXError::AppNotFound` if `pid` is not positive or `running`
+/// rejects it.
+pub fn running_pid(pid: i32, running: impl FnOnce(i32) -> bool) -> Result<i32, AXError> {
+    if pid > 0 && running(pid) {
+        Ok(pid)
+    } else {
+        Err(AXError::AppNotFound {
+            identifier: format!("pid {pid}"),
+        })
+    }
+}
+
+/// Whether `identifier` is a bundle ID: it has a `.` and is not a `glob:` or
+/// `re:` pattern, or a path.
+#[must_use]
+pub fn is_bundle_id(identifier: &str) -> bool {
+    identifier.contains('.')
+        && !identifier.contains('/')
+        && !identifier.starts_with("glob:")
+        && !identifier.starts_with("re:")
+}
+
+/// Whether `identifier` is the path of an app bundle, like
+/// `/Applications/Figma.app`.
+#[must_use]
+pub fn is_bundle_path(identifier: &str) -> bool {
+    identifier.contains('/')
+        && Path::new(identifier)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("app"))
+}
+
+/// Pick the one app in `apps` that `identifier` names. A bundle ID must match
+/// exactly. An `.app` path names the app running from that bundle only, not
+/// another copy with the same bundle ID, so `--launch` can start that one.
+/// `glob:PATTERN` (`*` and `?` wildcards, matching the whole string)
+/// and `re:REGEX` match an app's name or bundle ID, ignoring case. Anything
+/// else is a name, matched case-insensitively: exact names win over prefixes,
+/// which win over substrings, so `Note` picks "Notes" over "Notification
+/// Center".
+///
+/// Several instances of one app (all with the same name, or the same bundle
+/// ID) are narrowed to the frontmost one, else to the only one with open
+/// windows, as told by `has_windows`. Different apps that merely match equally
+/// well are always ambiguous.
+///
+/// # Errors
+///
+/// Returns `AXError::AppNotFound` if nothing matches,
+/// `AXError::AmbiguousApp` if several apps remain at the best tier, or
+/// `AXError::InvalidAppPattern` if a pattern does not compile.
+pub fn match_app(
+    apps: &[RunningApp],
+    identifier: &str,
+    has_windows: impl Fn(i32) -> bool,
+) -> Result<i32, AXError> {
+    let found: Vec<&RunningApp> = if let Some(pattern) = app_pattern(identifier) {
+        let pattern = pattern?;
+        apps.iter()
+            .filter(|a| {
+                pattern.is_match(&a.name)
+                    || a.bundle_id.as_deref().is_some_and(|b| pattern.is_match(b))
+            })
+            .collect()
+    } else if is_bundle_path(identifier) {
+        let path = std::fs::canonicalize(identifier).unwrap_or_else(|_| identifier.into());
+        apps.iter()
+            .filter(|a| a.bundle_path.as_deref() == Some(path.as_path()))
+            .collect()
+    } else if is_bundle_id(identifier) {
+        apps.iter()
+            .filter(|a| a.bundle_id.as_deref() == Some(identifier))
+            .collect()
+    } else {
+        let needle = identifier.to_lowercase();
+        let tiers: [&dyn Fn(&str) -> bool; 3] = [
+            &|name| name == needle,
+            &|name| name.starts_with(&needle),
+            &|name| name.contains(&needle),
+        ];
+        tiers
+            .iter()
+            .map(|matches| {
+                apps.iter()
+                    .filter(|a| matches(&a.name.to_lowercase()))
+                    .collect::<Vec<_>>()
+            })
+            .find(|found| !found.is_empty())
+            .unwrap_or_default()
+    };
+    match found.as_slice() {
+        [] => Err(AXError::AppNotFound {
+            identifier: identifier.to_owned(),
+        }),
+        [app] => Ok(app.pid),
+        several => break_tie(several, has_windows).ok_or_else(|| AXError::AmbiguousApp {
+            identifier: identifier.to_owned(),
+            candidates: several
+                .iter()
+                .map(|a| format!("{} (pid {})", a.name, a.pid))
+                .collect(),
+            pids: several.iter().map(|a| a.pid).collect(),
+        }),
+    }
+}
+
+/// The one of several instances of an app that is frontmost, or else the only
+/// one with windows. `None` if `apps` are not all the same app.
+fn break_tie(apps: &[&RunningApp], has_windows: impl Fn(i32) -> bool) -> Option<i32> {
+    let first = apps.first()?;
+    let same_name = apps
+        .iter()
+        .all(|a| a.name.eq_ignore_ascii_case(&first.name));
+    let same_bundle =
+        first.bundle_id.is_some() && apps.iter().all(|a| a.bundle_id == first.bundle_id);
+    if !same_name && !same_bundle {
+        return None;
+    }
+    if let Some(app) = apps.iter().find(|a| a.frontmost) {
+        debug!(pid = app.pid, name = %app.name, "picked the frontmost of several matching apps");
+        return Some(app.pid);
+    }
+    let with_windows: Vec<_> = apps.iter().filter(|a| has_windows(a.pid)).collect();
+    match with_windows.as_slice() {
+        [app] => {
+            debug!(pid = app.pid, name = %app.name, "picked the only matching app with windows");
+            Some(app.pid)
+        }
+        _ => None,
+    }
+}
+
+/// The case-insensitive regex behind a `glob:` or `re:` identifier, or `None`
+/// for a plain name or bundle ID.
+fn app_pattern(identifier: &str) -> Option<Result<Regex, AXError>> {
+    let compiled = if let Some(glob) = identifier.strip_prefix("glob:") {
+        pattern::glob(glob, false)
+    } else {
+        pattern::regex(identifier.strip_prefix("re:")?, false)
+    };
+    Some(compiled.map_err(|e| AXError::InvalidAppPattern {
+        pattern: identifier.to_owned(),
+        reason: e.to_string(),
+    }))
+}
+
+/// Get the PID of the frontmost (focused) application.
+///
+/// # Errors
+///
+/// Returns `Err(AXError::AppNotFound)` if no frontmost application can be determined.
+pub fn frontmost_app_pid() -> Result<i32, AXError> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    if let Some(app) = workspace.frontmostApplication() {
+        return Ok(app.processIdentifier());
+    }
+    Err(AXError::AppNotFound {
+        identifier: "<frontmost>".to_owned(),
+    })
+}
+
+/// Get info for all running applications.
+#[derive(Debug, Clone)]
+pub struct RunningApp {
+    pub name: String,
+    pub pid: i32,
+    pub bundle_id: Option<String>,
+    /// The app's bundle on disk, if it has one.
+    pub bundle_path: Option<PathBuf>,
+    pub frontmost: bool,
+    pub activation_policy: ActivationPolicy,
+    /// When the app was launched, in seconds since the Unix epoch; `None` for
+    /// processes not started through Launch Services.
+    pub launch_date: Option<u64>,
+}
+
+/// How an app presents itself (`NSApplicationActivationPolicy`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivationPolicy {
+    /// An ordinary app, with a Dock icon and a menu bar.
+    #[default]
+    Regular,
+    /// An agent (`LSUIElement`): no Dock icon, often just a status bar item.
+    Accessory,
+    /// A background-only process.
+    Prohibited,
+}
+
+/// List all running applications with GUI access.
+pub fn list_running_apps() -> Vec<RunningApp> {
+    running_apps(false)
+}
+
+/// Like [`list_running_apps`], but also background processes and agents
+/// without a localized name, named by bundle ID or executable instead.
+#[must_use]
+pub fn list_all_running_apps() -> Vec<RunningApp> {
+    running_apps(true)
+}
+
+/// Running apps sorted by name; those without a name only if `unnamed`.
+fn running_apps(unnamed: bool) -> Vec<RunningApp> {
+    let mut result = Vec::new();
+    let workspace = NSWorkspace::sharedWorkspace();
+    let apps = workspace.runningApplications();
+    let frontmost_pid = workspace
+        .frontmostApplication()
+        .map(|a| a.processIdentifier());
+
+    for app in apps.iter() {
+        let pid = app.processIdentifier();
+        let bundle_id = app.bundleIdentifier().map(|b| b.to_string());
+        let bundle_path = app
+            .bundleURL()
+            .and_then(|url| url.path())
+            .map(|p| PathBuf::from(p.to_string()));
+        let name = app
+            .localizedName()
+            .map(|n| n.to_string())
+            .filter(|n| !n.is_empty());
+        let name = match name {
+            Some(name) => name,
+            // Background agents have empty names.
+            None if unnamed => bundle_id
+                .clone()
+                .or_else(|| {
+                    let path = PathBuf::from(app.executableURL()?.path()?.to_string());
+                    Some(path.file_name()?.to_string_lossy().into_owned())
+                })
+                .unwrap_or_else(|| pid.to_string()),
+            None => continue,
+        };
+        let policy = app.activationPolicy();
+        let activation_policy = if policy == NSApplicationActivationPolicy::Accessory {
+            ActivationPolicy::Accessory
+        } else if policy == NSApplicationActivationPolicy::Prohibited {
+            ActivationPolicy::Prohibited
+        } else {
+            ActivationPolicy::Regular
+        };
+        let frontmost = frontmost_pid == Some(pid);
+        // Sub-second precision is noise for ordering apps by launch.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let launch_date = app
+            .launchDate()
+            .map(|date| date.timeIntervalSince1970().max(0.0) as u64);
+        result.push(RunningApp {
+            name,
+            pid,
+            bundle_id,
+            bundle_path,
+            frontmost,
+            activation_policy,
+            launch_date,
+        });
+    }
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+/// A stable key for the app behind `pid`: its bundle ID, or its name when it
+/// has none. Used to scope item IDs; empty if the process is gone.
+#[must_use]
+pub fn app_key(pid: i32) -> String {
+    NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+        .and_then(|app| app.bundleIdentifier().or_else(|| app.localizedName()))
+        .map(|s| s.to_string())
+        .unwrap_or_default()
+}
+
+/// Whether `pid` is still a running application.
+#[must_use]
+pub fn is_running(pid: i32) -> bool {
+    NSRunningApplication::runningApplicationWithProcessIdentifier(pid).is_some()
+}
+
+/// How often [`activate`] checks whether the app is ready.
+const ACTIVATE_POLL: Duration = Duration::from_millis(50);
+
+/// Bring the app behind `pid` to the front, then wait up to `timeout` for it
+/// to become frontmost with a populated menu bar. Apps that never get there
+/// are left to fail (or succeed) on their own when their menus are read.
+///
+/// # Errors
+///
+/// Returns `AXError::AppNotFound` if no application has that PID.
+pub fn activate(pid: i32, timeout: Duration) -> Result<(), AXError> {
+    let app =
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid).ok_or_else(|| {
+            AXError::AppNotFound {
+                identifier: pid.to_string(),
+            }
+        })?;
+    app.activateWithOptions(NSApplicationActivationOptions::ActivateAllWindows);
+
+    let element = AXElement::application(pid, DEFAULT_MESSAGING_TIMEOUT_SECS);
+    let deadline = Instant::now() + timeout;
+    loop {
+        let frontmost = frontmost_app_pid().ok() == Some(pid);
+        let has_menus = element
+            .menu_bar()
+            .and_then(|bar| bar.children())
+            .is_ok_and(|menus| !menus.is_empty());
+        if (frontmost && has_menus) || Instant::now() >= deadline {
+            return Ok(());
+        }
+        std::thread::sleep(ACTIVATE_POLL);
+    }
+}
+
+/// Launch the app `identifier` names (a bundle ID, an `.app` path, or a name
+/// as Launch Services knows it) and wait up to `timeout` for it to come up frontmost
+/// with a populated menu bar. Returns its PID.
+///
+/// # Errors
+///
+/// Returns `AXError::AppNotFound` if no installed app matches, or it is not
+/// running by the deadline.
+pub fn launch(identifier: &str, timeout: Duration) -> Result<i32, AXError> {
+    let not_found = || AXError::AppNotFound {
+        identifier: identifier.to_owned(),
+    };
+    let workspace = NSWorkspace::sharedWorkspace();
+    let launched = if is_bundle_id(identifier) {
+        workspace
+            .URLForApplicationWithBundleIdentifier(&NSString::from_str(identifier))
+            .is_some_and(|url| workspace.openURL(&url))
+    } else if is_bundle_path(identifier) {
+        Path::new(identifier).is_dir()
+            && workspace.openURL(&NSURL::fileURLWithPath(&NSString::from_str(identifier)))
+    } else {
+        launch_by_name(&workspace, identifier)
+    };
+    if !launched {
+        return Err(not_found());
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Ok(pid) = resolve_app_pid(identifier) {
+            activate(pid, deadline.saturating_duration_since(Instant::now()))?;
+            return Ok(pid);
+        }
+        if Instant::now() >= deadline {
+            return Err(not_found());
+        }
+        std::thread::sleep(ACTIVATE_POLL);
+    }
+}
+
+/// Ask Launch Services to start the app called `name`. Its replacement takes
+/// an app URL and a completion block; this one finds apps by name, which is
+/// what `--app` gives.
+#[allow(deprecated)]
+fn launch_by_name(workspace: &NSWorkspace, name: &str) -> bool {
+    workspace.launchApplication(&NSString::from_str(name))
+}
+
+/// Titles of the windows of the app behind `pid` (untitled ones as "").
+///
+/// # Errors
+///
+/// Returns `AXError` if the app's windows cannot be read.
+pub fn window_titles(pid: i32) -> Result<Vec<String>, AXError> {
+    let app = AXElement::application(pid, DEFAULT_MESSAGING_TIMEOUT_SECS);
+    Ok(app
+        .windows()?
+        .iter()
+        .map(|window| window.title().unwrap_or_default())
+        .collect())
+}
+
+/// The on-disk bundle of the app behind `pid`, if it has one.
+#[must_use]
+pub fn bundle_path(pid: i32) -> Option<PathBuf> {
+    NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+        .and_then(|app| app.bundleURL())
+        .and_then(|url| url.path())
+        .map(|p| PathBuf::from(p.to_string()))
+}
+
+/// The build version (`CFBundleVersion`, else `CFBundleShortVersionString`)
+/// from the Info.plist of the app behind `pid`.
+#[must_use]
+pub fn bundle_version(pid: i32) -> Option<String> {
+    let mut info = load_strings(&bundle_path(pid)?.join("Contents/Info.plist"));
+    info.remove("CFBundleVersion")
+        .or_else(|| info.remove("CFBundleShortVersionString"))
+}
+
+/// Resolve an optional `--app` flag to a PID.
+/// If `None`, returns the frontmost app PID.
+///
+/// # Errors
+///
+/// Returns `Err(AXError::AppNotFound)` if the app cannot be resolved.
+pub fn resolve_target(app: Option<&str>) -> Result<i32, AXError> {
+    match app {
+        Some(identifier) => resolve_app_pid(identifier),
+        None => frontmost_app_pid(),
+    }
+}
@@ -0,0 +1,678 @@
+/// Safe wrapper around AXUIElementRef with batch attribute fetching.
+use accessibility_sys::{
+    kAXChildrenAttribute, kAXEnabledAttribute, kAXErrorSuccess, kAXExtrasMenuBarAttribute,
+    kAXFocusedApplicationAttribute, kAXFocusedAttribute, kAXFocusedUIElementAttribute,
+    kAXHelpAttribute, kAXIdentifierAttribute, kAXMenuBarAttribute, kAXMenuItemCmdCharAttribute,
+    kAXMenuItemCmdGlyphAttribute, kAXMenuItemCmdModifiersAttribute,
+    kAXMenuItemCmdVirtualKeyAttribute, kAXMenuItemMarkCharAttribute,
+    kAXMenuItemPrimaryUIElementAttribute, kAXRoleAttribute, kAXSelectedAttribute,
+    kAXSubroleAttribute, kAXTitleAttribute, kAXValueTypeCFRange, kAXValueTypeCGPoint,
+    kAXValueTypeCGRect, kAXValueTypeCGSize, kAXVisibleChildrenAttribute, kAXWindowsAttribute,
+    AXUIElementCopyActionNames, AXUIElementCopyAttributeValue,
+    AXUIElementCopyMultipleAttributeValues, AXUIElementCreateApplication,
+    AXUIElementCreateSystemWide, AXUIElementGetPid, AXUIElementGetTypeID, AXUIElementPerformAction,
+    AXUIElementRef, AXUIElementSetMessagingTimeout, AXValueGetType, AXValueGetTypeID,
+    AXValueGetValue, AXValueRef,
+};
+use core_foundation::{
+    array::{CFArray, CFArrayRef},
+    base::{CFRange, CFType, CFTypeRef, TCFType},
+    boolean::CFBoolean,
+    string::{CFString, CFStringRef},
+};
+use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::instrument;
+
+use super::errors::{check_ax_error, AXError};
+
+/// Default timeout in seconds for AX API calls to unresponsive apps.
+pub const DEFAULT_MESSAGING_TIMEOUT_SECS: f32 = 1.0;
+
+/// Owned wrapper around an `AXUIElementRef`.
+///
+/// Automatically retains on clone and releases on drop via Core Foundation's
+/// reference counting (CFRetain / CFRelease are called by the `CFType` machinery).
+pub struct AXElement {
+    inner: CFType,
+}
+
+impl std::fmt::Debug for AXElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AXElement").finish_non_exhaustive()
+    }
+}
+
+impl Clone for AXElement {
+    fn clone(&self) -> Self {
+        // wrap_under_get_rule increments the retain count.
+        let inner = unsafe { CFType::wrap_under_get_rule(self.inner.as_CFTypeRef()) };
+        Self { inner }
+    }
+}
+
+// SAFETY: AXUIElementRef is thread-safe for read operations per Apple docs.
+unsafe impl Send for AXElement {}
+unsafe impl Sync for AXElement {}
+
+impl AXElement {
+    /// Wrap a raw `AXUIElementRef` (takes ownership; caller must not release separately).
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid, non-null `AXUIElementRef`.
+    pub unsafe fn from_raw(raw: AXUIElementRef) -> Self {
+        // SAFETY: caller guarantees `raw` is valid. `CFType::wrap_under_create_rule` takes
+        // ownership of the +1 retain count that AX API functions return.
+        let inner = unsafe { CFType::wrap_under_create_rule(raw as CFTypeRef) };
+        Self { inner }
+    }
+
+    /// Create an application-level element from a PID.
+    ///
+    /// Sets a messaging timeout of `timeout_secs` so the tool does not hang on
+    /// unresponsive apps (see [`DEFAULT_MESSAGING_TIMEOUT_SECS`]).
+    ///
+    /// # Errors
+    ///
+    /// Does not fail directly, but AX calls on the returned element will fail with
+    /// `AXError::Timeout` if the target app is unresponsive.
+    pub fn application(pid: i32, timeout_secs: f32) -> Self {
+        // SAFETY: `AXUIElementCreateApplication` returns a +1 retained ref. Always succeeds.
+        let raw = unsafe { AXUIElementCreateApplication(pid) };
+        // SAFETY: raw is always non-null.
+        let el = unsafe { Self::from_raw(raw) };
+        // Best-effort: set timeout. Ignore errors (element is valid regardless).
+        // SAFETY: FFI call with a valid element ref.
+        unsafe {
+            AXUIElementSetMessagingTimeout(el.as_raw(), timeout_secs);
+        }
+        el
+    }
+
+    /// Create the system-wide element, which reports the focused app and
+    /// element across all apps.
+    ///
+    /// Uses the same messaging timeout as [`application`](Self::application)
+    /// with [`DEFAULT_MESSAGING_TIMEOUT_SECS`].
+    #[must_use]
+    pub fn system_wide() -> Self {
+        // SAFETY: `AXUIElementCreateSystemWide` returns a +1 retained ref. Always succeeds.
+        let raw = unsafe { AXUIElementCreateSystemWide() };
+        // SAFETY: raw is always non-null.
+        let el = unsafe { Self::from_raw(raw) };
+        // SAFETY: FFI call with a valid element ref.
+        unsafe {
+            AXUIElementSetMessagingTimeout(el.as_raw(), DEFAULT_MESSAGING_TIMEOUT_SECS);
+        }
+        el
+    }
+
+    /// Return the underlying raw pointer (not retained; valid only as long as `self` is alive).
+    pub fn as_raw(&self) -> AXUIElementRef {
+        self.inner.as_CFTypeRef() as AXUIElementRef
+    }
+
+    /// Get the PID of the process that owns this element.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if the element is invalid.
+    #[allow(dead_code)]
+    pub fn pid(&self) -> Result<i32, AXError> {
+        let mut pid: i32 = 0;
+        // SAFETY: Safe FFI call. `pid` is a valid out-pointer.
+        let code = unsafe { AXUIElementGetPid(self.as_raw(), &mut pid) };
+        check_ax_error(code, "AXUIElementGetPid")?;
+        Ok(pid)
+    }
+
+    /// Get the menu bar element for an application element.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::AttributeUnsupported` if the app has no standard menu bar
+    /// (e.g., some Electron apps before they gain focus).
+    pub fn menu_bar(&self) -> Result<AXElement, AXError> {
+        self.copy_element_attribute(kAXMenuBarAttribute)
+    }
+
+    /// Get the extras (status bar / menu extras) menu bar for an application element.
+    ///
+    /// This is the right-side menu bar containing items like Wi-Fi, Bluetooth, etc.
+    /// Each app owns its own extras; iterate all running apps to find all status items.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::AttributeUnsupported` if the app has no extras menu bar.
+    pub fn extras_menu_bar(&self) -> Result<AXElement, AXError> {
+        self.copy_element_attribute(kAXExtrasMenuBarAttribute)
+    }
+
+    /// Copy a single attribute value as an `AXElement`.
+    fn copy_element_attribute(&self, attr: &'static str) -> Result<AXElement, AXError> {
+        let attr_cf = CFString::from_static_string(attr);
+        let mut value: CFTypeRef = std::ptr::null();
+        let code = unsafe {
+            AXUIElementCopyAttributeValue(self.as_raw(), attr_cf.as_concrete_TypeRef(), &mut value)
+        };
+        check_ax_error(code, attr)?;
+        if value.is_null() {
+            return Err(AXError::AttributeUnsupported(attr.to_owned()));
+        }
+        // SAFETY: value is a valid AXUIElementRef when the attribute is an element type.
+        Ok(unsafe { AXElement::from_raw(value as AXUIElementRef) })
+    }
+
+    /// The role, subrole, title, and identifier of this element, each `None`
+    /// where it has none or cannot be read.
+    #[must_use]
+    pub fn info(&self) -> ElementInfo {
+        let attrs = self
+            .batch_attributes(&[
+                kAXRoleAttribute,
+                kAXSubroleAttribute,
+                kAXTitleAttribute,
+                kAXIdentifierAttribute,
+            ])
+            .unwrap_or_default();
+        let string = |i: usize| match attrs.get(i) {
+            Some(Some(AttributeValue::String(s))) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        };
+        ElementInfo {
+            role: string(0),
+            subrole: string(1),
+            title: string(2),
+            identifier: string(3),
+        }
+    }
+
+    /// Get child elements (e.g., menu bar items or submenu items).
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if children cannot be fetched.
+    pub fn children(&self) -> Result<Vec<AXElement>, AXError> {
+        self.copy_array_attribute(kAXChildrenAttribute)
+    }
+
+    /// Get visible child elements (respects system hiding by Bartender/Ice).
+    ///
+    /// For extras menu bars, use this instead of `children()` to only get items
+    /// the user can actually see (not hidden by menu bar managers).
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if visible children cannot be fetched.
+    pub fn visible_children(&self) -> Result<Vec<AXElement>, AXError> {
+        self.copy_array_attribute(kAXVisibleChildrenAttribute)
+    }
+
+    /// Get an application element's windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if the windows cannot be fetched.
+    pub fn windows(&self) -> Result<Vec<AXElement>, AXError> {
+        self.copy_array_attribute(kAXWindowsAttribute)
+    }
+
+    /// The element's title, if it has one.
+    #[must_use]
+    pub fn title(&self) -> Option<String> {
+        match self.attribute(kAXTitleAttribute) {
+            Ok(Some(AttributeValue::String(title))) => Some(title),
+            _ => None,
+        }
+    }
+
+    /// Copy an array attribute as a `Vec<AXElement>`.
+    fn copy_array_attribute(&self, attr: &'static str) -> Result<Vec<AXElement>, AXError> {
+        let attr_cf = CFString::from_static_string(attr);
+        let mut value: CFTypeRef = std::ptr::null();
+        let code = unsafe {
+            AXUIElementCopyAttributeValue(self.as_raw(), attr_cf.as_concrete_TypeRef(), &mut value)
+        };
+        check_ax_error(code, attr)?;
+        if value.is_null() {
+            return Ok(Vec::new());
+        }
+        // SAFETY: AX children attribute always returns a CFArrayRef of AXUIElementRefs.
+        let array = unsafe { CFArray::<CFType>::wrap_under_create_rule(value as CFArrayRef) };
+        let mut result = Vec::with_capacity(array.len() as usize);
+        for item in array.iter() {
+            // Each item in the array is an AXUIElementRef (a CFTypeRef).
+            let raw = item.as_CFTypeRef() as AXUIElementRef;
+            // from_raw_retained calls wrap_under_get_rule, adding a retain so the element
+            // stays alive beyond the array's lifetime.
+            let el = unsafe { AXElement::from_raw_retained(raw) };
+            result.push(el);
+        }
+        Ok(result)
+    }
+
+    /// Wrap a raw `AXUIElementRef`, adding a retain (for array elements we don't own outright).
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid, non-null `AXUIElementRef`.
+    unsafe fn from_raw_retained(raw: AXUIElementRef) -> Self {
+        // wrap_under_get_rule increments the reference count.
+        let inner = unsafe { CFType::wrap_under_get_rule(raw as CFTypeRef) };
+        Self { inner }
+    }
+
+    /// Perform an action on this element (e.g., `kAXPressAction`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::ActionUnsupported` if the action is not available,
+    /// or `AXError::InvalidElement` if the element is stale.
+    #[instrument(level = "trace", skip(self))]
+    pub fn perform_action(&self, action: &str) -> Result<(), AXError> {
+        let action_cf = CFString::new(action);
+        let code =
+            unsafe { AXUIElementPerformAction(self.as_raw(), action_cf.as_concrete_TypeRef()) };
+        check_ax_error(code, action)
+    }
+
+    /// The names of the actions this element supports (e.g. `AXPress`,
+    /// `AXShowMenu`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if the actions cannot be fetched, e.g. the element
+    /// is stale.
+    pub fn action_names(&self) -> Result<Vec<String>, AXError> {
+        let mut names: CFArrayRef = std::ptr::null();
+        let code = unsafe { AXUIElementCopyActionNames(self.as_raw(), &mut names) };
+        check_ax_error(code, "AXUIElementCopyActionNames")?;
+        if names.is_null() {
+            return Ok(Vec::new());
+        }
+        // SAFETY: the action names come back as an owned CFArrayRef of CFStrings.
+        let array = unsafe { CFArray::<CFString>::wrap_under_create_rule(names) };
+        Ok(array.iter().map(|name| name.to_string()).collect())
+    }
+
+    /// Batch-fetch multiple attributes in a single IPC round-trip.
+    ///
+    /// Returns a parallel vec of `Option<AttributeValue>` — `None` if an attribute
+    /// is not supported or has no value for this element.
+    ///
+    /// This is the primary performance optimization: instead of N sequential IPC calls,
+    /// one call fetches all needed attributes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` on API-level failure (not on per-attribute absence).
+    #[instrument(level = "trace", skip_all, fields(attrs = attrs.len()))]
+    pub fn batch_attributes(
+        &self,
+        attrs: &[&'static str],
+    ) -> Result<Vec<Option<AttributeValue>>, AXError> {
+        // Build a CFArray of CFString attribute names.
+        let cf_attrs: Vec<CFString> = attrs
+            .iter()
+            .map(|&a| CFString::from_static_string(a))
+            .collect();
+
+        let cf_refs: Vec<*const core_foundation::string::__CFString> =
+            cf_attrs.iter().map(|s| s.as_concrete_TypeRef()).collect();
+
+        // SAFETY: CFArray::from_copyable creates a CFArray retaining each element.
+        let attr_array = CFArray::from_copyable(&cf_refs);
+
+        let mut out_array: CFArrayRef = std::ptr::null();
+        let code = unsafe {
+            AXUIElementCopyMultipleAttributeValues(
+                self.as_raw(),
+                attr_array.as_concrete_TypeRef(),
+                0u32, // options: 0 = don't stop on error
+                &mut out_array,
+            )
+        };
+
+        // A non-success top-level code means the element itself is bad.
+        if code != kAXErrorSuccess {
+            check_ax_error(code, "AXUIElementCopyMultipleAttributeValues")?;
+        }
+
+        if out_array.is_null() {
+            // Return all None
+            return Ok(vec![None; attrs.len()]);
+        }
+
+        // SAFETY: out is a CFArrayRef of results, one per attribute.
+        let result_array = unsafe { CFArray::<CFType>::wrap_under_create_rule(out_array) };
+
+        let mut values = Vec::with_capacity(attrs.len());
+        for item in result_array.iter() {
+            let type_id = item.type_of();
+            // AXValue errors come back as CFNumbers with the error code; we treat them as None.
+            // Real values are CFString, CFBoolean, CFNumber, or AXUIElementRef (CFType).
+            // We use type_of to distinguish.
+            let parsed = parse_cf_type(&item, type_id);
+            values.push(parsed);
+        }
+
+        Ok(values)
+    }
+
+    /// Fetch one attribute in its own IPC round-trip — the slow path
+    /// [`batch_attributes`](Self::batch_attributes) replaces. Kept for
+    /// benchmarking the two against each other, and for reading attributes
+    /// outside [`MENU_ITEM_ATTRS`] by name.
+    ///
+    /// Returns `None` if the attribute is not supported or has no value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` on any other failure.
+    #[instrument(level = "trace", skip(self))]
+    pub fn attribute(&self, attr: &str) -> Result<Option<AttributeValue>, AXError> {
+        use accessibility_sys::{kAXErrorAttributeUnsupported, kAXErrorNoValue};
+
+        let attr_cf = CFString::new(attr);
+        let mut value: CFTypeRef = std::ptr::null();
+        // SAFETY: FFI call with a valid element ref, an attribute name that
+        // outlives the call, and `value` as the out-pointer for the copy.
+        let code = unsafe {
+            AXUIElementCopyAttributeValue(
+                self.as_raw(),
+                attr_cf.as_concrete_TypeRef(),
+                &raw mut value,
+            )
+        };
+        if code == kAXErrorAttributeUnsupported || code == kAXErrorNoValue {
+            return Ok(None);
+        }
+        check_ax_error(code, attr)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        // SAFETY: a successful copy returns a +1 CFTypeRef we now own.
+        let value = unsafe { CFType::wrap_under_create_rule(value) };
+        Ok(parse_cf_type(&value, value.type_of()))
+    }
+}
+
+/// The names of a UI element, for reporting it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementInfo {
+    /// AX role, e.g. `AXTextArea`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// AX subrole, e.g. `AXSearchField`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subrole: Option<String>,
+    /// Title, where the element has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Developer-assigned identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+}
+
+/// What has keyboard focus system-wide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemFocus {
+    /// PID of the focused app.
+    pub pid: i32,
+    /// The focused element within it, if the app reports one.
+    pub element: Option<ElementInfo>,
+}
+
+/// Ask the system-wide element for the focused app and its focused element.
+///
+/// # Errors
+///
+/// Returns `AXError` if no app has focus (e.g. the login window) or the
+/// focused app cannot be read.
+pub fn system_focus() -> Result<SystemFocus, AXError> {
+    let system = AXElement::system_wide();
+    let app = system.copy_element_attribute(kAXFocusedApplicationAttribute)?;
+    let element = system
+        .copy_element_attribute(kAXFocusedUIElementAttribute)
+        .ok()
+        .map(|element| element.info());
+    Ok(SystemFocus {
+        pid: app.pid()?,
+        element,
+    })
+}
+
+/// A parsed attribute value from the AX API.
+#[derive(Debug, Clone)]
+pub enum AttributeValue {
+    /// String attribute (e.g., title, role).
+    String(String),
+    /// Boolean attribute (e.g., enabled).
+    Bool(bool),
+    /// Number attribute (e.g., modifier mask).
+    Number(i64),
+    /// Number attribute with a fractional part.
+    Float(f64),
+    /// `AXValue` point (e.g., `AXPosition`).
+    Point { x: f64, y: f64 },
+    /// `AXValue` size (e.g., `AXSize`).
+    Size { width: f64, height: f64 },
+    /// `AXValue` rectangle (e.g., `AXFrame`).
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    /// `AXValue` range (e.g., `AXSelectedTextRange`).
+    Range { location: i64, length: i64 },
+    /// A single element (e.g., `AXParent`).
+    Element(AXElement),
+    /// Child elements (from array attributes like `kAXChildrenAttribute`).
+    #[allow(dead_code)]
+    Elements(Vec<AXElement>),
+    /// An array holding anything other than elements.
+    Array(Vec<AttributeValue>),
+}
+
+impl AttributeValue {
+    /// The value as JSON. Points, sizes, rectangles, and ranges become objects
+    /// with their field names; elements are described by their role and title,
+    /// which takes another round-trip each.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::String(s) => json!(s),
+            Self::Bool(b) => json!(b),
+            Self::Number(n) => json!(n),
+            Self::Float(f) => json!(f),
+            Self::Point { x, y } => json!({ "x": x, "y": y }),
+            Self::Size { width, height } => json!({ "width": width, "height": height }),
+            Self::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => json!({ "x": x, "y": y, "width": width, "height": height }),
+            Self::Range { location, length } => json!({ "location": location, "length": length }),
+            Self::Element(element) => describe_element(element),
+            Self::Elements(elements) => elements.iter().map(describe_element).collect(),
+            Self::Array(values) => values.iter().map(Self::to_json).collect(),
+        }
+    }
+}
+
+/// An element as `{"role": …, "title": …}`, `null` where unreadable.
+fn describe_element(element: &AXElement) -> Value {
+    let attrs = element
+        .batch_attributes(&[kAXRoleAttribute, kAXTitleAttribute])
+        .unwrap_or_default();
+    let string = |i: usize| match attrs.get(i) {
+        Some(Some(AttributeValue::String(s))) => json!(s),
+        _ => Value::Null,
+    };
+    json!({ "role": string(0), "title": string(1) })
+}
+
+/// Parse a `CFType` into an `AttributeValue`.
+fn parse_cf_type(
+    value: &CFType,
+    type_id: core_foundation::base::CFTypeID,
+) -> Option<AttributeValue> {
+    use core_foundation::base::TCFType;
+
+    // CFString type
+    if type_id == CFString::type_id() {
+        // SAFETY: We verified the type_id matches CFString.
+        let s = unsafe { CFString::wrap_under_get_rule(value.as_CFTypeRef() as CFStringRef) };
+        return Some(AttributeValue::String(s.to_string()));
+    }
+
+    // CFBoolean type
+    if type_id == CFBoolean::type_id() {
+        // SAFETY: Verified type_id.
+        let b = unsafe { CFBoolean::wrap_under_get_rule(value.as_CFTypeRef() as *const _) };
+        return Some(AttributeValue::Bool(b.into()));
+    }
+
+    // CFNumber type (modifier mask, etc.)
+    if type_id == core_foundation::number::CFNumber::type_id() {
+        use core_foundation::number::CFNumber;
+        let n = unsafe { CFNumber::wrap_under_get_rule(value.as_CFTypeRef() as *const _) };
+        if let Some(v) = n.to_i64() {
+            return Some(AttributeValue::Number(v));
+        }
+        return n.to_f64().map(AttributeValue::Float);
+    }
+
+    // AXUIElement type (parent, top-level UI element, etc.)
+    if type_id == unsafe { AXUIElementGetTypeID() } {
+        // SAFETY: Verified type_id.
+        let el = unsafe { AXElement::from_raw_retained(value.as_CFTypeRef() as AXUIElementRef) };
+        return Some(AttributeValue::Element(el));
+    }
+
+    // AXValue type (points, sizes, rectangles, ranges)
+    if type_id == unsafe { AXValueGetTypeID() } {
+        return parse_ax_value(value.as_CFTypeRef() as AXValueRef);
+    }
+
+    // CFArray type (children)
+    if type_id == CFArray::<CFType>::type_id() {
+        let array =
+            unsafe { CFArray::<CFType>::wrap_under_get_rule(value.as_CFTypeRef() as CFArrayRef) };
+        let element_type = unsafe { AXUIElementGetTypeID() };
+        if array.iter().all(|item| item.type_of() == element_type) {
+            let mut elements = Vec::with_capacity(array.len() as usize);
+            for item in array.iter() {
+                let raw = item.as_CFTypeRef() as AXUIElementRef;
+                // SAFETY: Verified every item is an AXUIElementRef.
+                let el = unsafe { AXElement::from_raw_retained(raw) };
+                elements.push(el);
+            }
+            return Some(AttributeValue::Elements(elements));
+        }
+        let values = array
+            .iter()
+            .filter_map(|item| parse_cf_type(&item, item.type_of()))
+            .collect();
+        return Some(AttributeValue::Array(values));
+    }
+
+    // Unknown or error type (AX puts kAXError values as CFNumber — treated as None above).
+    None
+}
+
+/// Unpack the struct inside an `AXValue`; `None` for errors and unknown types.
+// The `kAXValueType*` patterns are Apple's constant names.
+#[allow(non_upper_case_globals)]
+fn parse_ax_value(value: AXValueRef) -> Option<AttributeValue> {
+    // SAFETY: `value` is a live AXValue, and each out-parameter has the layout
+    // of the type it is read as.
+    unsafe {
+        match AXValueGetType(value) {
+            kAXValueTypeCGPoint => {
+                let mut p = CGPoint::new(0.0, 0.0);
+                AXValueGetValue(value, kAXValueTypeCGPoint, (&raw mut p).cast())
+                    .then_some(AttributeValue::Point { x: p.x, y: p.y })
+            }
+            kAXValueTypeCGSize => {
+                let mut s = CGSize::new(0.0, 0.0);
+                AXValueGetValue(value, kAXValueTypeCGSize, (&raw mut s).cast()).then_some(
+                    AttributeValue::Size {
+                        width: s.width,
+                        height: s.height,
+                    },
+                )
+            }
+            kAXValueTypeCGRect => {
+                let mut r = CGRect::new(&CGPoint::new(0.0, 0.0), &CGSize::new(0.0, 0.0));
+                AXValueGetValue(value, kAXValueTypeCGRect, (&raw mut r).cast()).then_some(
+                    AttributeValue::Rect {
+                        x: r.origin.x,
+                        y: r.origin.y,
+                        width: r.size.width,
+                        height: r.size.height,
+                    },
+                )
+            }
+            kAXValueTypeCFRange => {
+                let mut r = CFRange::init(0, 0);
+                AXValueGetValue(value, kAXValueTypeCFRange, (&raw mut r).cast()).then_some(
+                    AttributeValue::Range {
+                        location: r.location as i64,
+                        length: r.length as i64,
+                    },
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The standard set of attributes to fetch for each menu item in one batch call.
+/// Order matters: results are indexed positionally.
+pub const MENU_ITEM_ATTRS: &[&str] = &[
+    kAXTitleAttribute,
+    kAXEnabledAttribute,
+    kAXMenuItemMarkCharAttribute,
+    kAXMenuItemCmdCharAttribute,
+    kAXMenuItemCmdModifiersAttribute,
+    kAXRoleAttribute,
+    kAXChildrenAttribute,
+    kAXMenuItemPrimaryUIElementAttribute,
+    kAXHelpAttribute,
+    kAXIdentifierAttribute,
+    kAXMenuItemCmdGlyphAttribute,
+    kAXMenuItemCmdVirtualKeyAttribute,
+    kAXSelectedAttribute,
+    kAXFocusedAttribute,
+];
+
+/// Indices into `MENU_ITEM_ATTRS`.
+pub mod attr_idx {
+    pub const TITLE: usize = 0;
+    pub const ENABLED: usize = 1;
+    pub const MARK_CHAR: usize = 2;
+    pub const CMD_CHAR: usize = 3;
+    pub const CMD_MODIFIERS: usize = 4;
+    pub const ROLE: usize = 5;
+    #[allow(dead_code)]
+    pub const CHILDREN: usize = 6;
+    /// Non-None when this item is an alternate of another item.
+    pub const PRIMARY_UI_ELEMENT: usize = 7;
+    /// Tooltip / help text.
+    pub const HELP: usize = 8;
+    /// Developer-assigned identifier; unlike the title, not localized.
+    pub const IDENTIFIER: usize = 9;
+    /// Glyph code standing in for the key of shortcuts on special keys.
+    pub const CMD_GLYPH: usize = 10;
+    /// Virtual key code (`kVK_*`) of the shortcut's key.
+    pub const CMD_VIRTUAL_KEY: usize = 11;
+    /// Whether the item is highlighted (the open menu's selection).
+    pub const SELECTED: usize = 12;
+    /// Whether the item has keyboard focus.
+    pub const FOCUSED: usize = 13;
+}
@@ -36,12 +36,36 @@ pub enum AXError {
         context: String,
     },
 
+    /// Synthesized key events could not be created (e.g. no window server).
+    #[error("Could not synthesize modifier key events")]
+    EventFailed,
+
     /// No running application matched the identifier provided.
     #[error("No running application matches '{identifier}'")]
     AppNotFound {
         /// The app name, PID string, or bundle ID that was searched.
         identifier: String,
     },
+
+    /// Several running applications matched the identifier equally well.
+    #[error("Several running applications match '{identifier}':\n  {}", candidates.join("\n  "))]
+    AmbiguousApp {
+        /// The app name or bundle ID that was searched.
+        identifier: String,
+        /// Name and PID of each matching app.
+        candidates: Vec<String>,
+        /// PID of each matching app, in the same order.
+        pids: Vec<i32>,
+    },
+
+    /// A `glob:` or `re:` app identifier is not a valid pattern.
+    #[error("Invalid app pattern '{pattern}': {reason}")]
+    InvalidAppPattern {
+        /// The identifier as given.
+        pattern: String,
+        /// Why it does not compile.
+        reason: String,
+    },
 }
 
 /// Map a raw `accessibility_sys` AX error code to our typed `AXError`.
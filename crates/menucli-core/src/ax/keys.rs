@@ -0,0 +1,136 @@
+/// Synthesized modifier keys, held down while a menu item is pressed.
+///
+/// Apps read the live modifier state when a press arrives, so pressing an
+/// Option-key alternate's element alone often runs the primary item. Posting
+/// key-down events first makes the app see the modifiers held, as if the user
+/// were pressing them while choosing the item.
+use std::str::FromStr;
+use std::time::Duration;
+
+use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
+use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+use serde::{Deserialize, Serialize};
+
+use super::errors::AXError;
+
+/// How long to let the window server register held keys before pressing.
+const SETTLE: Duration = Duration::from_millis(20);
+
+/// A modifier key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modifier {
+    /// ⌘
+    Command,
+    /// ⇧
+    Shift,
+    /// ⌥
+    Option,
+    /// ⌃
+    Control,
+}
+
+impl Modifier {
+    /// Virtual key code of the left-hand key.
+    fn key_code(self) -> CGKeyCode {
+        match self {
+            Self::Command => 0x37,
+            Self::Shift => 0x38,
+            Self::Option => 0x3A,
+            Self::Control => 0x3B,
+        }
+    }
+
+    fn flag(self) -> CGEventFlags {
+        match self {
+            Self::Command => CGEventFlags::CGEventFlagCommand,
+            Self::Shift => CGEventFlags::CGEventFlagShift,
+            Self::Option => CGEventFlags::CGEventFlagAlternate,
+            Self::Control => CGEventFlags::CGEventFlagControl,
+        }
+    }
+}
+
+impl FromStr for Modifier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "command" | "cmd" => Ok(Self::Command),
+            "shift" => Ok(Self::Shift),
+            "option" | "opt" | "alt" => Ok(Self::Option),
+            "control" | "ctrl" => Ok(Self::Control),
+            other => Err(format!(
+                "unknown modifier '{other}' (expected command, shift, option, or control)"
+            )),
+        }
+    }
+}
+
+/// Modifier keys held down until dropped, then released in reverse order.
+#[must_use = "the keys are released as soon as this is dropped"]
+pub struct HeldModifiers {
+    source: CGEventSource,
+    keys: Vec<Modifier>,
+}
+
+impl HeldModifiers {
+    /// Post key-down events for `keys`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::EventFailed` if the events cannot be created; any
+    /// keys already held are released first.
+    pub fn hold(keys: &[Modifier]) -> Result<Self, AXError> {
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|()| AXError::EventFailed)?;
+        let mut held = Self {
+            source,
+            keys: Vec::with_capacity(keys.len()),
+        };
+        for &key in keys {
+            held.keys.push(key);
+            if let Err(e) = held.post(key, true) {
+                held.keys.pop();
+                return Err(e);
+            }
+        }
+        std::thread::sleep(SETTLE);
+        Ok(held)
+    }
+
+    /// Post a key event for `key`, flagged with every key currently held.
+    fn post(&self, key: Modifier, down: bool) -> Result<(), AXError> {
+        let event = CGEvent::new_keyboard_event(self.source.clone(), key.key_code(), down)
+            .map_err(|()| AXError::EventFailed)?;
+        let flags = self
+            .keys
+            .iter()
+            .fold(CGEventFlags::empty(), |flags, k| flags | k.flag());
+        event.set_flags(flags);
+        event.post(CGEventTapLocation::HID);
+        Ok(())
+    }
+}
+
+impl Drop for HeldModifiers {
+    fn drop(&mut self) {
+        while let Some(key) = self.keys.pop() {
+            let _ = self.post(key, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_modifier() {
+        assert_eq!("option".parse::<Modifier>().unwrap(), Modifier::Option);
+        assert_eq!("Alt".parse::<Modifier>().unwrap(), Modifier::Option);
+        assert_eq!("cmd".parse::<Modifier>().unwrap(), Modifier::Command);
+        assert_eq!("ctrl".parse::<Modifier>().unwrap(), Modifier::Control);
+        assert!("hyper".parse::<Modifier>().is_err());
+    }
+}
@@ -0,0 +1,114 @@
+/// English titles for localized menus, read from the app bundle's `.strings` tables.
+///
+/// A localized app ships one `<lang>.lproj` directory per language, each holding
+/// `.strings` tables with the same keys. Pairing a key's value in every non-English
+/// table with its value in the English table yields a localized → English map, so
+/// "Ablage" can be matched as "File" without knowing which language is active.
+use std::collections::HashMap;
+use std::path::Path;
+
+use core_foundation::{
+    base::{CFType, TCFType},
+    data::CFData,
+    dictionary::CFDictionary,
+    propertylist::{create_with_data, kCFPropertyListImmutable, CFPropertyList},
+    string::CFString,
+};
+
+use super::app::bundle_path;
+
+/// Localization directories holding the English (development) strings, in order
+/// of preference.
+const ENGLISH_LPROJ: &[&str] = &["en.lproj", "English.lproj", "Base.lproj"];
+
+/// Map every localized string in the bundle of `pid` to its English original.
+///
+/// Empty when the app has no bundle, no English tables, or keeps its English
+/// titles only in compiled nibs (common for Base-internationalized apps).
+#[must_use]
+pub fn english_titles(pid: i32) -> HashMap<String, String> {
+    bundle_path(pid)
+        .map(|bundle| english_titles_in(&bundle.join("Contents/Resources")))
+        .unwrap_or_default()
+}
+
+fn english_titles_in(resources: &Path) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    let Some(english) = ENGLISH_LPROJ
+        .iter()
+        .map(|dir| resources.join(dir))
+        .find(|dir| dir.is_dir())
+    else {
+        return map;
+    };
+    let Ok(entries) = std::fs::read_dir(resources) else {
+        return map;
+    };
+    let localized_dirs = entries.flatten().map(|e| e.path()).filter(|dir| {
+        dir.extension().is_some_and(|ext| ext == "lproj")
+            && dir
+                .file_name()
+                .is_some_and(|name| !ENGLISH_LPROJ.iter().any(|en| name.eq_ignore_ascii_case(en)))
+    });
+    for dir in localized_dirs {
+        let Ok(tables) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for table in tables.flatten().map(|e| e.path()) {
+            if table.extension().is_none_or(|ext| ext != "strings") {
+                continue;
+            }
+            let Some(name) = table.file_name() else {
+                continue;
+            };
+            let english_table = load_strings(&english.join(name));
+            if english_table.is_empty() {
+                continue;
+            }
+            for (key, localized) in load_strings(&table) {
+                if let Some(original) = english_table.get(&key) {
+                    if *original != localized {
+                        map.entry(localized).or_insert_with(|| original.clone());
+                    }
+                }
+            }
+        }
+    }
+    map
+}
+
+/// Read a `.strings` table (text or binary plist) into key → value pairs.
+/// Also reads the string-valued keys of any other dictionary plist.
+pub(crate) fn load_strings(path: &Path) -> HashMap<String, String> {
+    let Ok(bytes) = std::fs::read(path) else {
+        return HashMap::new();
+    };
+    let Ok((raw, _format)) =
+        create_with_data(CFData::from_buffer(&bytes), kCFPropertyListImmutable)
+    else {
+        return HashMap::new();
+    };
+    // SAFETY: `create_with_data` returns a +1 retained, non-null property list.
+    let plist = unsafe { CFPropertyList::wrap_under_create_rule(raw) };
+    let Some(dict) = plist.downcast_into::<CFDictionary>() else {
+        return HashMap::new();
+    };
+    let (keys, values) = dict.get_keys_and_values();
+    keys.into_iter()
+        .zip(values)
+        .filter_map(|(k, v)| {
+            // SAFETY: dictionary keys and values are valid CF objects owned by `dict`;
+            // the get rule adds the retain our wrappers release.
+            let (k, v) = unsafe {
+                (
+                    CFType::wrap_under_get_rule(k),
+                    CFType::wrap_under_get_rule(v),
+                )
+            };
+            Some((
+                k.downcast::<CFString>()?.to_string(),
+                v.downcast::<CFString>()?.to_string(),
+            ))
+        })
+        .collect()
+}
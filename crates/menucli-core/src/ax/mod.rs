@@ -0,0 +1,20 @@
+/// Public API for the macOS Accessibility layer.
+pub mod app;
+pub mod element;
+pub mod errors;
+pub mod keys;
+pub mod locale;
+pub mod observer;
+pub mod permissions;
+
+pub use app::{list_all_running_apps, list_running_apps, resolve_target, ActivationPolicy};
+pub use element::{
+    attr_idx, system_focus, AXElement, AttributeValue, ElementInfo, SystemFocus,
+    DEFAULT_MESSAGING_TIMEOUT_SECS, MENU_ITEM_ATTRS,
+};
+pub use errors::AXError;
+pub use keys::{HeldModifiers, Modifier};
+pub use observer::Observer;
+pub use permissions::{
+    ensure_trusted, host_app_path, permission_instructions, ACCESSIBILITY_SETTINGS_URL,
+};
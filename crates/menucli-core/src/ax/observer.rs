@@ -0,0 +1,174 @@
+/// Wait for AX notifications on a single element via `AXObserver`.
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+use accessibility_sys::{
+    AXObserverAddNotification, AXObserverCreate, AXObserverGetRunLoopSource, AXObserverRef,
+    AXObserverRemoveNotification, AXUIElementRef,
+};
+use core_foundation::{
+    base::{CFType, CFTypeRef, TCFType},
+    runloop::{kCFRunLoopDefaultMode, CFRunLoop, CFRunLoopRunResult, CFRunLoopSource},
+    string::{CFString, CFStringRef},
+};
+
+use super::element::AXElement;
+use super::errors::{check_ax_error, AXError};
+
+/// An `AXObserver` registered for a fixed set of notifications on one element,
+/// delivering them on the current thread's run loop.
+///
+/// Registrations and the run loop source are removed on drop, so the observer
+/// must be created, waited on, and dropped on the same thread.
+pub struct Observer {
+    handle: CFType,
+    source: CFRunLoopSource,
+    element: AXElement,
+    notifications: &'static [&'static str],
+    /// Names of notifications delivered but not yet consumed, oldest first,
+    /// written by [`on_notification`]. Boxed so its address stays fixed while
+    /// registered as the refcon.
+    received: Box<RefCell<Vec<String>>>,
+}
+
+impl std::fmt::Debug for Observer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observer")
+            .field("notifications", &self.notifications)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Observer {
+    /// Observe `notifications` on `element`, which belongs to the app `pid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if the observer cannot be created or if the element
+    /// supports none of the notifications.
+    pub fn watch(
+        pid: i32,
+        element: &AXElement,
+        notifications: &'static [&'static str],
+    ) -> Result<Self, AXError> {
+        let mut raw: AXObserverRef = std::ptr::null_mut();
+        // SAFETY: `raw` is a valid out-pointer; the callback has the expected ABI.
+        let code = unsafe { AXObserverCreate(pid, on_notification, &raw mut raw) };
+        check_ax_error(code, "AXObserverCreate")?;
+        // SAFETY: `AXObserverCreate` succeeded, so `raw` is a +1 retained observer.
+        let handle = unsafe { CFType::wrap_under_create_rule(raw as CFTypeRef) };
+
+        let received = Box::new(RefCell::new(Vec::new()));
+        let refcon = std::ptr::from_ref(received.as_ref()).cast_mut().cast();
+        let mut registered = 0;
+        let mut last_err = None;
+        for name in notifications {
+            let name_cf = CFString::from_static_string(name);
+            // SAFETY: observer and element are valid; `refcon` outlives the
+            // registration because it is removed in `Drop` before `received` is freed.
+            let code = unsafe {
+                AXObserverAddNotification(
+                    raw,
+                    element.as_raw(),
+                    name_cf.as_concrete_TypeRef(),
+                    refcon,
+                )
+            };
+            match check_ax_error(code, name) {
+                Ok(()) => registered += 1,
+                Err(e) => last_err = Some(e),
+            }
+        }
+        if registered == 0 {
+            return Err(last_err.unwrap_or(AXError::InvalidElement));
+        }
+
+        // SAFETY: the observer is valid; the source is owned by it (get rule).
+        let source =
+            unsafe { CFRunLoopSource::wrap_under_get_rule(AXObserverGetRunLoopSource(raw)) };
+        // SAFETY: reading an immutable framework constant.
+        CFRunLoop::get_current().add_source(&source, unsafe { kCFRunLoopDefaultMode });
+
+        Ok(Self {
+            handle,
+            source,
+            element: element.clone(),
+            notifications,
+            received,
+        })
+    }
+
+    /// Run the current run loop until a notification arrives or `timeout`
+    /// elapses. Returns the notification name, or `None` on timeout.
+    #[must_use]
+    pub fn wait(&self, timeout: Duration) -> Option<String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            {
+                let mut received = self.received.borrow_mut();
+                if !received.is_empty() {
+                    return Some(received.remove(0));
+                }
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return None;
+            }
+            // SAFETY: reading an immutable framework constant.
+            CFRunLoop::run_in_mode(unsafe { kCFRunLoopDefaultMode }, remaining, true);
+        }
+    }
+
+    /// Deliver every notification queued since the last call, without
+    /// blocking, and return their names oldest first.
+    #[must_use]
+    pub fn drain(&self) -> Vec<String> {
+        // SAFETY: reading an immutable framework constant.
+        let mode = unsafe { kCFRunLoopDefaultMode };
+        while CFRunLoop::run_in_mode(mode, Duration::ZERO, true)
+            == CFRunLoopRunResult::HandledSource
+        {}
+        std::mem::take(&mut *self.received.borrow_mut())
+    }
+
+    fn as_raw(&self) -> AXObserverRef {
+        self.handle.as_CFTypeRef() as AXObserverRef
+    }
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        // SAFETY: reading an immutable framework constant.
+        CFRunLoop::get_current().remove_source(&self.source, unsafe { kCFRunLoopDefaultMode });
+        for name in self.notifications {
+            let name_cf = CFString::from_static_string(name);
+            // SAFETY: valid observer and element; unregistered names are ignored by AX.
+            unsafe {
+                AXObserverRemoveNotification(
+                    self.as_raw(),
+                    self.element.as_raw(),
+                    name_cf.as_concrete_TypeRef(),
+                );
+            }
+        }
+    }
+}
+
+/// `AXObserverCallback`: queue the notification name for [`Observer::wait`]
+/// and [`Observer::drain`].
+unsafe extern "C" fn on_notification(
+    _observer: AXObserverRef,
+    _element: AXUIElementRef,
+    notification: CFStringRef,
+    refcon: *mut std::ffi::c_void,
+) {
+    if refcon.is_null() || notification.is_null() {
+        return;
+    }
+    // SAFETY: `refcon` is the `RefCell` boxed by `Observer::watch`, alive until
+    // the registration is removed; callbacks run on the observer's thread.
+    let received = unsafe { &*refcon.cast::<RefCell<Vec<String>>>() };
+    // SAFETY: AX passes a valid CFString for the duration of the callback.
+    let name = unsafe { CFString::wrap_under_get_rule(notification) }.to_string();
+    received.borrow_mut().push(name);
+}
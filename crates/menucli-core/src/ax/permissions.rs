@@ -0,0 +1,59 @@
+/// Accessibility permission check helpers.
+use std::path::PathBuf;
+
+use accessibility_sys::AXIsProcessTrusted;
+
+use super::app::{bundle_path, resolve_app_pid};
+use super::errors::AXError;
+
+// One literal for both the constant and the instructions that quote it.
+macro_rules! settings_url {
+    () => {
+        "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+    };
+}
+
+/// URL that opens System Settings at Privacy & Security → Accessibility.
+pub const ACCESSIBILITY_SETTINGS_URL: &str = settings_url!();
+
+/// Check whether this process is trusted for Accessibility access.
+///
+/// Returns `Ok(())` if trusted, `Err(AXError::NotTrusted)` otherwise.
+///
+/// # Errors
+///
+/// Returns `Err(AXError::NotTrusted)` if Accessibility permission has not been granted.
+pub fn ensure_trusted() -> Result<(), AXError> {
+    // SAFETY: Safe C FFI call with no arguments. Returns a Boolean.
+    let trusted = unsafe { AXIsProcessTrusted() };
+    if trusted {
+        Ok(())
+    } else {
+        Err(AXError::NotTrusted)
+    }
+}
+
+/// Human-readable instructions for granting Accessibility permission.
+pub fn permission_instructions() -> &'static str {
+    concat!(
+        "To grant Accessibility permission:\n  \
+         1. Open System Settings → Privacy & Security → Accessibility\n  \
+         2. Click the + button and add your terminal application\n  \
+         3. Restart your terminal\n\n  \
+         Or run: open \"",
+        settings_url!(),
+        "\""
+    )
+}
+
+/// The bundle of the app menucli runs under (the terminal to add to the
+/// Accessibility list), if macOS says which one it is.
+///
+/// Apps pass their bundle ID to the processes they launch in
+/// `__CFBundleIdentifier`; a shell reached some other way (ssh, launchd)
+/// has no host app.
+#[must_use]
+pub fn host_app_path() -> Option<PathBuf> {
+    let bundle_id = std::env::var("__CFBundleIdentifier").ok()?;
+    bundle_path(resolve_app_pid(&bundle_id).ok()?)
+}
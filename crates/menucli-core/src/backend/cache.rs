@@ -0,0 +1,287 @@
+/// A backend decorator that keeps built menu trees on disk for a short while.
+///
+/// Walking a big app's menus costs hundreds of AX round-trips; scripts that run
+/// `search` or `list` against the same app every few seconds can reuse the last
+/// walk instead. Entries are keyed by the app's bundle ID and the tree options,
+/// and are only reused while they are younger than the TTL, were saved for the
+/// same PID (so a relaunch invalidates them), and match the app's version.
+///
+/// Cached nodes carry no AX element, so only read-only commands should use it.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use super::{AxBackend, MenuWatch};
+use crate::ax::app::RunningApp;
+use crate::ax::{AXError, Modifier, SystemFocus};
+use crate::menu::tree::{MenuFrame, TreeOptions};
+use crate::menu::{MenuError, MenuNode, ResolveOptions};
+
+/// Environment variable overriding the cache directory.
+pub const CACHE_DIR_ENV: &str = "MENUCLI_CACHE_DIR";
+
+/// The cache directory: `$MENUCLI_CACHE_DIR`, else `$XDG_CACHE_HOME/menucli`,
+/// else `~/.cache/menucli`.
+#[must_use]
+pub fn default_dir() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).filter(|v| !v.is_empty());
+    var(CACHE_DIR_ENV)
+        .map(PathBuf::from)
+        .or_else(|| var("XDG_CACHE_HOME").map(|d| PathBuf::from(d).join("menucli")))
+        .or_else(|| var("HOME").map(|h| PathBuf::from(h).join(".cache/menucli")))
+}
+
+/// One cached tree, as stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    pid: i32,
+    version: Option<String>,
+    /// Seconds since the Unix epoch when the tree was walked.
+    saved_at: u64,
+    nodes: Vec<MenuNode>,
+}
+
+/// Wraps another backend, serving `build_tree` / `build_extras_tree` from the
+/// disk cache when a fresh entry exists and saving every walk it does.
+pub struct CacheBackend<'a> {
+    inner: &'a dyn AxBackend,
+    dir: PathBuf,
+    ttl: Duration,
+    apps: OnceLock<Vec<RunningApp>>,
+}
+
+impl<'a> CacheBackend<'a> {
+    /// Cache `inner`'s trees in `dir`, reusing them for up to `ttl`.
+    #[must_use]
+    pub fn new(inner: &'a dyn AxBackend, dir: PathBuf, ttl: Duration) -> Self {
+        Self {
+            inner,
+            dir,
+            ttl,
+            apps: OnceLock::new(),
+        }
+    }
+
+    /// Where the tree of `pid` built with these options is stored.
+    fn entry_path(
+        &self,
+        pid: i32,
+        extras: bool,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> PathBuf {
+        let apps = self.apps.get_or_init(|| self.inner.list_apps());
+        let app = apps.iter().find(|a| a.pid == pid);
+        let name = app
+            .and_then(|a| a.bundle_id.clone())
+            .or_else(|| app.map(|a| a.name.clone()))
+            .unwrap_or_else(|| pid.to_string());
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        let mut hasher = DefaultHasher::new();
+        extras.hash(&mut hasher);
+        max_depth.hash(&mut hasher);
+        opts.include_alternates.hash(&mut hasher);
+        opts.locale_aliases.hash(&mut hasher);
+        opts.open_menus.hash(&mut hasher);
+        for menu in &opts.menus {
+            menu.to_lowercase().hash(&mut hasher);
+        }
+        // Kept apart from `menus` so `--menu A` and `--exclude-menus A` differ.
+        opts.menus.len().hash(&mut hasher);
+        for menu in &opts.exclude_menus {
+            menu.to_lowercase().hash(&mut hasher);
+        }
+        self.dir
+            .join(format!("{name}-{:016x}.json", hasher.finish()))
+    }
+
+    fn load(&self, path: &Path, pid: i32, version: Option<&str>) -> Option<Vec<MenuNode>> {
+        let entry: Entry = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+        let age = now_secs().saturating_sub(entry.saved_at);
+        (entry.pid == pid && entry.version.as_deref() == version && age < self.ttl.as_secs())
+            .then_some(entry.nodes)
+    }
+
+    /// Best-effort: a cache that cannot be written is simply not used.
+    fn store(&self, path: &Path, pid: i32, version: Option<String>, nodes: &[MenuNode]) {
+        let entry = Entry {
+            pid,
+            version,
+            saved_at: now_secs(),
+            nodes: nodes.to_vec(),
+        };
+        if let Ok(json) = serde_json::to_vec(&entry) {
+            let _ = std::fs::create_dir_all(&self.dir).and_then(|()| std::fs::write(path, json));
+        }
+    }
+
+    fn cached(
+        &self,
+        pid: i32,
+        extras: bool,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+        build: impl FnOnce() -> Result<Vec<MenuNode>, MenuError>,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        let path = self.entry_path(pid, extras, max_depth, opts);
+        let version = self.inner.app_version(pid);
+        if let Some(nodes) = self.load(&path, pid, version.as_deref()) {
+            debug!(path = %path.display(), "menu tree cache hit");
+            return Ok(nodes);
+        }
+        debug!(path = %path.display(), "menu tree cache miss");
+        let nodes = build()?;
+        // Never save a walk cut short by Ctrl-C.
+        if !opts.cancel.is_cancelled() {
+            self.store(&path, pid, version, &nodes);
+        }
+        Ok(nodes)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl AxBackend for CacheBackend<'_> {
+    fn ensure_trusted(&self) -> Result<(), AXError> {
+        self.inner.ensure_trusted()
+    }
+
+    fn list_apps(&self) -> Vec<RunningApp> {
+        self.apps.get_or_init(|| self.inner.list_apps()).clone()
+    }
+
+    fn list_all_apps(&self) -> Vec<RunningApp> {
+        self.inner.list_all_apps()
+    }
+
+    fn resolve_target(&self, app: Option<&str>) -> Result<i32, AXError> {
+        self.inner.resolve_target(app)
+    }
+
+    fn focus(&self) -> Result<SystemFocus, AXError> {
+        self.inner.focus()
+    }
+
+    fn activate(&self, pid: i32, timeout: Duration) -> Result<(), AXError> {
+        self.inner.activate(pid, timeout)
+    }
+
+    fn launch(&self, app: &str, timeout: Duration) -> Result<i32, AXError> {
+        self.inner.launch(app, timeout)
+    }
+
+    fn window_titles(&self, pid: i32) -> Result<Vec<String>, AXError> {
+        self.inner.window_titles(pid)
+    }
+
+    fn app_version(&self, pid: i32) -> Option<String> {
+        self.inner.app_version(pid)
+    }
+
+    fn build_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.cached(pid, false, max_depth, opts, || {
+            self.inner.build_tree(pid, max_depth, opts)
+        })
+    }
+
+    fn build_extras_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.cached(pid, true, max_depth, opts, || {
+            self.inner.build_extras_tree(pid, max_depth, opts)
+        })
+    }
+
+    fn build_branch(
+        &self,
+        pid: i32,
+        path: &str,
+        resolve: &ResolveOptions,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.inner.build_branch(pid, path, resolve, opts)
+    }
+
+    fn press(&self, pid: i32, node: &MenuNode) -> Result<(), MenuError> {
+        self.inner.press(pid, node)
+    }
+
+    fn press_with(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        modifiers: &[Modifier],
+    ) -> Result<(), MenuError> {
+        self.inner.press_with(pid, node, modifiers)
+    }
+
+    fn actions(&self, pid: i32, node: &MenuNode) -> Result<Vec<String>, MenuError> {
+        self.inner.actions(pid, node)
+    }
+
+    fn perform_action(&self, pid: i32, node: &MenuNode, action: &str) -> Result<(), MenuError> {
+        self.inner.perform_action(pid, node, action)
+    }
+
+    fn menu_frame(&self, pid: i32, node: &MenuNode) -> Result<Option<MenuFrame>, MenuError> {
+        self.inner.menu_frame(pid, node)
+    }
+
+    fn attribute(&self, pid: i32, node: &MenuNode, attr: &str) -> Result<Value, MenuError> {
+        self.inner.attribute(pid, node, attr)
+    }
+
+    fn read_checked(&self, pid: i32, node: &MenuNode) -> Result<bool, MenuError> {
+        self.inner.read_checked(pid, node)
+    }
+
+    fn press_and_wait(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        timeout: Duration,
+    ) -> Result<Option<bool>, MenuError> {
+        self.inner.press_and_wait(pid, node, timeout)
+    }
+
+    fn fetch_attributes(
+        &self,
+        pid: i32,
+        batch: bool,
+        opts: &TreeOptions,
+    ) -> Result<usize, MenuError> {
+        self.inner.fetch_attributes(pid, batch, opts)
+    }
+
+    fn watch_menus(&self, pid: i32) -> Option<Box<dyn MenuWatch>> {
+        self.inner.watch_menus(pid)
+    }
+}
@@ -0,0 +1,949 @@
+/// In-memory backend serving menus from a JSON fixture.
+///
+/// Fixture format (all fields except `title`, `name`, and `pid` are optional):
+///
+/// ```json
+/// {
+///   "apps": [{
+///     "name": "TextEdit", "pid": 101, "bundle_id": "com.apple.TextEdit", "frontmost": true,
+///     "menus": [
+///       {"title": "File", "children": [
+///         {"title": "Save As…", "shortcut": "⇧⌘S"},
+///         {"title": "Print…", "enabled": false}
+///       ]},
+///       {"title": "View", "children": [{"title": "Show Ruler", "checked": false}]}
+///     ],
+///     "extras": []
+///   }]
+/// }
+/// ```
+///
+/// Items with a `checked` value are checkmark items: pressing one flips it, so
+/// `toggle` round-trips exactly as it does against a live app.
+///
+/// [`FakeBackend::from_snapshot`] also accepts the output of `list --tree --json`
+/// (a bare array of tree nodes), served as a single frontmost app.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::AxBackend;
+use crate::ax::app::{
+    is_bundle_id, is_bundle_path, match_app, running_pid, ActivationPolicy, RunningApp,
+};
+use crate::ax::{AXError, ElementInfo, SystemFocus};
+use crate::menu::id::assign_ids;
+use crate::menu::tree::{
+    check_action, escape_title, mark_radio_group, node_field, CheckState, TreeOptions, PATH_SEP,
+};
+use crate::menu::{MenuError, MenuNode};
+use crate::types::MenuTreeOutput;
+
+/// PID reported for the single app behind a tree snapshot.
+pub const SNAPSHOT_PID: i32 = 0;
+
+/// Root of a fixture file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Running applications and their menus.
+    #[serde(default)]
+    pub apps: Vec<FixtureApp>,
+}
+
+/// One fake running application.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureApp {
+    /// Localized app name.
+    pub name: String,
+    /// Process ID.
+    pub pid: i32,
+    /// Bundle identifier.
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+    /// Path of the app bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_path: Option<PathBuf>,
+    /// Whether this is the frontmost app (the default target).
+    #[serde(default)]
+    pub frontmost: bool,
+    /// Regular app, agent, or background process.
+    #[serde(default)]
+    pub activation_policy: ActivationPolicy,
+    /// Launch time in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_date: Option<u64>,
+    /// Top-level menu bar items.
+    #[serde(default)]
+    pub menus: Vec<FixtureNode>,
+    /// Status bar (menu extras) items.
+    #[serde(default)]
+    pub extras: Vec<FixtureNode>,
+    /// Titles of the app's open windows.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<String>,
+    /// The element with keyboard focus, reported while the app is frontmost.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused_element: Option<ElementInfo>,
+    /// Whether the app is running; an installed app that is not only shows
+    /// up once launched.
+    #[serde(default = "running", skip_serializing_if = "is_running")]
+    pub running: bool,
+}
+
+fn running() -> bool {
+    true
+}
+
+// Takes a reference for `skip_serializing_if`.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_running(running: &bool) -> bool {
+    *running
+}
+
+/// One fake menu item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureNode {
+    /// Display title.
+    pub title: String,
+    /// Whether the item is enabled (default `true`).
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Checkmark state; `None` for items without a checkmark.
+    #[serde(default)]
+    pub checked: Option<bool>,
+    /// Shows a dash (mixed state) instead of the `checked` state, until pressed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mixed: bool,
+    /// Shows a bullet when checked: one choice of a radio group, so pressing
+    /// it unchecks the other radio items among its siblings.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub radio: bool,
+    /// Pre-formatted shortcut (e.g., "⇧⌘S").
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    /// Virtual key code of the shortcut's key.
+    #[serde(default)]
+    pub virtual_key: Option<u16>,
+    /// The shortcut's unformatted key character.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_char: Option<String>,
+    /// The shortcut's raw modifier mask.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_modifiers_mask: Option<u8>,
+    /// The glyph code of a shortcut key without a character.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_glyph: Option<u16>,
+    /// Whether the item is highlighted.
+    #[serde(default)]
+    pub selected: Option<bool>,
+    /// Whether the item has keyboard focus.
+    #[serde(default)]
+    pub focused: Option<bool>,
+    /// AX role; defaults to `AXMenuBarItem` at the top level, `AXMenuItem` below.
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Marks an Option-key alternate: the title of the primary item it replaces.
+    #[serde(default)]
+    pub alternate_of: Option<String>,
+    /// Help (tooltip) text.
+    #[serde(default)]
+    pub help: Option<String>,
+    /// AX actions the item supports; empty for the usual `AXPress` and
+    /// `AXCancel`, plus `AXShowMenu` for a submenu.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
+    /// Further AX attributes by name (`AXPosition`, …), as `attr` prints
+    /// them; the item's other fields answer the usual ones.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, Value>,
+    /// Locale-independent names (identifier, English title), served as
+    /// [`MenuNode::aliases`] when [`TreeOptions::locale_aliases`] is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Submenu items.
+    #[serde(default)]
+    pub children: Vec<FixtureNode>,
+    /// The submenu only lists its `children` while open, as apps filling
+    /// Open Recent or Services lazily do, so they are left out unless
+    /// [`TreeOptions::open_menus`] opens it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lazy: bool,
+    /// How many presses the item ignores before it reacts, as a busy app
+    /// that drops AX presses does.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub ignored_presses: u32,
+}
+
+// Takes a reference for `skip_serializing_if`.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(count: &u32) -> bool {
+    *count == 0
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl From<&MenuTreeOutput> for FixtureNode {
+    fn from(node: &MenuTreeOutput) -> Self {
+        Self {
+            title: node.title.clone(),
+            enabled: node.enabled,
+            // Snapshots only record the state, not whether the item has a checkmark.
+            checked: node.checked.then_some(true),
+            mixed: node.check_state == CheckState::Mixed,
+            radio: node.is_radio,
+            shortcut: node.shortcut.clone(),
+            virtual_key: node.virtual_key,
+            cmd_char: node.cmd_char.clone(),
+            cmd_modifiers_mask: node.cmd_modifiers_mask,
+            cmd_glyph: node.cmd_glyph,
+            selected: node.selected,
+            focused: node.focused,
+            role: Some(node.role.clone()),
+            alternate_of: node
+                .is_alternate
+                .then(|| node.alternate_of.clone().unwrap_or_default()),
+            help: node.help.clone(),
+            actions: Vec::new(),
+            attributes: BTreeMap::new(),
+            aliases: Vec::new(),
+            children: node.children.iter().map(Self::from).collect(),
+            lazy: false,
+            ignored_presses: 0,
+        }
+    }
+}
+
+/// Backend serving a [`Fixture`]. Presses mutate the in-memory copy only.
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    fixture: Mutex<Fixture>,
+    pressed: Mutex<Vec<String>>,
+}
+
+impl FakeBackend {
+    /// Wrap an already-parsed fixture.
+    #[must_use]
+    pub fn new(fixture: Fixture) -> Self {
+        Self {
+            fixture: Mutex::new(fixture),
+            pressed: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Parse a fixture from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::InvalidFixture` if the JSON does not match the format.
+    pub fn from_json(json: &str) -> Result<Self, MenuError> {
+        serde_json::from_str(json)
+            .map(Self::new)
+            .map_err(|e| MenuError::InvalidFixture {
+                path: "<inline>".to_owned(),
+                reason: e.to_string(),
+            })
+    }
+
+    /// Load a fixture file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::InvalidFixture` if the file cannot be read or parsed.
+    pub fn from_file(path: &Path) -> Result<Self, MenuError> {
+        let invalid = |reason: String| MenuError::InvalidFixture {
+            path: path.display().to_string(),
+            reason,
+        };
+        let json = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        serde_json::from_str(&json)
+            .map(Self::new)
+            .map_err(|e| invalid(e.to_string()))
+    }
+
+    /// Load a saved tree: either a fixture file or the JSON array printed by
+    /// `list --tree --json`, which is served as one frontmost app with PID
+    /// [`SNAPSHOT_PID`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::InvalidFixture` if the file cannot be read or parsed.
+    pub fn from_snapshot(path: &Path) -> Result<Self, MenuError> {
+        let invalid = |reason: String| MenuError::InvalidFixture {
+            path: path.display().to_string(),
+            reason,
+        };
+        let json = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        if !json.trim_start().starts_with('[') {
+            return serde_json::from_str(&json)
+                .map(Self::new)
+                .map_err(|e| invalid(e.to_string()));
+        }
+        let nodes: Vec<MenuTreeOutput> =
+            serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+        Ok(Self::new(Fixture {
+            apps: vec![FixtureApp {
+                name: "snapshot".to_owned(),
+                pid: SNAPSHOT_PID,
+                bundle_id: None,
+                bundle_path: None,
+                frontmost: true,
+                activation_policy: ActivationPolicy::Regular,
+                launch_date: None,
+                menus: nodes.iter().map(FixtureNode::from).collect(),
+                extras: Vec::new(),
+                windows: Vec::new(),
+                focused_element: None,
+                running: true,
+            }],
+        }))
+    }
+
+    /// The fixture's apps sorted by name; those without a name (named by
+    /// bundle ID or PID instead) only if `unnamed`.
+    fn apps(&self, unnamed: bool) -> Vec<RunningApp> {
+        let Ok(fixture) = self.fixture.lock() else {
+            return Vec::new();
+        };
+        let mut apps: Vec<RunningApp> = fixture
+            .apps
+            .iter()
+            .filter(|a| a.running && (unnamed || !a.name.is_empty()))
+            .map(|a| RunningApp {
+                name: if a.name.is_empty() {
+                    a.bundle_id.clone().unwrap_or_else(|| a.pid.to_string())
+                } else {
+                    a.name.clone()
+                },
+                pid: a.pid,
+                bundle_id: a.bundle_id.clone(),
+                bundle_path: a.bundle_path.clone(),
+                frontmost: a.frontmost,
+                activation_policy: a.activation_policy,
+                launch_date: a.launch_date,
+            })
+            .collect();
+        apps.sort_by(|a, b| a.name.cmp(&b.name));
+        apps
+    }
+
+    /// Paths of every item pressed so far, in order.
+    #[must_use]
+    pub fn pressed(&self) -> Vec<String> {
+        self.pressed.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    fn with_app<T>(&self, pid: i32, f: impl FnOnce(&mut FixtureApp) -> T) -> Result<T, MenuError> {
+        let mut fixture = self
+            .fixture
+            .lock()
+            .map_err(|_| MenuError::AX(AXError::InvalidElement))?;
+        fixture
+            .apps
+            .iter_mut()
+            .find(|a| a.pid == pid && a.running)
+            .map(f)
+            .ok_or_else(|| MenuError::AppNotFound {
+                identifier: pid.to_string(),
+            })
+    }
+}
+
+impl AxBackend for FakeBackend {
+    fn ensure_trusted(&self) -> Result<(), AXError> {
+        Ok(())
+    }
+
+    fn list_apps(&self) -> Vec<RunningApp> {
+        self.apps(false)
+    }
+
+    fn list_all_apps(&self) -> Vec<RunningApp> {
+        self.apps(true)
+    }
+
+    fn resolve_target(&self, app: Option<&str>) -> Result<i32, AXError> {
+        match app {
+            None => self
+                .list_apps()
+                .iter()
+                .find(|a| a.frontmost)
+                .map(|a| a.pid)
+                .ok_or_else(|| AXError::AppNotFound {
+                    identifier: "<frontmost>".to_owned(),
+                }),
+            Some(identifier) => {
+                if let Ok(pid) = identifier.parse::<i32>() {
+                    return running_pid(pid, |pid| {
+                        self.list_all_apps().iter().any(|a| a.pid == pid)
+                    });
+                }
+                // Bundle IDs and paths also find nameless agents, as on a live system.
+                let apps = self.apps(is_bundle_id(identifier) || is_bundle_path(identifier));
+                match_app(&apps, identifier, |pid| {
+                    self.window_titles(pid)
+                        .is_ok_and(|titles| !titles.is_empty())
+                })
+            }
+        }
+    }
+
+    fn focus(&self) -> Result<SystemFocus, AXError> {
+        let pid = self.resolve_target(None)?;
+        let element = self
+            .with_app(pid, |app| app.focused_element.clone())
+            .ok()
+            .flatten();
+        Ok(SystemFocus { pid, element })
+    }
+
+    fn launch(&self, app: &str, timeout: Duration) -> Result<i32, AXError> {
+        {
+            let mut fixture = self.fixture.lock().map_err(|_| AXError::InvalidElement)?;
+            let needle = app.to_lowercase();
+            let installed = fixture.apps.iter_mut().find(|a| {
+                !a.running
+                    && (a.name.to_lowercase() == needle
+                        || a.bundle_id.as_deref() == Some(app)
+                        || a.bundle_path.as_deref() == Some(Path::new(app)))
+            });
+            let Some(installed) = installed else {
+                return Err(AXError::AppNotFound {
+                    identifier: app.to_owned(),
+                });
+            };
+            installed.running = true;
+        }
+        let pid = self.resolve_target(Some(app))?;
+        self.activate(pid, timeout)?;
+        Ok(pid)
+    }
+
+    fn activate(&self, pid: i32, _timeout: Duration) -> Result<(), AXError> {
+        let mut fixture = self.fixture.lock().map_err(|_| AXError::InvalidElement)?;
+        if !fixture.apps.iter().any(|a| a.pid == pid && a.running) {
+            return Err(AXError::AppNotFound {
+                identifier: pid.to_string(),
+            });
+        }
+        for app in &mut fixture.apps {
+            app.frontmost = app.pid == pid;
+        }
+        Ok(())
+    }
+
+    fn window_titles(&self, pid: i32) -> Result<Vec<String>, AXError> {
+        self.with_app(pid, |app| app.windows.clone())
+            .map_err(|_| AXError::AppNotFound {
+                identifier: pid.to_string(),
+            })
+    }
+
+    fn build_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.with_app(pid, |app| {
+            let mut nodes = to_nodes(&app.menus, "", 1, max_depth, opts);
+            assign_ids(&mut nodes, app_key(app));
+            nodes
+        })
+    }
+
+    fn build_extras_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.with_app(pid, |app| {
+            let mut nodes = to_nodes(&app.extras, "", 1, max_depth, opts);
+            assign_ids(&mut nodes, app_key(app));
+            nodes
+        })
+    }
+
+    fn press(&self, pid: i32, node: &MenuNode) -> Result<(), MenuError> {
+        if !node.enabled {
+            return Err(MenuError::ItemDisabled {
+                path: node.path.clone(),
+            });
+        }
+        let found = self.with_app(pid, |app| {
+            let item = find_in(&mut app.menus, "", &node.path)
+                .or_else(|| find_in(&mut app.extras, "", &node.path));
+            match item {
+                Some((siblings, i)) if siblings[i].ignored_presses > 0 => {
+                    siblings[i].ignored_presses -= 1;
+                    true
+                }
+                Some((siblings, i)) if siblings[i].radio => {
+                    // Choosing a radio item deselects the rest of its group.
+                    for (j, sibling) in siblings.iter_mut().enumerate() {
+                        if sibling.radio {
+                            sibling.checked = Some(j == i);
+                        }
+                    }
+                    true
+                }
+                Some((siblings, i)) => {
+                    let item = &mut siblings[i];
+                    if item.mixed {
+                        // Like AppKit: pressing a mixed item turns it on.
+                        item.mixed = false;
+                        item.checked = Some(true);
+                    } else if let Some(checked) = item.checked.as_mut() {
+                        *checked = !*checked;
+                    }
+                    true
+                }
+                None => false,
+            }
+        })?;
+        if !found {
+            return Err(MenuError::AX(AXError::InvalidElement));
+        }
+        if let Ok(mut pressed) = self.pressed.lock() {
+            pressed.push(node.path.clone());
+        }
+        Ok(())
+    }
+
+    fn actions(&self, pid: i32, node: &MenuNode) -> Result<Vec<String>, MenuError> {
+        let actions = self.with_app(pid, |app| {
+            let (siblings, i) = find_in(&mut app.menus, "", &node.path)
+                .or_else(|| find_in(&mut app.extras, "", &node.path))?;
+            let item = &siblings[i];
+            if !item.actions.is_empty() {
+                return Some(item.actions.clone());
+            }
+            let mut actions = vec!["AXPress".to_owned(), "AXCancel".to_owned()];
+            if !item.children.is_empty() {
+                actions.push("AXShowMenu".to_owned());
+            }
+            Some(actions)
+        })?;
+        actions.ok_or(MenuError::AX(AXError::InvalidElement))
+    }
+
+    fn perform_action(&self, pid: i32, node: &MenuNode, action: &str) -> Result<(), MenuError> {
+        check_action(node, action, self.actions(pid, node)?)?;
+        if action == "AXPress" {
+            return self.press(pid, node);
+        }
+        // Other actions only open or close menus, which a fixture does not model.
+        Ok(())
+    }
+
+    fn attribute(&self, pid: i32, node: &MenuNode, attr: &str) -> Result<Value, MenuError> {
+        let value = self.with_app(pid, |app| {
+            let item = find_mut(&mut app.menus, "", &node.path)
+                .or_else(|| find_mut(&mut app.extras, "", &node.path))?;
+            Some(item.attributes.get(attr).cloned())
+        })?;
+        match value {
+            Some(Some(value)) => Ok(value),
+            Some(None) => Ok(node_field(node, attr)),
+            None => Err(MenuError::AX(AXError::InvalidElement)),
+        }
+    }
+
+    fn read_checked(&self, pid: i32, node: &MenuNode) -> Result<bool, MenuError> {
+        self.with_app(pid, |app| {
+            find_mut(&mut app.menus, "", &node.path)
+                .or_else(|| find_mut(&mut app.extras, "", &node.path))
+                .map(|item| item.mixed || item.checked.unwrap_or(false))
+        })?
+        .ok_or(MenuError::AX(AXError::InvalidElement))
+    }
+}
+
+fn join_path(parent_path: &str, title: &str) -> String {
+    let escaped = escape_title(title);
+    if parent_path.is_empty() {
+        escaped.into_owned()
+    } else {
+        format!("{parent_path}{PATH_SEP}{escaped}")
+    }
+}
+
+/// Convert fixture items to `MenuNode`s the same way the live tree walk would.
+fn to_nodes(
+    items: &[FixtureNode],
+    parent_path: &str,
+    depth: usize,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+) -> Vec<MenuNode> {
+    let mut nodes: Vec<MenuNode> = items
+        .iter()
+        .filter(|item| opts.include_alternates || item.alternate_of.is_none())
+        .enumerate()
+        .filter(|(_, item)| depth > 1 || opts.wants_menu(&item.title))
+        .map(|(i, item)| {
+            let path = join_path(parent_path, &item.title);
+            let listed = !item.lazy || opts.open_menus;
+            let children = if listed && max_depth.is_none_or(|max| depth < max) {
+                to_nodes(&item.children, &path, depth + 1, max_depth, opts)
+            } else {
+                Vec::new()
+            };
+            let default_role = if depth == 1 {
+                "AXMenuBarItem"
+            } else {
+                "AXMenuItem"
+            };
+            MenuNode {
+                title: item.title.clone(),
+                path,
+                enabled: item.enabled,
+                checked: item.mixed || item.checked.unwrap_or(false),
+                check_state: if item.mixed {
+                    CheckState::Mixed
+                } else {
+                    CheckState::from_checked(item.checked.unwrap_or(false))
+                },
+                // Named by `mark_radio_group` below.
+                radio_group: item.radio.then(String::new),
+                radio_bulleted: false,
+                shortcut: item.shortcut.clone(),
+                virtual_key: item.virtual_key,
+                cmd_char: item.cmd_char.clone(),
+                cmd_modifiers: item.cmd_modifiers_mask,
+                cmd_glyph: item.cmd_glyph,
+                selected: item.selected,
+                focused: item.focused,
+                role: item.role.clone().unwrap_or_else(|| default_role.to_owned()),
+                depth,
+                index: i + 1,
+                id: String::new(),
+                children,
+                element: None,
+                is_alternate: item.alternate_of.is_some(),
+                alternate_of: item.alternate_of.clone(),
+                help: item.help.clone(),
+                aliases: if opts.locale_aliases {
+                    item.aliases.clone()
+                } else {
+                    Vec::new()
+                },
+            }
+        })
+        .collect();
+    // Fixtures have no separators, so all siblings form one section.
+    if depth > 1 {
+        mark_radio_group(&mut nodes, &format!("{parent_path}#1"));
+    }
+    nodes
+}
+
+/// Same key as the live backend: bundle ID, falling back to the app name.
+fn app_key(app: &FixtureApp) -> &str {
+    app.bundle_id.as_deref().unwrap_or(&app.name)
+}
+
+/// Find the fixture item whose full path is `path`.
+fn find_mut<'a>(
+    items: &'a mut [FixtureNode],
+    parent_path: &str,
+    path: &str,
+) -> Option<&'a mut FixtureNode> {
+    find_in(items, parent_path, path).map(|(siblings, i)| &mut siblings[i])
+}
+
+/// Find the fixture item whose full path is `path`, as its siblings and its
+/// position among them.
+fn find_in<'a>(
+    items: &'a mut [FixtureNode],
+    parent_path: &str,
+    path: &str,
+) -> Option<(&'a mut [FixtureNode], usize)> {
+    if let Some(i) = items
+        .iter()
+        .position(|item| join_path(parent_path, &item.title) == path)
+    {
+        return Some((items, i));
+    }
+    for item in items.iter_mut() {
+        let item_path = join_path(parent_path, &item.title);
+        if path.starts_with(&item_path) {
+            if let Some(found) = find_in(&mut item.children, &item_path, path) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::resolve;
+
+    const FIXTURE: &str = r#"{
+        "apps": [{
+            "name": "TextEdit", "pid": 101, "bundle_id": "com.apple.TextEdit", "frontmost": true,
+            "menus": [
+                {"title": "File", "children": [
+                    {"title": "Save As…", "shortcut": "⇧⌘S"},
+                    {"title": "Print…", "enabled": false}
+                ]},
+                {"title": "View", "children": [
+                    {"title": "Show Ruler", "checked": false},
+                    {"title": "Hide Ruler", "alternate_of": "Show Ruler"}
+                ]}
+            ]
+        }]
+    }"#;
+
+    fn backend() -> FakeBackend {
+        FakeBackend::from_json(FIXTURE).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        let b = backend();
+        assert_eq!(b.resolve_target(None).unwrap(), 101);
+        assert_eq!(b.resolve_target(Some("textedit")).unwrap(), 101);
+        assert_eq!(b.resolve_target(Some("com.apple.TextEdit")).unwrap(), 101);
+        assert!(b.resolve_target(Some("Safari")).is_err());
+        assert_eq!(b.resolve_target(Some("101")).unwrap(), 101);
+        for pid in ["999", "0", "-1"] {
+            assert!(matches!(
+                b.resolve_target(Some(pid)),
+                Err(AXError::AppNotFound { identifier }) if identifier == format!("pid {pid}")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_resolve_target_prefers_closer_names() {
+        let b = FakeBackend::from_json(
+            r#"{"apps": [
+                {"name": "Notification Center", "pid": 1, "menus": []},
+                {"name": "Notes", "pid": 2, "menus": []},
+                {"name": "Stickies Notes", "pid": 3, "menus": []},
+                {"name": "Mail", "pid": 4, "menus": []},
+                {"name": "Mailplane", "pid": 5, "menus": []}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(b.resolve_target(Some("notes")).unwrap(), 2);
+        assert_eq!(b.resolve_target(Some("mail")).unwrap(), 4);
+        assert_eq!(b.resolve_target(Some("stick")).unwrap(), 3);
+        match b.resolve_target(Some("Note")) {
+            Err(AXError::AmbiguousApp { candidates, .. }) => {
+                assert_eq!(candidates, ["Notes (pid 2)", "Notification Center (pid 1)"]);
+            }
+            other => panic!("expected AmbiguousApp, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_target_breaks_ties() {
+        let fixture = |frontmost: bool| {
+            format!(
+                r#"{{"apps": [
+                    {{"name": "Google Chrome", "pid": 1, "menus": []}},
+                    {{"name": "Google Chrome", "pid": 2, "windows": ["Inbox"], "menus": []}},
+                    {{"name": "Google Chrome", "pid": 3, "frontmost": {frontmost}, "menus": []}}
+                ]}}"#
+            )
+        };
+        let b = FakeBackend::from_json(&fixture(true)).unwrap();
+        assert_eq!(b.resolve_target(Some("Google Chrome")).unwrap(), 3);
+        let b = FakeBackend::from_json(&fixture(false)).unwrap();
+        assert_eq!(b.resolve_target(Some("Google Chrome")).unwrap(), 2);
+
+        // Different apps matching equally well are not broken by focus.
+        let b = FakeBackend::from_json(
+            r#"{"apps": [
+                {"name": "Notes", "pid": 1, "frontmost": true, "menus": []},
+                {"name": "Notion", "pid": 2, "menus": []}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            b.resolve_target(Some("No")),
+            Err(AXError::AmbiguousApp { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_target_patterns() {
+        let b = FakeBackend::from_json(
+            r#"{"apps": [
+                {"name": "Blender 4.2", "pid": 1, "bundle_id": "org.blenderfoundation.blender", "menus": []},
+                {"name": "Adobe Photoshop 2024", "pid": 2, "bundle_id": "com.adobe.Photoshop", "menus": []},
+                {"name": "Adobe Illustrator", "pid": 3, "bundle_id": "com.adobe.illustrator", "menus": []}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(b.resolve_target(Some("glob:blender *")).unwrap(), 1);
+        assert_eq!(b.resolve_target(Some("glob:com.adobe.photo*")).unwrap(), 2);
+        assert_eq!(b.resolve_target(Some("re:^adobe ill")).unwrap(), 3);
+        assert!(matches!(
+            b.resolve_target(Some("glob:Adobe *")),
+            Err(AXError::AmbiguousApp { pids, .. }) if pids == [3, 2]
+        ));
+        // A glob matches the whole name.
+        assert!(b.resolve_target(Some("glob:blender")).is_err());
+        assert!(matches!(
+            b.resolve_target(Some("re:[")),
+            Err(AXError::InvalidAppPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_target_by_bundle_path() {
+        let b = FakeBackend::from_json(
+            r#"{"apps": [
+                {"name": "Figma", "pid": 1, "bundle_id": "com.figma.Desktop", "bundle_path": "/Applications/Figma.app", "menus": []},
+                {"name": "Figma", "pid": 2, "bundle_id": "com.figma.Desktop", "bundle_path": "/Users/me/Builds/Figma.app", "menus": []}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            b.resolve_target(Some("/Applications/Figma.app")).unwrap(),
+            1
+        );
+        assert_eq!(
+            b.resolve_target(Some("/Users/me/Builds/Figma.app/"))
+                .unwrap(),
+            2
+        );
+        assert!(matches!(
+            b.resolve_target(Some("/Applications/Sketch.app")),
+            Err(AXError::AppNotFound { .. })
+        ));
+        // Another copy of a running app is not running itself.
+        assert!(matches!(
+            b.resolve_target(Some("/Applications/Beta/Figma.app")),
+            Err(AXError::AppNotFound { .. })
+        ));
+        // Without a slash it is a bundle ID, not a path.
+        assert!(b.resolve_target(Some("Figma.app")).is_err());
+    }
+
+    #[test]
+    fn test_activate_moves_frontmost() {
+        let b = FakeBackend::from_json(
+            r#"{"apps": [
+                {"name": "TextEdit", "pid": 101, "frontmost": true, "menus": []},
+                {"name": "Finder", "pid": 202, "menus": []}
+            ]}"#,
+        )
+        .unwrap();
+        b.activate(202, Duration::ZERO).unwrap();
+        assert_eq!(b.resolve_target(None).unwrap(), 202);
+        assert!(b.activate(999, Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_build_tree_paths_and_alternates() {
+        let b = backend();
+        let tree = b.build_tree(101, None, &TreeOptions::default()).unwrap();
+        assert_eq!(tree[0].children[0].path, "File::Save As…");
+        assert_eq!(tree[1].children.len(), 1);
+
+        let opts = TreeOptions {
+            include_alternates: true,
+            ..TreeOptions::default()
+        };
+        let tree = b.build_tree(101, None, &opts).unwrap();
+        assert!(tree[1].children[1].is_alternate);
+    }
+
+    #[test]
+    fn test_build_tree_menu_filter() {
+        let opts = TreeOptions {
+            menus: vec!["view".to_owned()],
+            ..TreeOptions::default()
+        };
+        let tree = backend().build_tree(101, None, &opts).unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].title.as_str(), tree[0].index), ("View", 2));
+
+        let opts = TreeOptions {
+            exclude_menus: vec!["FILE".to_owned()],
+            ..TreeOptions::default()
+        };
+        let tree = backend().build_tree(101, None, &opts).unwrap();
+        assert!(tree.iter().all(|n| n.title != "File"));
+        assert_eq!(tree[0].index, 2);
+    }
+
+    #[test]
+    fn test_press_flips_checkmark() {
+        let b = backend();
+        let opts = TreeOptions::default();
+        let tree = b.build_tree(101, None, &opts).unwrap();
+        let node = resolve(&tree, "View::Show Ruler").unwrap();
+        assert!(!node.checked);
+        b.press(101, node).unwrap();
+
+        let tree = b.build_tree(101, None, &opts).unwrap();
+        assert!(resolve(&tree, "View::Show Ruler").unwrap().checked);
+        assert_eq!(b.pressed(), vec!["View::Show Ruler".to_owned()]);
+    }
+
+    #[test]
+    fn test_perform_action_checks_support() {
+        let b = backend();
+        let opts = TreeOptions::default();
+        let tree = b.build_tree(101, None, &opts).unwrap();
+        let view = tree.iter().find(|n| n.title == "View").unwrap();
+        let actions = b.actions(101, view).unwrap();
+        assert!(actions.contains(&"AXShowMenu".to_owned()));
+        b.perform_action(101, view, "AXShowMenu").unwrap();
+
+        let node = resolve(&tree, "View::Show Ruler").unwrap();
+        assert!(matches!(
+            b.perform_action(101, node, "AXShowMenu"),
+            Err(MenuError::ActionUnsupported { .. })
+        ));
+        b.perform_action(101, node, "AXPress").unwrap();
+        assert_eq!(b.pressed(), vec!["View::Show Ruler".to_owned()]);
+    }
+
+    #[test]
+    fn test_press_radio_item_deselects_group() {
+        let b = FakeBackend::from_json(
+            r#"{"apps": [{"name": "Preview", "pid": 1, "menus": [
+                {"title": "View", "children": [{"title": "Display", "children": [
+                    {"title": "Single Page", "checked": true, "radio": true},
+                    {"title": "Two Pages", "checked": false, "radio": true}
+                ]}]}
+            ]}]}"#,
+        )
+        .unwrap();
+        let opts = TreeOptions::default();
+        let tree = b.build_tree(1, None, &opts).unwrap();
+        let two = resolve(&tree, "View::Display::Two Pages").unwrap();
+        assert_eq!(two.radio_group.as_deref(), Some("View::Display#1"));
+        b.press(1, two).unwrap();
+
+        let tree = b.build_tree(1, None, &opts).unwrap();
+        let single = resolve(&tree, "View::Display::Single Page").unwrap();
+        assert!(!single.checked);
+        assert!(resolve(&tree, "View::Display::Two Pages").unwrap().checked);
+    }
+
+    #[test]
+    fn test_press_disabled() {
+        let b = backend();
+        let tree = b.build_tree(101, None, &TreeOptions::default()).unwrap();
+        let node = resolve(&tree, "File::Print…").unwrap();
+        assert!(matches!(
+            b.press(101, node),
+            Err(MenuError::ItemDisabled { .. })
+        ));
+    }
+}
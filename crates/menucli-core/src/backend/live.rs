@@ -0,0 +1,174 @@
+/// The real backend: the macOS Accessibility API.
+use std::time::Duration;
+
+use accessibility_sys::{
+    kAXCreatedNotification, kAXMenuOpenedNotification, kAXTitleChangedNotification,
+    kAXUIElementDestroyedNotification,
+};
+use serde_json::Value;
+
+use super::{AxBackend, MenuWatch};
+use crate::ax::app::{
+    activate, bundle_version, is_running, launch, list_all_running_apps, list_running_apps,
+    resolve_target, window_titles, RunningApp,
+};
+use crate::ax::{
+    ensure_trusted, system_focus, AXElement, AXError, Modifier, Observer, SystemFocus,
+    DEFAULT_MESSAGING_TIMEOUT_SECS,
+};
+use crate::menu::tree::{
+    build_branch, build_extras_tree, build_tree_with_opts, fetch_attributes, node_actions,
+    node_attribute, perform_node_action, press_and_wait, press_node, press_node_with, read_checked,
+    submenu_frame, MenuFrame, TreeOptions,
+};
+use crate::menu::{MenuError, MenuNode, ResolveOptions};
+
+/// Backend that reads live menus via `AXUIElement`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiveBackend;
+
+impl AxBackend for LiveBackend {
+    fn ensure_trusted(&self) -> Result<(), AXError> {
+        ensure_trusted()
+    }
+
+    fn list_apps(&self) -> Vec<RunningApp> {
+        list_running_apps()
+    }
+
+    fn list_all_apps(&self) -> Vec<RunningApp> {
+        list_all_running_apps()
+    }
+
+    fn resolve_target(&self, app: Option<&str>) -> Result<i32, AXError> {
+        resolve_target(app)
+    }
+
+    fn focus(&self) -> Result<SystemFocus, AXError> {
+        system_focus()
+    }
+
+    fn activate(&self, pid: i32, timeout: Duration) -> Result<(), AXError> {
+        activate(pid, timeout)
+    }
+
+    fn launch(&self, app: &str, timeout: Duration) -> Result<i32, AXError> {
+        launch(app, timeout)
+    }
+
+    fn window_titles(&self, pid: i32) -> Result<Vec<String>, AXError> {
+        window_titles(pid)
+    }
+
+    fn app_version(&self, pid: i32) -> Option<String> {
+        bundle_version(pid)
+    }
+
+    fn build_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        build_tree_with_opts(pid, max_depth, opts)
+    }
+
+    fn build_extras_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        build_extras_tree(pid, max_depth, opts)
+    }
+
+    fn build_branch(
+        &self,
+        pid: i32,
+        path: &str,
+        resolve: &ResolveOptions,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        build_branch(pid, path, resolve, opts)
+    }
+
+    fn press(&self, _pid: i32, node: &MenuNode) -> Result<(), MenuError> {
+        press_node(node)
+    }
+
+    fn press_with(
+        &self,
+        _pid: i32,
+        node: &MenuNode,
+        modifiers: &[Modifier],
+    ) -> Result<(), MenuError> {
+        press_node_with(node, modifiers)
+    }
+
+    fn actions(&self, _pid: i32, node: &MenuNode) -> Result<Vec<String>, MenuError> {
+        node_actions(node)
+    }
+
+    fn perform_action(&self, _pid: i32, node: &MenuNode, action: &str) -> Result<(), MenuError> {
+        perform_node_action(node, action)
+    }
+
+    fn menu_frame(&self, _pid: i32, node: &MenuNode) -> Result<Option<MenuFrame>, MenuError> {
+        submenu_frame(node)
+    }
+
+    fn attribute(&self, _pid: i32, node: &MenuNode, attr: &str) -> Result<Value, MenuError> {
+        node_attribute(node, attr)
+    }
+
+    fn press_and_wait(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        timeout: Duration,
+    ) -> Result<Option<bool>, MenuError> {
+        press_and_wait(pid, node, timeout)
+    }
+
+    fn read_checked(&self, _pid: i32, node: &MenuNode) -> Result<bool, MenuError> {
+        read_checked(node)
+    }
+
+    fn fetch_attributes(
+        &self,
+        pid: i32,
+        batch: bool,
+        opts: &TreeOptions,
+    ) -> Result<usize, MenuError> {
+        fetch_attributes(pid, batch, opts)
+    }
+
+    fn watch_menus(&self, pid: i32) -> Option<Box<dyn MenuWatch>> {
+        let app = AXElement::application(pid, DEFAULT_MESSAGING_TIMEOUT_SECS);
+        let observer = Observer::watch(pid, &app, MENU_CHANGE_NOTIFICATIONS).ok()?;
+        Some(Box::new(LiveMenuWatch { pid, observer }))
+    }
+}
+
+/// Notifications, observed on the application element (so they cover all of
+/// its menus), after which a cached tree may be stale.
+const MENU_CHANGE_NOTIFICATIONS: &[&str] = &[
+    kAXMenuOpenedNotification,
+    kAXCreatedNotification,
+    kAXUIElementDestroyedNotification,
+    kAXTitleChangedNotification,
+];
+
+/// [`MenuWatch`] backed by an `AXObserver` on the app element. Notifications
+/// are queued by the run loop and collected on each [`MenuWatch::changed`].
+struct LiveMenuWatch {
+    pid: i32,
+    observer: Observer,
+}
+
+impl MenuWatch for LiveMenuWatch {
+    fn changed(&mut self) -> bool {
+        let notified = !self.observer.drain().is_empty();
+        notified || !is_running(self.pid)
+    }
+}
@@ -0,0 +1,298 @@
+/// Backend abstraction over the Accessibility API.
+///
+/// Commands talk to an [`AxBackend`] instead of calling the AX layer directly, so
+/// the same code paths can run against a JSON fixture ([`FakeBackend`]) on machines
+/// without Accessibility access (CI, Linux dev boxes).
+///
+/// [`from_env`] picks the backend: [`FakeBackend`] when `MENUCLI_FIXTURE` points at a
+/// fixture file, otherwise [`LiveBackend`].
+pub mod cache;
+pub mod fake;
+pub mod live;
+pub mod retry;
+
+pub use cache::CacheBackend;
+pub use fake::FakeBackend;
+pub use live::LiveBackend;
+pub use retry::{RetryBackend, RetryPolicy};
+
+use std::time::Duration;
+
+use accessibility_sys::kAXPressAction;
+use serde_json::Value;
+
+use crate::ax::app::RunningApp;
+use crate::ax::{AXError, Modifier, SystemFocus};
+use crate::menu::tree::{
+    check_action, node_field, sweep_extras, ExtrasResult, MenuFrame, TreeOptions,
+};
+use crate::menu::{MenuError, MenuNode, ResolveOptions};
+
+/// A change feed for one app's menus, from [`AxBackend::watch_menus`].
+pub trait MenuWatch {
+    /// Whether the app's menus may have changed, or the app has quit, since
+    /// the watch started or the last call. Never blocks.
+    fn changed(&mut self) -> bool;
+}
+
+/// Environment variable naming a JSON fixture to serve instead of the live AX API.
+pub const FIXTURE_ENV: &str = "MENUCLI_FIXTURE";
+
+/// Everything the commands need from the Accessibility layer.
+pub trait AxBackend: Send + Sync {
+    /// Check whether this process may use the Accessibility API.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::NotTrusted` if permission has not been granted.
+    fn ensure_trusted(&self) -> Result<(), AXError>;
+
+    /// List running applications with a name, sorted by name.
+    fn list_apps(&self) -> Vec<RunningApp>;
+
+    /// Like [`list_apps`](Self::list_apps), but also background processes and
+    /// agents without a name. The default lists only named apps.
+    fn list_all_apps(&self) -> Vec<RunningApp> {
+        self.list_apps()
+    }
+
+    /// Resolve an optional `--app` identifier (name, PID, or bundle ID) to a PID.
+    /// `None` means the frontmost app.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::AppNotFound` if nothing matches, or
+    /// `AXError::AmbiguousApp` if several apps match equally well.
+    fn resolve_target(&self, app: Option<&str>) -> Result<i32, AXError>;
+
+    /// What has keyboard focus system-wide. The default reports the
+    /// frontmost app, without a focused element.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if no app has focus.
+    fn focus(&self) -> Result<SystemFocus, AXError> {
+        Ok(SystemFocus {
+            pid: self.resolve_target(None)?,
+            element: None,
+        })
+    }
+
+    /// Bring the app behind `pid` to the front and wait up to `timeout` for
+    /// its menu bar to appear. The default does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::AppNotFound` if the app is gone.
+    fn activate(&self, _pid: i32, _timeout: Duration) -> Result<(), AXError> {
+        Ok(())
+    }
+
+    /// Launch the app `app` names (a name or bundle ID) and wait up to
+    /// `timeout` for it to come up frontmost with a menu bar, returning its
+    /// PID. The default cannot launch anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError::AppNotFound` if no installed app matches or it did
+    /// not start in time.
+    fn launch(&self, app: &str, _timeout: Duration) -> Result<i32, AXError> {
+        Err(AXError::AppNotFound {
+            identifier: app.to_owned(),
+        })
+    }
+
+    /// Titles of the windows of the app behind `pid`. The default has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AXError` if the windows cannot be read.
+    fn window_titles(&self, _pid: i32) -> Result<Vec<String>, AXError> {
+        Ok(Vec::new())
+    }
+
+    /// Version of the app behind `pid`, if known. The default knows none.
+    fn app_version(&self, _pid: i32) -> Option<String> {
+        None
+    }
+
+    /// Build the app menu tree for `pid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the tree cannot be read.
+    fn build_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError>;
+
+    /// Build the status bar (menu extras) tree for `pid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the tree cannot be read or the app has no extras.
+    fn build_extras_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError>;
+
+    /// Build just enough of the app menu tree for `pid` to resolve the exact
+    /// `::` path `path` with `resolve`. The default builds the whole tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the tree cannot be read.
+    fn build_branch(
+        &self,
+        pid: i32,
+        _path: &str,
+        _resolve: &ResolveOptions,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.build_tree(pid, None, opts)
+    }
+
+    /// Press a node previously built by this backend for `pid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::ItemDisabled` if the item is disabled, or `MenuError::AX`
+    /// if the action fails.
+    fn press(&self, pid: i32, node: &MenuNode) -> Result<(), MenuError>;
+
+    /// Press `node` while holding `modifiers` down. The default presses
+    /// without them.
+    ///
+    /// # Errors
+    ///
+    /// As for [`press`](Self::press), plus `MenuError::AX(AXError::EventFailed)`
+    /// if the keys cannot be held.
+    fn press_with(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        _modifiers: &[Modifier],
+    ) -> Result<(), MenuError> {
+        self.press(pid, node)
+    }
+
+    /// The AX actions `node` supports. The default offers only `AXPress`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::AX` if the item's actions cannot be read.
+    fn actions(&self, _pid: i32, _node: &MenuNode) -> Result<Vec<String>, MenuError> {
+        Ok(vec![kAXPressAction.to_owned()])
+    }
+
+    /// Perform the named AX action on `node`, after checking it is one of its
+    /// [`actions`](Self::actions). The default can only press.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::ActionUnsupported` if `node` does not support
+    /// `action`, otherwise as for [`press`](Self::press).
+    fn perform_action(&self, pid: i32, node: &MenuNode, action: &str) -> Result<(), MenuError> {
+        check_action(node, action, self.actions(pid, node)?)?;
+        self.press(pid, node)
+    }
+
+    /// The on-screen frame of the menu `node` opens, while it is open. The
+    /// default has no screen, so never finds it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::AX` if the menu's frame cannot be read.
+    fn menu_frame(&self, _pid: i32, _node: &MenuNode) -> Result<Option<MenuFrame>, MenuError> {
+        Ok(None)
+    }
+
+    /// Read the attribute named `attr` from `node`, as JSON (`null` if it
+    /// has none). The default only knows the attributes read while building
+    /// the tree; see [`node_field`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::AX` if the attribute cannot be read.
+    fn attribute(&self, _pid: i32, node: &MenuNode, attr: &str) -> Result<Value, MenuError> {
+        Ok(node_field(node, attr))
+    }
+
+    /// Re-read the checkmark state of a node previously built by this backend
+    /// for `pid`, without rebuilding the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::AX(AXError::InvalidElement)` if the item no longer
+    /// exists as built (the app may have rebuilt its menu).
+    fn read_checked(&self, pid: i32, node: &MenuNode) -> Result<bool, MenuError>;
+
+    /// Press `node` and wait up to `timeout` for its checkmark to change.
+    ///
+    /// Returns the checkmark read as soon as it differs from `node.checked`
+    /// (or when `timeout` elapses), or `None` if it could not be read from the pressed
+    /// element (e.g. the app rebuilt its menu) and must be resolved again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` only if the press itself fails.
+    fn press_and_wait(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        _timeout: Duration,
+    ) -> Result<Option<bool>, MenuError> {
+        self.press(pid, node)?;
+        Ok(self.read_checked(pid, node).ok())
+    }
+
+    /// Read every menu item's attributes for `pid` without building a tree,
+    /// in one AX call per item (`batch`) or one per attribute, returning how
+    /// many items were read; `bench` times the two. The default builds the
+    /// tree, so both modes cost the same.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the menus cannot be read.
+    fn fetch_attributes(
+        &self,
+        pid: i32,
+        _batch: bool,
+        opts: &TreeOptions,
+    ) -> Result<usize, MenuError> {
+        self.build_tree(pid, None, opts)
+            .map(|tree| crate::menu::flatten(&tree).len())
+    }
+
+    /// Start watching `pid`'s menus, so long-lived callers can drop cached
+    /// trees exactly when they go stale. `None` if the backend cannot watch
+    /// (the default), in which case callers keep their own expiry policy.
+    fn watch_menus(&self, _pid: i32) -> Option<Box<dyn MenuWatch>> {
+        None
+    }
+
+    /// Build extras trees for all running apps, several at a time (see
+    /// [`sweep_extras`]), skipping apps without extras.
+    fn build_all_extras(&self, max_depth: Option<usize>, opts: &TreeOptions) -> Vec<ExtrasResult> {
+        sweep_extras(&self.list_apps(), opts, |pid, opts| {
+            self.build_extras_tree(pid, max_depth, opts)
+        })
+    }
+}
+
+/// Select the backend from the environment: a [`FakeBackend`] when
+/// [`FIXTURE_ENV`] is set, otherwise the [`LiveBackend`].
+///
+/// # Errors
+///
+/// Returns `MenuError::InvalidFixture` if the fixture cannot be read or parsed.
+pub fn from_env() -> Result<Box<dyn AxBackend>, MenuError> {
+    match std::env::var_os(FIXTURE_ENV) {
+        Some(path) if !path.is_empty() => Ok(Box::new(FakeBackend::from_file(path.as_ref())?)),
+        _ => Ok(Box::new(LiveBackend)),
+    }
+}
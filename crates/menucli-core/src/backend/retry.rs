@@ -0,0 +1,242 @@
+/// A backend decorator that retries transient AX failures.
+///
+/// Apps that are briefly busy (saving, compiling) answer AX calls with
+/// `kAXErrorCannotComplete`, surfaced as [`AXError::Timeout`]. [`RetryBackend`]
+/// retries tree builds and presses that fail that way, with exponential back-off,
+/// instead of aborting the command.
+use std::time::Duration;
+
+use serde_json::Value;
+use tracing::debug;
+
+use super::{AxBackend, MenuWatch};
+use crate::ax::app::RunningApp;
+use crate::ax::{AXError, Modifier, SystemFocus};
+use crate::menu::tree::{MenuFrame, TreeOptions};
+use crate::menu::{MenuError, MenuNode, ResolveOptions};
+
+/// How many times to retry a transient failure, and how long to wait first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Extra attempts after the first one fails.
+    pub retries: u32,
+    /// Delay before the first retry; doubled before each further one.
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Run `f`, retrying it while it fails with [`AXError::Timeout`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once retries are exhausted, or the first error
+    /// that is not transient.
+    pub fn run<T>(&self, mut f: impl FnMut() -> Result<T, MenuError>) -> Result<T, MenuError> {
+        let mut delay = self.delay;
+        let mut attempt = 0;
+        loop {
+            match f() {
+                Err(MenuError::AX(AXError::Timeout)) if attempt < self.retries => {
+                    debug!(attempt, ?delay, "AX call timed out; retrying");
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Wraps another backend, retrying its AX work under a [`RetryPolicy`].
+pub struct RetryBackend {
+    inner: Box<dyn AxBackend>,
+    policy: RetryPolicy,
+}
+
+impl RetryBackend {
+    /// Retry `inner`'s tree builds and presses under `policy`.
+    #[must_use]
+    pub fn new(inner: Box<dyn AxBackend>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl AxBackend for RetryBackend {
+    fn ensure_trusted(&self) -> Result<(), AXError> {
+        self.inner.ensure_trusted()
+    }
+
+    fn list_apps(&self) -> Vec<RunningApp> {
+        self.inner.list_apps()
+    }
+
+    fn list_all_apps(&self) -> Vec<RunningApp> {
+        self.inner.list_all_apps()
+    }
+
+    fn resolve_target(&self, app: Option<&str>) -> Result<i32, AXError> {
+        self.inner.resolve_target(app)
+    }
+
+    fn focus(&self) -> Result<SystemFocus, AXError> {
+        self.inner.focus()
+    }
+
+    fn activate(&self, pid: i32, timeout: Duration) -> Result<(), AXError> {
+        self.inner.activate(pid, timeout)
+    }
+
+    fn launch(&self, app: &str, timeout: Duration) -> Result<i32, AXError> {
+        self.inner.launch(app, timeout)
+    }
+
+    // `--wait-for` polls these anyway.
+    fn window_titles(&self, pid: i32) -> Result<Vec<String>, AXError> {
+        self.inner.window_titles(pid)
+    }
+
+    fn app_version(&self, pid: i32) -> Option<String> {
+        self.inner.app_version(pid)
+    }
+
+    fn build_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.policy
+            .run(|| self.inner.build_tree(pid, max_depth, opts))
+    }
+
+    fn build_extras_tree(
+        &self,
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.policy
+            .run(|| self.inner.build_extras_tree(pid, max_depth, opts))
+    }
+
+    fn build_branch(
+        &self,
+        pid: i32,
+        path: &str,
+        resolve: &ResolveOptions,
+        opts: &TreeOptions,
+    ) -> Result<Vec<MenuNode>, MenuError> {
+        self.policy
+            .run(|| self.inner.build_branch(pid, path, resolve, opts))
+    }
+
+    fn press(&self, pid: i32, node: &MenuNode) -> Result<(), MenuError> {
+        self.policy.run(|| self.inner.press(pid, node))
+    }
+
+    fn press_with(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        modifiers: &[Modifier],
+    ) -> Result<(), MenuError> {
+        self.policy
+            .run(|| self.inner.press_with(pid, node, modifiers))
+    }
+
+    fn actions(&self, pid: i32, node: &MenuNode) -> Result<Vec<String>, MenuError> {
+        self.policy.run(|| self.inner.actions(pid, node))
+    }
+
+    fn perform_action(&self, pid: i32, node: &MenuNode, action: &str) -> Result<(), MenuError> {
+        self.policy
+            .run(|| self.inner.perform_action(pid, node, action))
+    }
+
+    fn menu_frame(&self, pid: i32, node: &MenuNode) -> Result<Option<MenuFrame>, MenuError> {
+        self.policy.run(|| self.inner.menu_frame(pid, node))
+    }
+
+    fn attribute(&self, pid: i32, node: &MenuNode, attr: &str) -> Result<Value, MenuError> {
+        self.policy.run(|| self.inner.attribute(pid, node, attr))
+    }
+
+    fn read_checked(&self, pid: i32, node: &MenuNode) -> Result<bool, MenuError> {
+        self.policy.run(|| self.inner.read_checked(pid, node))
+    }
+
+    fn press_and_wait(
+        &self,
+        pid: i32,
+        node: &MenuNode,
+        timeout: Duration,
+    ) -> Result<Option<bool>, MenuError> {
+        // Only a failed press is reported as an error, so retrying never
+        // presses an item twice.
+        self.policy
+            .run(|| self.inner.press_and_wait(pid, node, timeout))
+    }
+
+    fn fetch_attributes(
+        &self,
+        pid: i32,
+        batch: bool,
+        opts: &TreeOptions,
+    ) -> Result<usize, MenuError> {
+        self.policy
+            .run(|| self.inner.fetch_attributes(pid, batch, opts))
+    }
+
+    fn watch_menus(&self, pid: i32) -> Option<Box<dyn MenuWatch>> {
+        self.inner.watch_menus(pid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            retries,
+            delay: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_retries_timeouts_until_success() {
+        let mut calls = 0;
+        let result = policy(3).run(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(MenuError::AX(AXError::Timeout))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_gives_up_after_retries() {
+        let mut calls = 0;
+        let result: Result<(), _> = policy(2).run(|| {
+            calls += 1;
+            Err(MenuError::AX(AXError::Timeout))
+        });
+        assert!(matches!(result, Err(MenuError::AX(AXError::Timeout))));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_other_errors_not_retried() {
+        let mut calls = 0;
+        let result: Result<(), _> = policy(5).run(|| {
+            calls += 1;
+            Err(MenuError::AX(AXError::InvalidElement))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
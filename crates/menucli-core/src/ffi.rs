@@ -0,0 +1,237 @@
+/// C API over the menu layer (built with `--features ffi`).
+///
+/// Every function returns a status code — `0` on success, otherwise the exit code
+/// the CLI would use for the same failure — and stores a NUL-terminated JSON
+/// string in `*out`: the result on success, or the `{"ok": false, "error": {...}}`
+/// envelope on failure. Release it with [`menucli_free_string`].
+///
+/// `opts` is an optional JSON object (may be null) whose keys mirror the CLI flags.
+/// The matching C declarations live in `include/menucli.h`.
+use std::ffi::{c_char, CStr, CString};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::menu::tree::{build_extras_tree, TreeOptions};
+use crate::menu::{
+    build_tree_with_opts, flatten, press_node, resolve, CancelToken, MenuError, MenuNode,
+};
+use crate::types::{ErrorDetail, ErrorOutput, MenuItemOutput};
+
+/// Status code for invalid arguments: null pointers, bad UTF-8, or malformed `opts`.
+pub const MENUCLI_INVALID_ARGUMENT: i32 = 2;
+
+/// Options accepted by [`menucli_list_json`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ListOpts {
+    extras: bool,
+    depth: Option<usize>,
+    enabled_only: bool,
+    alternates: bool,
+}
+
+/// Options accepted by [`menucli_click`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ClickOpts {
+    extras: bool,
+    dry_run: bool,
+    alternates: bool,
+}
+
+/// List the menu items of the app with `pid` as a flat JSON array.
+///
+/// `opts` keys: `extras`, `depth`, `enabled_only`, `alternates`.
+///
+/// # Safety
+///
+/// `opts` must be null or a valid NUL-terminated string, and `out` must be a
+/// valid pointer to writable storage for one `char *`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn menucli_list_json(
+    pid: i32,
+    opts: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    // SAFETY: forwarded from the caller's contract.
+    let opts: ListOpts = match unsafe { parse_opts(opts) } {
+        Ok(opts) => opts,
+        Err(message) => return unsafe { write_invalid(out, &message) },
+    };
+
+    let result = build(pid, opts.extras, opts.depth, opts.alternates).map(|tree| {
+        let mut items: Vec<MenuItemOutput> = flatten(&tree)
+            .into_iter()
+            .map(|f| MenuItemOutput::from_flat(f, None))
+            .collect();
+        if opts.enabled_only {
+            items.retain(|i| i.enabled);
+        }
+        items
+    });
+
+    // SAFETY: forwarded from the caller's contract.
+    unsafe { write_result(out, result) }
+}
+
+/// Resolve `path` (exact path or fuzzy query) in the app with `pid` and press it.
+///
+/// On success `*out` holds the resolved item. `opts` keys: `extras`, `dry_run`,
+/// `alternates`.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string, `opts` must be null or a valid
+/// NUL-terminated string, and `out` must be a valid pointer to writable storage
+/// for one `char *`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn menucli_click(
+    pid: i32,
+    path: *const c_char,
+    opts: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    // SAFETY: forwarded from the caller's contract.
+    let path = match unsafe { read_str(path) } {
+        Ok(Some(path)) => path,
+        Ok(None) => return unsafe { write_invalid(out, "path must not be null") },
+        Err(message) => return unsafe { write_invalid(out, &message) },
+    };
+    // SAFETY: forwarded from the caller's contract.
+    let opts: ClickOpts = match unsafe { parse_opts(opts) } {
+        Ok(opts) => opts,
+        Err(message) => return unsafe { write_invalid(out, &message) },
+    };
+
+    let result = build(pid, opts.extras, None, opts.alternates).and_then(|tree| {
+        let node = resolve(&tree, path)?;
+        if !opts.dry_run {
+            press_node(node)?;
+        }
+        Ok(MenuItemOutput::from_node(node))
+    });
+
+    // SAFETY: forwarded from the caller's contract.
+    unsafe { write_result(out, result) }
+}
+
+/// Free a string previously returned through an `out` parameter. Null is a no-op.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer obtained from this library that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn menucli_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: `s` came from `CString::into_raw` in `write_out`.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+fn build(
+    pid: i32,
+    extras: bool,
+    depth: Option<usize>,
+    alternates: bool,
+) -> Result<Vec<MenuNode>, MenuError> {
+    let opts = TreeOptions {
+        include_alternates: alternates,
+        locale_aliases: false,
+        menus: Vec::new(),
+        exclude_menus: Vec::new(),
+        ax_timeout: None,
+        progress: None,
+        cancel: CancelToken::default(),
+        extras_jobs: None,
+        open_menus: false,
+    };
+    if extras {
+        build_extras_tree(pid, depth, &opts)
+    } else {
+        build_tree_with_opts(pid, depth, &opts)
+    }
+}
+
+/// Borrow a C string as `&str`; null maps to `None`.
+///
+/// # Safety
+///
+/// `s` must be null or a valid NUL-terminated string that outlives the result.
+unsafe fn read_str<'a>(s: *const c_char) -> Result<Option<&'a str>, String> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: caller guarantees `s` is a valid NUL-terminated string.
+    let s = unsafe { CStr::from_ptr(s) };
+    s.to_str().map(Some).map_err(|e| e.to_string())
+}
+
+/// Parse an optional JSON options object; null or empty means defaults.
+///
+/// # Safety
+///
+/// Same contract as [`read_str`].
+unsafe fn parse_opts<T: DeserializeOwned + Default>(opts: *const c_char) -> Result<T, String> {
+    // SAFETY: forwarded from the caller's contract.
+    match unsafe { read_str(opts) }? {
+        None => Ok(T::default()),
+        Some(s) if s.trim().is_empty() => Ok(T::default()),
+        Some(s) => serde_json::from_str(s).map_err(|e| format!("invalid opts: {e}")),
+    }
+}
+
+/// Serialize `result` into `*out` and return its status code.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing one pointer.
+unsafe fn write_result<T: Serialize>(out: *mut *mut c_char, result: Result<T, MenuError>) -> i32 {
+    let (code, json) = match result {
+        Ok(value) => (0, serde_json::to_string(&value)),
+        Err(err) => (
+            err.exit_code(),
+            serde_json::to_string(&ErrorOutput::from_menu_error(&err)),
+        ),
+    };
+    // SAFETY: forwarded from the caller's contract.
+    unsafe { write_out(out, json.unwrap_or_default()) };
+    code
+}
+
+/// Store an `invalid_argument` error envelope in `*out`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing one pointer.
+unsafe fn write_invalid(out: *mut *mut c_char, message: &str) -> i32 {
+    let envelope = ErrorOutput {
+        ok: false,
+        error: ErrorDetail {
+            code: "invalid_argument".to_owned(),
+            message: message.to_owned(),
+            candidates: None,
+            app: None,
+            pid: None,
+            strategy_tried: None,
+            hint: None,
+        },
+    };
+    // SAFETY: forwarded from the caller's contract.
+    unsafe { write_out(out, serde_json::to_string(&envelope).unwrap_or_default()) };
+    MENUCLI_INVALID_ARGUMENT
+}
+
+/// Hand `json` to the caller through `out`. Null `out` discards the string.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing one pointer.
+unsafe fn write_out(out: *mut *mut c_char, json: String) {
+    if out.is_null() {
+        return;
+    }
+    // JSON output never contains interior NULs (they are escaped as \u0000).
+    let raw = CString::new(json).unwrap_or_default().into_raw();
+    // SAFETY: caller guarantees `out` is valid for writes.
+    unsafe { *out = raw };
+}
@@ -0,0 +1,36 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+//! menucli-core — read and drive macOS app menu bars through the Accessibility API.
+//!
+//! This is the library behind the `menucli` CLI. Layers:
+//!
+//! - [`ax`]: safe wrappers over `AXUIElement` and app PID resolution.
+//! - [`backend`]: the [`AxBackend`] seam — live AX, or a JSON fixture for tests.
+//! - [`menu`]: tree building, flattening, search, and path resolution.
+//! - [`pattern`]: the glob and regex name patterns those layers share.
+//! - [`types`]: the serializable output shapes the CLI prints.
+//!
+//! Most callers only need [`MenuTree`], [`resolve`], and [`press`]:
+//!
+//! ```no_run
+//! use menucli_core::{ax::resolve_target, press, resolve, MenuTree};
+//!
+//! let pid = resolve_target(Some("TextEdit"))?;
+//! let tree = MenuTree::build(pid)?;
+//! let node = resolve(&tree.nodes, "save as")?;
+//! press(node)?;
+//! # Ok::<(), menucli_core::MenuError>(())
+//! ```
+//!
+//! Build with `--features ffi` to additionally export a small C API (see `ffi`).
+
+pub mod ax;
+pub mod backend;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod menu;
+pub mod pattern;
+pub mod types;
+
+pub use backend::AxBackend;
+pub use menu::{press_node as press, resolve, MenuError, MenuNode, MenuTree, TreeOptions};
@@ -0,0 +1,72 @@
+/// Cooperative cancellation of tree walks.
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use super::errors::MenuError;
+
+const RUNNING: u8 = 0;
+const CANCELLED: u8 = 1;
+const EXPIRED: u8 = 2;
+
+/// A flag shared between a tree walk and whoever may interrupt it (e.g. a
+/// SIGINT handler or a `--timeout` deadline). Walkers check it before each
+/// item and stop early, so the walk returns promptly with whatever it has read.
+///
+/// Clones share the same flag. Setting it only takes an atomic operation, so
+/// it is safe to call from a signal handler. The first reason to stop wins.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicU8>);
+
+impl CancelToken {
+    /// Ask every walk holding this token to stop.
+    pub fn cancel(&self) {
+        self.stop(CANCELLED);
+    }
+
+    /// Stop every walk holding this token because a deadline passed; checks
+    /// then fail with [`MenuError::TimedOut`] instead.
+    pub fn expire(&self) {
+        self.stop(EXPIRED);
+    }
+
+    fn stop(&self, reason: u8) {
+        let _ = self
+            .0
+            .compare_exchange(RUNNING, reason, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) or [`expire`](Self::expire) has been called.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed) != RUNNING
+    }
+
+    /// Fail once cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::Cancelled` if the token has been cancelled, or
+    /// `MenuError::TimedOut` if it expired.
+    pub fn check(&self) -> Result<(), MenuError> {
+        match self.0.load(Ordering::Relaxed) {
+            RUNNING => Ok(()),
+            EXPIRED => Err(MenuError::TimedOut),
+            _ => Err(MenuError::Cancelled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_reason_wins() {
+        let token = CancelToken::default();
+        assert!(token.check().is_ok());
+        token.expire();
+        token.cancel();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(MenuError::TimedOut)));
+    }
+}
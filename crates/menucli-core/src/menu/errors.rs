@@ -0,0 +1,295 @@
+/// Errors from the menu domain layer.
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ax::AXError;
+
+/// Errors that can occur while working with menu trees.
+#[derive(Debug, Error)]
+#[allow(dead_code)]
+pub enum MenuError {
+    /// Accessibility permission not granted.
+    #[error("Accessibility permission not granted")]
+    AccessDenied,
+
+    /// No running application matched the identifier.
+    #[error("No running application matches '{identifier}'")]
+    AppNotFound {
+        /// The searched identifier.
+        identifier: String,
+    },
+
+    /// No menu item matched the query or path.
+    #[error("No menu item matches '{query}'")]
+    ItemNotFound {
+        /// The searched query or path.
+        query: String,
+        /// The last strategy tried before giving up.
+        strategy: MatchStrategy,
+    },
+
+    /// Multiple menu items matched with similar confidence; cannot auto-resolve.
+    #[error("Ambiguous match for '{query}'. Candidates:\n{}", candidates.join("\n  "))]
+    AmbiguousMatch {
+        /// The searched query.
+        query: String,
+        /// Full paths of all candidates that matched.
+        candidates: Vec<String>,
+        /// The strategy that matched several items.
+        strategy: MatchStrategy,
+    },
+
+    /// The menu item matched but is disabled and cannot be activated.
+    #[error("Menu item '{path}' is disabled")]
+    ItemDisabled {
+        /// Full path of the disabled item.
+        path: String,
+    },
+
+    /// The menu item's element does not support the requested AX action.
+    #[error("Menu item '{path}' does not support {action} (it supports: {})", supported.join(", "))]
+    ActionUnsupported {
+        /// Full path of the item.
+        path: String,
+        /// The requested action.
+        action: String,
+        /// The actions the element does support.
+        supported: Vec<String>,
+    },
+
+    /// The menu item does not have a checkmark and cannot be toggled.
+    #[error("Menu item '{path}' is not a toggleable (checkmark) item")]
+    NotToggleable {
+        /// Full path of the non-toggleable item.
+        path: String,
+    },
+
+    /// The menu item is in a mixed (dash) state, so toggling has no single
+    /// opposite state to reach.
+    #[error("Menu item '{path}' is in a mixed state; click it to turn it on for everything")]
+    MixedState {
+        /// Full path of the mixed item.
+        path: String,
+    },
+
+    /// The menu item is the chosen item of a radio group; pressing it again
+    /// leaves it chosen, so it cannot be toggled off.
+    #[error("Menu item '{path}' is the selected choice of radio group '{group}'; click another item in the group instead")]
+    RadioSelected {
+        /// Full path of the radio item.
+        path: String,
+        /// The radio group it belongs to.
+        group: String,
+    },
+
+    /// The item's checkmark did not reach the state `toggle --expect` asked
+    /// for, even after a second press.
+    #[error("Menu item '{path}' did not turn {expected} after pressing it")]
+    StateNotReached {
+        /// Full path of the item.
+        path: String,
+        /// The requested state, "on" or "off".
+        expected: String,
+    },
+
+    /// A `state --assert` condition does not hold for the item.
+    #[error("Menu item '{path}' is not {condition}")]
+    AssertionFailed {
+        /// Full path of the item.
+        path: String,
+        /// The condition that failed (e.g. "checked").
+        condition: String,
+    },
+
+    /// A `click --wait-for` condition did not hold before the wait timed out.
+    #[error("Condition '{condition}' was not met in time")]
+    ConditionNotMet {
+        /// The condition as written.
+        condition: String,
+    },
+
+    /// Input the command reads besides its arguments (such as paths on stdin)
+    /// is unusable.
+    #[error("Invalid input: {reason}")]
+    InvalidInput {
+        /// What is wrong with it.
+        reason: String,
+    },
+
+    /// A fixture (`MENUCLI_FIXTURE`) or snapshot (`--from-file`) could not be loaded.
+    #[error("Cannot load menu tree from '{path}': {reason}")]
+    InvalidFixture {
+        /// Path of the fixture or snapshot file.
+        path: String,
+        /// Why it was rejected.
+        reason: String,
+    },
+
+    /// A `[hooks]` command from the config file failed.
+    #[error("Hook '{hook}' failed: {reason}")]
+    HookFailed {
+        /// The hook point, e.g. `pre_click`.
+        hook: String,
+        /// Why: the exit status, or why it could not run.
+        reason: String,
+    },
+
+    /// `action --screenshot` could not capture the opened menu.
+    #[error("Cannot capture the menu of '{path}': {reason}")]
+    ScreenshotFailed {
+        /// Full path of the item whose menu was opened.
+        path: String,
+        /// Why: the menu was not on screen, or the capture failed.
+        reason: String,
+    },
+
+    /// `--fail-if-empty` was given and there was nothing to print.
+    #[error("No menu items to output")]
+    NoResults,
+
+    /// The operation was interrupted (Ctrl-C) before it finished.
+    #[error("Interrupted")]
+    Cancelled,
+
+    /// The `--timeout` deadline passed before the operation finished.
+    #[error("Timed out before finishing")]
+    TimedOut,
+
+    /// An underlying AX API error.
+    #[error("Accessibility API error: {0}")]
+    AX(#[from] AXError),
+}
+
+/// How a query was matched against the menu tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStrategy {
+    /// Stable item ID (`--id`).
+    Id,
+    /// A `::`-separated path, segment by segment.
+    ExactPath,
+    /// A path with `*` wildcards, each matching within a single segment.
+    WildcardPath,
+    /// The item's title, case-insensitively.
+    ExactTitle,
+    /// Fuzzy scoring of full paths.
+    Fuzzy,
+}
+
+impl MatchStrategy {
+    /// The strategy's name, as it appears in JSON.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::ExactPath => "exact_path",
+            Self::WildcardPath => "wildcard_path",
+            Self::ExactTitle => "exact_title",
+            Self::Fuzzy => "fuzzy",
+        }
+    }
+}
+
+/// Exit code mapping for `MenuError` variants.
+impl MenuError {
+    /// Return the CLI exit code for this error.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::AccessDenied => 3,
+            Self::AppNotFound { .. } | Self::ItemNotFound { .. } | Self::AmbiguousMatch { .. } => 4,
+            Self::ItemDisabled { .. }
+            | Self::ActionUnsupported { .. }
+            | Self::NotToggleable { .. }
+            | Self::MixedState { .. }
+            | Self::RadioSelected { .. }
+            | Self::StateNotReached { .. }
+            | Self::ConditionNotMet { .. }
+            | Self::InvalidInput { .. }
+            | Self::InvalidFixture { .. }
+            | Self::HookFailed { .. }
+            | Self::ScreenshotFailed { .. } => 1,
+            Self::NoResults => 5,
+            Self::AssertionFailed { .. } => 6,
+            Self::Cancelled => 130,
+            Self::TimedOut => 124,
+            Self::AX(ax) => match ax {
+                AXError::NotTrusted => 3,
+                AXError::AppNotFound { .. } | AXError::AmbiguousApp { .. } => 4,
+                _ => 1,
+            },
+        }
+    }
+
+    /// A suggestion for getting past this error, if there is one.
+    #[must_use]
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::AccessDenied | Self::AX(AXError::NotTrusted) => Some(
+                "grant access in System Settings > Privacy & Security > Accessibility (`menucli check-access --open-settings` opens it), then rerun",
+            ),
+            Self::AppNotFound { .. } | Self::AX(AXError::AppNotFound { .. }) => {
+                Some("run `menucli apps` to see running apps and their bundle IDs")
+            }
+            Self::AX(AXError::AmbiguousApp { .. }) => Some(
+                "give the full app name, or the bundle ID or PID of one candidate; click and state take --first",
+            ),
+            Self::AX(AXError::InvalidAppPattern { .. }) => {
+                Some("`glob:` takes * and ? wildcards; `re:` takes a regular expression")
+            }
+            Self::ItemNotFound { strategy, .. } => Some(match strategy {
+                MatchStrategy::Id => {
+                    "IDs follow the item's path; look it up again with `menucli list --fields id,path`"
+                }
+                MatchStrategy::ExactPath | MatchStrategy::WildcardPath => {
+                    "check the path with `menucli list`; items the app adds when a menu opens need --open-menus"
+                }
+                MatchStrategy::ExactTitle | MatchStrategy::Fuzzy => {
+                    "try `menucli search`; items the app adds when a menu opens need --open-menus"
+                }
+            }),
+            Self::AmbiguousMatch { .. } => {
+                Some("use one of the candidate paths, or pick one with --first or --prefer")
+            }
+            Self::ItemDisabled { .. } => {
+                Some("the app disables items that do not apply right now; check its state or focused window")
+            }
+            Self::ActionUnsupported { .. } => {
+                Some("use one of the supported actions; `menucli action PATH` lists them")
+            }
+            Self::NotToggleable { .. } => Some("use `menucli click` for items without a checkmark"),
+            Self::MixedState { .. } => Some("use `menucli click`, or toggle --expect on|off"),
+            Self::RadioSelected { .. } => {
+                Some("click the item of the group you want chosen instead")
+            }
+            Self::StateNotReached { .. } => {
+                Some("the app may update the checkmark late; check it with `menucli state`")
+            }
+            Self::TimedOut => Some("raise --timeout, or --ax-timeout for a slow app"),
+            Self::HookFailed { .. } => Some("check the command in the config file's [hooks] table"),
+            Self::ScreenshotFailed { .. } => Some(
+                "open menus with --name AXShowMenu on an item that has a submenu; capturing needs Screen Recording permission",
+            ),
+            Self::NoResults => Some(
+                "loosen the filters or query; an app that is not frontmost may show no menus",
+            ),
+            Self::AX(_) => Some("the app may be busy; try --retries or a larger --ax-timeout"),
+            Self::AssertionFailed { .. }
+            | Self::ConditionNotMet { .. }
+            | Self::InvalidInput { .. }
+            | Self::InvalidFixture { .. }
+            | Self::Cancelled => None,
+        }
+    }
+
+    /// The match strategy behind a resolution error.
+    #[must_use]
+    pub fn strategy(&self) -> Option<MatchStrategy> {
+        match self {
+            Self::ItemNotFound { strategy, .. } | Self::AmbiguousMatch { strategy, .. } => {
+                Some(*strategy)
+            }
+            _ => None,
+        }
+    }
+}
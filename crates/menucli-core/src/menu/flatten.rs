@@ -0,0 +1,211 @@
+/// Flatten a menu tree into a list of `FlatItem`s with full path notation.
+use super::tree::{CheckState, MenuNode};
+
+/// A flat representation of a menu item (no children).
+#[derive(Debug, Clone)]
+pub struct FlatItem {
+    /// Display title (leaf name only).
+    pub title: String,
+    /// Full path from root (e.g., "File::Save As…").
+    pub path: String,
+    /// Full path of the containing item (e.g., "File"); `None` for top-level
+    /// menus.
+    pub parent_path: Option<String>,
+    /// Whether the item is enabled.
+    pub enabled: bool,
+    /// Whether the item has a checkmark.
+    pub checked: bool,
+    /// On, off, or mixed.
+    pub check_state: CheckState,
+    /// The radio group this item is a choice of, if it is radio-style.
+    pub radio_group: Option<String>,
+    /// Formatted keyboard shortcut.
+    pub shortcut: Option<String>,
+    /// Virtual key code of the shortcut's key.
+    pub virtual_key: Option<u16>,
+    /// The shortcut's unformatted key character.
+    pub cmd_char: Option<String>,
+    /// The shortcut's raw modifier mask.
+    pub cmd_modifiers: Option<u8>,
+    /// The glyph code of a shortcut key without a character.
+    pub cmd_glyph: Option<u16>,
+    /// Whether the item is highlighted, where reported.
+    pub selected: Option<bool>,
+    /// Whether the item has keyboard focus, where reported.
+    pub focused: Option<bool>,
+    /// AX role string.
+    pub role: String,
+    /// Depth in the menu hierarchy.
+    pub depth: usize,
+    /// 1-based position among its siblings.
+    pub index: usize,
+    /// Stable content-hash ID.
+    pub id: String,
+    /// Number of direct children (0 for leaf items).
+    pub children_count: usize,
+    /// Number of items below this one, at any depth.
+    pub descendants_count: usize,
+    /// How many of those are enabled; 0 in a non-empty submenu means every
+    /// item in it is disabled.
+    pub enabled_descendants: usize,
+    /// Whether this item is an Option-key alternate.
+    pub is_alternate: bool,
+    /// Title of the primary item this alternate replaces, if any.
+    pub alternate_of: Option<String>,
+    /// Help (tooltip) text.
+    pub help: Option<String>,
+}
+
+/// Flatten a tree of `MenuNode`s into a `Vec<FlatItem>`.
+///
+/// Traversal is depth-first, pre-order (parent before children).
+#[must_use]
+pub fn flatten(nodes: &[MenuNode]) -> Vec<FlatItem> {
+    let mut result = Vec::new();
+    for node in nodes {
+        flatten_node(node, None, &mut result);
+    }
+    result
+}
+
+/// Flatten `node`, the child of the item at `parent`, and its subtree into
+/// `out`, returning how many of those items are enabled.
+fn flatten_node(node: &MenuNode, parent: Option<&str>, out: &mut Vec<FlatItem>) -> usize {
+    let at = out.len();
+    out.push(FlatItem {
+        title: node.title.clone(),
+        path: node.path.clone(),
+        parent_path: parent.map(str::to_owned),
+        enabled: node.enabled,
+        checked: node.checked,
+        check_state: node.check_state,
+        radio_group: node.radio_group.clone(),
+        shortcut: node.shortcut.clone(),
+        virtual_key: node.virtual_key,
+        cmd_char: node.cmd_char.clone(),
+        cmd_modifiers: node.cmd_modifiers,
+        cmd_glyph: node.cmd_glyph,
+        selected: node.selected,
+        focused: node.focused,
+        role: node.role.clone(),
+        depth: node.depth,
+        index: node.index,
+        id: node.id.clone(),
+        children_count: node.children.len(),
+        // Filled in once the children are flattened.
+        descendants_count: 0,
+        enabled_descendants: 0,
+        is_alternate: node.is_alternate,
+        alternate_of: node.alternate_of.clone(),
+        help: node.help.clone(),
+    });
+    let enabled: usize = node
+        .children
+        .iter()
+        .map(|c| flatten_node(c, Some(&node.path), out))
+        .sum();
+    out[at].descendants_count = out.len() - at - 1;
+    out[at].enabled_descendants = enabled;
+    enabled + usize::from(node.enabled)
+}
+
+/// Number of items below `node`, at any depth.
+#[must_use]
+pub fn descendants_count(node: &MenuNode) -> usize {
+    node.children.iter().map(|c| 1 + descendants_count(c)).sum()
+}
+
+/// Number of enabled items below `node`, at any depth.
+#[must_use]
+pub fn enabled_descendants(node: &MenuNode) -> usize {
+    node.children
+        .iter()
+        .map(|c| usize::from(c.enabled) + enabled_descendants(c))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_node(title: &str, path: &str, children: Vec<MenuNode>) -> MenuNode {
+        MenuNode {
+            title: title.to_owned(),
+            path: path.to_owned(),
+            enabled: true,
+            checked: false,
+            check_state: CheckState::Off,
+            radio_group: None,
+            radio_bulleted: false,
+            shortcut: None,
+            virtual_key: None,
+            cmd_char: None,
+            cmd_modifiers: None,
+            cmd_glyph: None,
+            selected: None,
+            focused: None,
+            role: "AXMenuItem".to_owned(),
+            depth: 1,
+            index: 0,
+            id: String::new(),
+            children,
+            element: None,
+            is_alternate: false,
+            alternate_of: None,
+            help: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_flatten_single_level() {
+        let nodes = vec![
+            mock_node("File", "File", vec![]),
+            mock_node("Edit", "Edit", vec![]),
+        ];
+        let flat = flatten(&nodes);
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat[0].path, "File");
+        assert_eq!(flat[1].path, "Edit");
+    }
+
+    #[test]
+    fn test_flatten_nested() {
+        let child = mock_node("New", "File::New", vec![]);
+        let parent = mock_node("File", "File", vec![child]);
+        let flat = flatten(&[parent]);
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat[0].path, "File");
+        assert_eq!(flat[1].path, "File::New");
+        assert_eq!(flat[0].children_count, 1);
+        assert_eq!(flat[1].children_count, 0);
+        assert_eq!(flat[0].parent_path, None);
+        assert_eq!(flat[1].parent_path.as_deref(), Some("File"));
+    }
+
+    #[test]
+    fn test_descendants_count() {
+        let bold = mock_node("Bold", "Format::Font::Bold", vec![]);
+        let font = mock_node("Font", "Format::Font", vec![bold]);
+        let wrap = mock_node("Wrap", "Format::Wrap", vec![]);
+        let format = mock_node("Format", "Format", vec![font, wrap]);
+        let flat = flatten(std::slice::from_ref(&format));
+        let counts: Vec<usize> = flat.iter().map(|f| f.descendants_count).collect();
+        assert_eq!(counts, [3, 1, 0, 0]);
+        assert_eq!(descendants_count(&format), 3);
+    }
+
+    #[test]
+    fn test_enabled_descendants() {
+        let mut bold = mock_node("Bold", "Format::Font::Bold", vec![]);
+        bold.enabled = false;
+        let font = mock_node("Font", "Format::Font", vec![bold]);
+        let wrap = mock_node("Wrap", "Format::Wrap", vec![]);
+        let format = mock_node("Format", "Format", vec![font, wrap]);
+        let flat = flatten(std::slice::from_ref(&format));
+        let counts: Vec<usize> = flat.iter().map(|f| f.enabled_descendants).collect();
+        assert_eq!(counts, [2, 0, 0, 0]);
+        assert_eq!(enabled_descendants(&format), 2);
+        assert_eq!(enabled_descendants(&format.children[0]), 0);
+    }
+}
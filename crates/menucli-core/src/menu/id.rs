@@ -0,0 +1,60 @@
+/// Stable item IDs: a short content hash that survives across invocations.
+///
+/// An ID hashes the owning app's bundle identifier, the item's normalized
+/// (lowercased) path, and its AX role, so the same item gets the same ID every
+/// run — unlike element refs, which die with the process. The hash is 64-bit
+/// FNV-1a, chosen because its output is fixed by definition rather than by the
+/// standard library version.
+use super::tree::MenuNode;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Compute the ID of an item owned by the app identified by `app_key`
+/// (its bundle ID, or its name when it has none).
+#[must_use]
+pub fn item_id(app_key: &str, path: &str, role: &str) -> String {
+    let path = path.to_lowercase();
+    let mut hash = FNV_OFFSET;
+    for part in [app_key, path.as_str(), role] {
+        // NUL-terminate each part so ("ab", "c") and ("a", "bc") differ.
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Set `id` on every node in the tree.
+pub fn assign_ids(nodes: &mut [MenuNode], app_key: &str) {
+    for node in nodes {
+        node.id = item_id(app_key, &node.path, &node.role);
+        assign_ids(&mut node.children, app_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_is_stable_and_case_insensitive() {
+        let id = item_id("com.apple.TextEdit", "File::Save As…", "AXMenuItem");
+        assert_eq!(id.len(), 16);
+        assert_eq!(
+            id,
+            item_id("com.apple.TextEdit", "file::save as…", "AXMenuItem")
+        );
+    }
+
+    #[test]
+    fn test_id_depends_on_app_and_role() {
+        let id = item_id("com.apple.TextEdit", "File::Save", "AXMenuItem");
+        assert_ne!(id, item_id("com.apple.Preview", "File::Save", "AXMenuItem"));
+        assert_ne!(
+            id,
+            item_id("com.apple.TextEdit", "File::Save", "AXMenuBarItem")
+        );
+    }
+}
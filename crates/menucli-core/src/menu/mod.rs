@@ -0,0 +1,19 @@
+/// Menu domain layer: tree building, flattening, search, path resolution.
+pub mod cancel;
+pub mod errors;
+pub mod flatten;
+pub mod id;
+pub mod resolve;
+pub mod search;
+pub mod shortcut;
+pub mod tree;
+
+pub use cancel::CancelToken;
+pub use errors::{MatchStrategy, MenuError};
+pub use flatten::{flatten, FlatItem};
+pub use resolve::{
+    explain, is_exact_path, resolve, resolve_all, resolve_candidates, resolve_explained,
+    resolve_id, resolve_with, subtree, Explanation, Prefer, Resolution, ResolveOptions, Verdict,
+};
+pub use search::{search, SearchFilters, SearchOptions};
+pub use tree::{build_tree_with_opts, press_node, CheckState, MenuNode, MenuTree, TreeOptions};
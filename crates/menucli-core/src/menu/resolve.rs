@@ -0,0 +1,966 @@
+/// Path resolution: convert user-provided strings to specific `MenuNode`s.
+///
+/// Resolution strategy (in priority order):
+///
+/// 1. **Exact path match**: If input contains "::", walk the tree level-by-level
+///    with exact title matching. A `*` in a segment matches any run of
+///    characters (`File::*::PDF`), so one path can match several items, and a
+///    segment like `#3` that matches no title selects the third child.
+///    "Exact" ignores case, reads `...` as `…` and straight quotes as curly, and
+///    also accepts a node's locale-independent aliases. Without `::`, the
+///    separators people type by habit (`File > Save As`, `File/Save As`) are
+///    tried as paths first.
+/// 2. **Exact title match (leaf)**: Search all leaf items for an exact title match.
+///    Succeeds only if exactly one item matches.
+/// 3. **Fuzzy match**: Run fuzzy search. Auto-resolve if the top result has a
+///    significantly higher score than the second (confidence > threshold).
+/// 4. **Ambiguity error**: If multiple items match with similar scores.
+///
+/// [`resolve_with`] can break ties between same-titled matches with
+/// [`Prefer`] rules before giving up with an ambiguity error.
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use nucleo_matcher::{
+    chars::normalize,
+    pattern::{CaseMatching, Normalization, Pattern},
+    Matcher, Utf32Str,
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::{
+    errors::{MatchStrategy, MenuError},
+    tree::{split_path, unescape_segment, MenuNode, PATH_SEP},
+};
+use crate::pattern;
+
+/// Default minimum score ratio between 1st and 2nd result to auto-resolve a
+/// fuzzy match.
+pub const FUZZY_AUTO_RESOLVE_RATIO: f32 = 2.0;
+
+/// A tie-breaking rule for matches that are otherwise equally good.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefer {
+    /// Enabled items win over disabled ones.
+    Enabled,
+    /// Leaf items win over submenu containers.
+    Leaf,
+    /// Items closest to the menu bar win.
+    Shallow,
+}
+
+impl FromStr for Prefer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "enabled" => Ok(Self::Enabled),
+            "leaf" => Ok(Self::Leaf),
+            "shallow" => Ok(Self::Shallow),
+            other => Err(format!(
+                "unknown preference '{other}' (expected enabled, leaf, or shallow)"
+            )),
+        }
+    }
+}
+
+/// Tuning for [`resolve_with`].
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    /// Tie-breaking rules for equally good matches, applied in order.
+    pub prefer: Vec<Prefer>,
+    /// Auto-resolve a fuzzy match only if it outscores the runner-up by this factor.
+    pub fuzzy_ratio: f32,
+    /// Ignore fuzzy matches scoring below this.
+    pub min_score: u32,
+    /// Also fuzzy-match against each item's help (tooltip) text.
+    pub include_help: bool,
+    /// Ignore diacritics in exact title comparisons ("Resume" matches "Résumé").
+    pub fold_diacritics: bool,
+    /// Stop after the exact path and exact title strategies; never fuzzy-match.
+    pub exact: bool,
+    /// Compare titles byte-for-byte: no case, ellipsis, quote, or diacritic folding.
+    pub case_sensitive: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            prefer: Vec::new(),
+            fuzzy_ratio: FUZZY_AUTO_RESOLVE_RATIO,
+            min_score: 0,
+            include_help: false,
+            fold_diacritics: false,
+            exact: false,
+            case_sensitive: false,
+        }
+    }
+}
+
+impl ResolveOptions {
+    /// `title` in the form exact comparisons use (see [`normalize_title`]).
+    fn comparable<'t>(&self, title: &'t str) -> Cow<'t, str> {
+        if self.case_sensitive {
+            Cow::Borrowed(title)
+        } else {
+            Cow::Owned(normalize_title(title, self.fold_diacritics))
+        }
+    }
+}
+
+/// Resolve a user-provided path/query to a single `MenuNode`.
+///
+/// The node is found by reference in the tree; the returned node is cloned
+/// (including its `element` ref which is `Clone`-able via CF retain).
+///
+/// # Errors
+///
+/// - `MenuError::ItemNotFound` — no item matches
+/// - `MenuError::AmbiguousMatch` — multiple items match with similar confidence
+pub fn resolve<'a>(nodes: &'a [MenuNode], query: &str) -> Result<&'a MenuNode, MenuError> {
+    resolve_with(nodes, query, &ResolveOptions::default())
+}
+
+/// Like [`resolve`], with tunable fuzzy thresholds. When several items match
+/// equally (same title, or the same wildcard path), the `prefer` rules are
+/// applied in order to narrow them down.
+///
+/// # Errors
+///
+/// Same as [`resolve`]; `AmbiguousMatch` only if the rules leave several items.
+#[instrument(level = "debug", skip(nodes, opts))]
+pub fn resolve_with<'a>(
+    nodes: &'a [MenuNode],
+    query: &str,
+    opts: &ResolveOptions,
+) -> Result<&'a MenuNode, MenuError> {
+    resolve_explained(nodes, query, opts).map(|r| r.node)
+}
+
+/// An item a query resolved to, and how.
+#[derive(Debug, Clone, Copy)]
+pub struct Resolution<'a> {
+    /// The matched item.
+    pub node: &'a MenuNode,
+    /// The strategy that matched it.
+    pub strategy: MatchStrategy,
+    /// Its fuzzy score; `None` for the exact strategies.
+    pub score: Option<u32>,
+}
+
+/// Like [`resolve_with`], also telling which strategy matched and, for a
+/// fuzzy match, its score.
+///
+/// # Errors
+///
+/// Same as [`resolve_with`].
+pub fn resolve_explained<'a>(
+    nodes: &'a [MenuNode],
+    query: &str,
+    opts: &ResolveOptions,
+) -> Result<Resolution<'a>, MenuError> {
+    let Explanation {
+        mut candidates,
+        verdict,
+        ..
+    } = explain(nodes, query, opts)?;
+    let strategy = candidates[0].strategy;
+    if verdict.resolves() {
+        return Ok(candidates.remove(0));
+    }
+    if strategy == MatchStrategy::Fuzzy {
+        candidates.truncate(5);
+    }
+    Err(MenuError::AmbiguousMatch {
+        query: query.to_owned(),
+        candidates: candidates.iter().map(|r| r.node.path.clone()).collect(),
+        strategy,
+    })
+}
+
+/// Whether, and why, a query settles on a single item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Only one item matched.
+    Unique,
+    /// The best fuzzy match outscored the runner-up by at least
+    /// [`ResolveOptions::fuzzy_ratio`].
+    Confident,
+    /// The best fuzzy match did not outscore the runner-up by enough.
+    TooClose,
+    /// Several items matched exactly, and the `prefer` rules left more than one.
+    Tied,
+}
+
+impl Verdict {
+    /// Whether the best candidate is picked rather than reported as ambiguous.
+    #[must_use]
+    pub fn resolves(self) -> bool {
+        matches!(self, Self::Unique | Self::Confident)
+    }
+}
+
+/// How a query resolves: the candidates, and how one was (or was not) picked.
+#[derive(Debug, Clone)]
+pub struct Explanation<'a> {
+    /// Every item the first matching strategy found, best first.
+    pub candidates: Vec<Resolution<'a>>,
+    /// The best fuzzy score over the runner-up's, when several items matched
+    /// fuzzily.
+    pub ratio: Option<f32>,
+    /// The outcome.
+    pub verdict: Verdict,
+}
+
+/// The full trace behind [`resolve_explained`]: every candidate, the fuzzy
+/// confidence ratio, and whether auto-resolve triggered.
+///
+/// # Errors
+///
+/// Returns `MenuError::ItemNotFound` if no strategy matches anything.
+pub fn explain<'a>(
+    nodes: &'a [MenuNode],
+    query: &str,
+    opts: &ResolveOptions,
+) -> Result<Explanation<'a>, MenuError> {
+    let candidates = resolve_candidates(nodes, query, opts)?;
+    let (ratio, verdict) = match candidates.as_slice() {
+        [_] => (None, Verdict::Unique),
+        // Auto-resolve if best is significantly ahead of second.
+        [best, second, ..] if best.strategy == MatchStrategy::Fuzzy => {
+            let best_score = best.score.unwrap_or_default();
+            let second_score = second.score.unwrap_or_default();
+            let ratio = best_score as f32 / (second_score as f32).max(1.0);
+            let verdict = if ratio >= opts.fuzzy_ratio {
+                Verdict::Confident
+            } else {
+                Verdict::TooClose
+            };
+            (Some(ratio), verdict)
+        }
+        _ => (None, Verdict::Tied),
+    };
+    Ok(Explanation {
+        candidates,
+        ratio,
+        verdict,
+    })
+}
+
+/// Every item the first matching strategy finds for `query`, best first:
+/// what [`resolve_explained`] picks from, or reports as ambiguous. Never
+/// empty.
+///
+/// # Errors
+///
+/// Returns `MenuError::ItemNotFound` if no strategy matches anything.
+pub fn resolve_candidates<'a>(
+    nodes: &'a [MenuNode],
+    query: &str,
+    opts: &ResolveOptions,
+) -> Result<Vec<Resolution<'a>>, MenuError> {
+    if let Some(path) = canonical_separators(query) {
+        match resolve_candidates(nodes, &path, opts) {
+            // Not a path after all (e.g. "Show/Hide Toolbar"): match it as typed.
+            Err(MenuError::ItemNotFound { .. }) => {}
+            result => return result,
+        }
+    }
+    let exact = |nodes: Vec<&'a MenuNode>, strategy: MatchStrategy| -> Vec<Resolution<'a>> {
+        nodes
+            .into_iter()
+            .map(|node| Resolution {
+                node,
+                strategy,
+                score: None,
+            })
+            .collect()
+    };
+
+    let prefer = opts.prefer.as_slice();
+    // Strategy 1: Exact path match (query contains separator)
+    if is_wildcard_path(query) {
+        let matches = break_ties(resolve_by_wildcard_path(nodes, query, opts)?, prefer);
+        return Ok(exact(matches, MatchStrategy::WildcardPath));
+    }
+    if query.contains(PATH_SEP) {
+        let node = resolve_by_exact_path(nodes, query, opts)?;
+        return Ok(exact(vec![node], MatchStrategy::ExactPath));
+    }
+
+    // Strategy 2: Exact title match (case-insensitive)
+    let wanted = opts.comparable(query);
+    let exact_matches: Vec<&MenuNode> = collect_leaves(nodes)
+        .into_iter()
+        .filter(|n| names(n).any(|name| opts.comparable(name) == wanted))
+        .collect();
+    let exact_matches = break_ties(exact_matches, prefer);
+    if !exact_matches.is_empty() {
+        return Ok(exact(exact_matches, MatchStrategy::ExactTitle));
+    }
+
+    if opts.exact {
+        return Err(MenuError::ItemNotFound {
+            query: query.to_owned(),
+            strategy: MatchStrategy::ExactTitle,
+        });
+    }
+
+    // Strategy 3: Fuzzy match
+    fuzzy_candidates(nodes, query, opts)
+}
+
+/// Apply each rule in turn, keeping only the candidates it favors — unless it
+/// favors none of them, in which case the rule is skipped.
+fn break_ties<'a>(mut candidates: Vec<&'a MenuNode>, prefer: &[Prefer]) -> Vec<&'a MenuNode> {
+    for rule in prefer {
+        if candidates.len() <= 1 {
+            break;
+        }
+        let min_depth = candidates.iter().map(|n| n.depth).min().unwrap_or(0);
+        let kept: Vec<&MenuNode> = candidates
+            .iter()
+            .copied()
+            .filter(|n| match rule {
+                Prefer::Enabled => n.enabled,
+                Prefer::Leaf => n.children.is_empty(),
+                Prefer::Shallow => n.depth == min_depth,
+            })
+            .collect();
+        if !kept.is_empty() {
+            candidates = kept;
+        }
+    }
+    candidates
+}
+
+/// Resolve a query that may match several items, in tree order.
+///
+/// Wildcard paths return every match; any other query resolves exactly as
+/// [`resolve_with`] does and yields a single item.
+///
+/// # Errors
+///
+/// Same as [`resolve`]; wildcard paths fail only with `MenuError::ItemNotFound`,
+/// or `MenuError::InvalidInput` for a segment too long to compile.
+#[instrument(level = "debug", skip(nodes, opts))]
+pub fn resolve_all<'a>(
+    nodes: &'a [MenuNode],
+    query: &str,
+    opts: &ResolveOptions,
+) -> Result<Vec<&'a MenuNode>, MenuError> {
+    let canonical = canonical_separators(query);
+    let path = canonical.as_deref().unwrap_or(query);
+    if is_wildcard_path(path) {
+        resolve_by_wildcard_path(nodes, path, opts)
+    } else {
+        resolve_with(nodes, query, opts).map(|n| vec![n])
+    }
+}
+
+/// The children of the item at `within` (e.g. `Format::Font`), for narrowing
+/// resolution or search to one submenu when titles repeat across menus.
+///
+/// `within` is walked as an exact path first, then resolved like any query,
+/// both with the caller's `opts`.
+///
+/// # Errors
+///
+/// Same as [`resolve_with`] applied to `within`.
+pub fn subtree<'a>(
+    nodes: &'a [MenuNode],
+    within: &str,
+    opts: &ResolveOptions,
+) -> Result<&'a [MenuNode], MenuError> {
+    // A bare title names a top-level menu here, not a leaf as in `resolve`.
+    resolve_by_exact_path(nodes, within, opts)
+        .or_else(|_| resolve_with(nodes, within, opts))
+        .map(|n| n.children.as_slice())
+}
+
+/// Find the node with the given stable [`id`](super::id::item_id).
+///
+/// # Errors
+///
+/// Returns `MenuError::ItemNotFound` if no node in the tree has that ID.
+#[instrument(level = "debug", skip(nodes))]
+pub fn resolve_id<'a>(nodes: &'a [MenuNode], id: &str) -> Result<&'a MenuNode, MenuError> {
+    let mut all = Vec::new();
+    collect_all(nodes, &mut all);
+    all.into_iter()
+        .find(|n| n.id.eq_ignore_ascii_case(id))
+        .ok_or_else(|| MenuError::ItemNotFound {
+            query: id.to_owned(),
+            strategy: MatchStrategy::Id,
+        })
+}
+
+/// Rewrite `File > Save As` or `File/Save As` as `File::Save As`. `None` if the
+/// query already uses `::`, has no alternative separator, or has an empty segment.
+fn canonical_separators(query: &str) -> Option<String> {
+    if query.contains(PATH_SEP) {
+        return None;
+    }
+    let segments: Vec<&str> = if query.contains(" > ") {
+        query.split(" > ").map(str::trim).collect()
+    } else if query.contains('/') {
+        query.split('/').map(str::trim).collect()
+    } else {
+        return None;
+    };
+    if segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+    Some(segments.join(PATH_SEP))
+}
+
+/// Whether `query` is a plain `::` path (no wildcards), which resolves by
+/// walking one branch of the tree and never falls back to fuzzy matching.
+#[must_use]
+pub fn is_exact_path(query: &str) -> bool {
+    query.contains(PATH_SEP) && !is_wildcard_path(query)
+}
+
+/// Whether `query` is a path with a `*` wildcard in at least one segment.
+/// A `?` matches one character inside such a path (see [`pattern::glob`]),
+/// but does not make one on its own: titles often end in a question mark.
+fn is_wildcard_path(query: &str) -> bool {
+    query.contains(PATH_SEP) && split_path(query).iter().any(|s| s.contains('*'))
+}
+
+/// Walk the tree level-by-level like [`resolve_by_exact_path`], but keep every
+/// node whose title matches the segment's glob instead of the first.
+fn resolve_by_wildcard_path<'a>(
+    nodes: &'a [MenuNode],
+    path: &str,
+    opts: &ResolveOptions,
+) -> Result<Vec<&'a MenuNode>, MenuError> {
+    let mut matched: Vec<&MenuNode> = Vec::new();
+    let mut current: Vec<&[MenuNode]> = vec![nodes];
+
+    for segment in split_path(path) {
+        // Both sides are already in comparable form, so the glob itself is
+        // case-sensitive.
+        let glob =
+            pattern::glob(&opts.comparable(&unescape_segment(segment)), true).map_err(|e| {
+                MenuError::InvalidInput {
+                    reason: format!("{segment}: {e}"),
+                }
+            })?;
+        let index = parse_index(segment);
+        matched = current
+            .iter()
+            .flat_map(|level| {
+                let hits: Vec<&MenuNode> = level
+                    .iter()
+                    .filter(|n| names(n).any(|name| glob.is_match(&opts.comparable(name))))
+                    .collect();
+                if hits.is_empty() {
+                    index.and_then(|i| level.get(i)).into_iter().collect()
+                } else {
+                    hits
+                }
+            })
+            .collect();
+        current = matched.iter().map(|n| n.children.as_slice()).collect();
+    }
+
+    if matched.is_empty() {
+        return Err(MenuError::ItemNotFound {
+            query: path.to_owned(),
+            strategy: MatchStrategy::WildcardPath,
+        });
+    }
+    Ok(matched)
+}
+
+/// Parse a positional segment like `#3` into a 0-based sibling offset.
+fn parse_index(segment: &str) -> Option<usize> {
+    segment
+        .strip_prefix('#')?
+        .parse::<usize>()
+        .ok()?
+        .checked_sub(1)
+}
+
+/// Position in `level` of the item one exact-path segment names: the first
+/// whose title or alias matches, else the `#N`th.
+pub(crate) fn find_segment(
+    level: &[MenuNode],
+    segment: &str,
+    opts: &ResolveOptions,
+) -> Option<usize> {
+    let unescaped = unescape_segment(segment);
+    let wanted = opts.comparable(&unescaped);
+    level
+        .iter()
+        .position(|n| names(n).any(|name| opts.comparable(name) == wanted))
+        .or_else(|| parse_index(&unescaped).filter(|&i| i < level.len()))
+}
+
+/// Walk the tree level-by-level using the path segments split by `::`.
+///
+/// Handles escaped `\::` in segments via [`split_path`] / [`unescape_segment`].
+fn resolve_by_exact_path<'a>(
+    nodes: &'a [MenuNode],
+    path: &str,
+    opts: &ResolveOptions,
+) -> Result<&'a MenuNode, MenuError> {
+    let segments = split_path(path);
+    let mut current = nodes;
+    let mut found: Option<&MenuNode> = None;
+
+    for segment in &segments {
+        match find_segment(current, segment, opts).map(|i| &current[i]) {
+            Some(node) => {
+                found = Some(node);
+                current = &node.children;
+            }
+            None => {
+                return Err(MenuError::ItemNotFound {
+                    query: path.to_owned(),
+                    strategy: MatchStrategy::ExactPath,
+                });
+            }
+        }
+    }
+
+    found.ok_or_else(|| MenuError::ItemNotFound {
+        query: path.to_owned(),
+        strategy: MatchStrategy::ExactPath,
+    })
+}
+
+/// Every name a path segment may match: the title, then any aliases.
+fn names(node: &MenuNode) -> impl Iterator<Item = &str> {
+    std::iter::once(node.title.as_str()).chain(node.aliases.iter().map(String::as_str))
+}
+
+/// Fold a title for exact comparison: lowercase, `…` as `...`, curly quotes as
+/// straight ones, and — when `fold_diacritics` is set — accented Latin letters
+/// as their base letter. Apps use the typographic forms; people type the plain.
+fn normalize_title(title: &str, fold_diacritics: bool) -> String {
+    let mut out = String::with_capacity(title.len());
+    for c in title.chars().flat_map(char::to_lowercase) {
+        match c {
+            '\u{2026}' => out.push_str("..."),
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => out.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => out.push('"'),
+            c if fold_diacritics => out.push(normalize(c)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Collect all leaf nodes (items with no children) from the tree.
+fn collect_leaves(nodes: &[MenuNode]) -> Vec<&MenuNode> {
+    let mut leaves = Vec::new();
+    for node in nodes {
+        if node.children.is_empty() {
+            leaves.push(node);
+        } else {
+            leaves.extend(collect_leaves(&node.children));
+        }
+    }
+    leaves
+}
+
+/// Collect all nodes (including non-leaves) for fuzzy search.
+fn collect_all<'a>(nodes: &'a [MenuNode], out: &mut Vec<&'a MenuNode>) {
+    for node in nodes {
+        out.push(node);
+        collect_all(&node.children, out);
+    }
+}
+
+/// Every item whose path (or, with `include_help`, help text) fuzzy-matches
+/// `query` at `min_score` or better, best first.
+fn fuzzy_candidates<'a>(
+    nodes: &'a [MenuNode],
+    query: &str,
+    opts: &ResolveOptions,
+) -> Result<Vec<Resolution<'a>>, MenuError> {
+    let mut all = Vec::new();
+    collect_all(nodes, &mut all);
+
+    let pattern = Pattern::parse(query, CaseMatching::Smart, Normalization::Smart);
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT.match_paths());
+
+    let mut scored: Vec<Resolution<'a>> = all
+        .iter()
+        .filter_map(|&node| {
+            let mut buf = Vec::new();
+            let path_score = pattern.score(Utf32Str::new(&node.path, &mut buf), &mut matcher);
+            let help_score = node
+                .help
+                .as_deref()
+                .filter(|_| opts.include_help)
+                .and_then(|help| pattern.score(Utf32Str::new(help, &mut buf), &mut matcher));
+            path_score
+                .max(help_score)
+                .filter(|&s| s >= opts.min_score)
+                .map(|s| Resolution {
+                    node,
+                    strategy: MatchStrategy::Fuzzy,
+                    score: Some(s),
+                })
+        })
+        .collect();
+
+    if scored.is_empty() {
+        return Err(MenuError::ItemNotFound {
+            query: query.to_owned(),
+            strategy: MatchStrategy::Fuzzy,
+        });
+    }
+    scored.sort_by(|a, b| b.score.cmp(&a.score));
+    Ok(scored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::CheckState;
+
+    fn node(title: &str, path: &str, children: Vec<MenuNode>) -> MenuNode {
+        MenuNode {
+            title: title.to_owned(),
+            path: path.to_owned(),
+            enabled: true,
+            checked: false,
+            check_state: CheckState::Off,
+            radio_group: None,
+            radio_bulleted: false,
+            shortcut: None,
+            virtual_key: None,
+            cmd_char: None,
+            cmd_modifiers: None,
+            cmd_glyph: None,
+            selected: None,
+            focused: None,
+            role: "AXMenuItem".to_owned(),
+            depth: 1,
+            index: 0,
+            id: String::new(),
+            children,
+            element: None,
+            is_alternate: false,
+            alternate_of: None,
+            help: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    fn tree() -> Vec<MenuNode> {
+        vec![
+            node(
+                "File",
+                "File",
+                vec![
+                    node("New", "File::New", vec![]),
+                    node("Save As…", "File::Save As…", vec![]),
+                    node("Close", "File::Close", vec![]),
+                ],
+            ),
+            node(
+                "Edit",
+                "Edit",
+                vec![
+                    node("Copy", "Edit::Copy", vec![]),
+                    node("Paste", "Edit::Paste", vec![]),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_exact_path() {
+        let t = tree();
+        let result = resolve(&t, "File::Save As…").unwrap();
+        assert_eq!(result.path, "File::Save As…");
+    }
+
+    #[test]
+    fn test_exact_title_unique() {
+        let t = tree();
+        let result = resolve(&t, "Paste").unwrap();
+        assert_eq!(result.path, "Edit::Paste");
+    }
+
+    #[test]
+    fn test_exact_title_ambiguous() {
+        // "New" and "Copy" don't collide, but let's test ambiguity with a custom tree.
+        let t = vec![
+            node("File", "File", vec![node("Save", "File::Save", vec![])]),
+            node("Edit", "Edit", vec![node("Save", "Edit::Save", vec![])]),
+        ];
+        let result = resolve(&t, "save");
+        assert!(matches!(result, Err(MenuError::AmbiguousMatch { .. })));
+    }
+
+    #[test]
+    fn test_wildcard_path_all_matches() {
+        let t = tree();
+        let paths: Vec<_> = resolve_all(&t, "*::*e", &ResolveOptions::default())
+            .unwrap()
+            .iter()
+            .map(|n| n.path.clone())
+            .collect();
+        assert_eq!(paths, ["File::Close", "Edit::Paste"]);
+        let paths: Vec<_> = resolve_all(&t, "*::cl?se", &ResolveOptions::default())
+            .unwrap()
+            .iter()
+            .map(|n| n.path.clone())
+            .collect();
+        assert_eq!(paths, ["File::Close"]);
+    }
+
+    #[test]
+    fn test_wildcard_path_single_or_ambiguous() {
+        let t = tree();
+        assert_eq!(resolve(&t, "*::Save*").unwrap().path, "File::Save As…");
+        assert!(matches!(
+            resolve(&t, "*::*e"),
+            Err(MenuError::AmbiguousMatch { .. })
+        ));
+        assert!(matches!(
+            resolve(&t, "View::*"),
+            Err(MenuError::ItemNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_index_segment() {
+        let t = tree();
+        assert_eq!(resolve(&t, "File::#3").unwrap().path, "File::Close");
+        assert_eq!(resolve(&t, "#2::#1").unwrap().path, "Edit::Copy");
+        assert_eq!(
+            resolve_all(&t, "*::#2", &ResolveOptions::default())
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(matches!(
+            resolve(&t, "File::#0"),
+            Err(MenuError::ItemNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_subtree_narrows_resolution() {
+        let t = vec![
+            node("File", "File", vec![node("Save", "File::Save", vec![])]),
+            node("Edit", "Edit", vec![node("Save", "Edit::Save", vec![])]),
+        ];
+        let scope = subtree(&t, "Edit", &ResolveOptions::default()).unwrap();
+        assert_eq!(resolve(scope, "save").unwrap().path, "Edit::Save");
+
+        let strict = ResolveOptions {
+            exact: true,
+            case_sensitive: true,
+            ..ResolveOptions::default()
+        };
+        assert!(subtree(&t, "edit", &ResolveOptions::default()).is_ok());
+        assert!(subtree(&t, "edit", &strict).is_err());
+    }
+
+    #[test]
+    fn test_prefer_breaks_ties() {
+        let mut disabled = node("Save", "Edit::Save", vec![]);
+        disabled.enabled = false;
+        let t = vec![
+            node("File", "File", vec![node("Save", "File::Save", vec![])]),
+            node("Edit", "Edit", vec![disabled]),
+        ];
+        assert!(matches!(
+            resolve(&t, "save"),
+            Err(MenuError::AmbiguousMatch { .. })
+        ));
+        let opts = |prefer| ResolveOptions {
+            prefer,
+            ..ResolveOptions::default()
+        };
+        let result = resolve_with(&t, "save", &opts(vec![Prefer::Enabled])).unwrap();
+        assert_eq!(result.path, "File::Save");
+        assert!(matches!(
+            resolve_with(&t, "save", &opts(vec![Prefer::Leaf])),
+            Err(MenuError::AmbiguousMatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fuzzy_thresholds() {
+        let t = tree();
+        let strict = ResolveOptions {
+            fuzzy_ratio: f32::MAX,
+            ..ResolveOptions::default()
+        };
+        assert!(matches!(
+            resolve_with(&t, "fl", &strict),
+            Err(MenuError::AmbiguousMatch { .. })
+        ));
+        let floor = ResolveOptions {
+            min_score: u32::MAX,
+            ..ResolveOptions::default()
+        };
+        assert!(matches!(
+            resolve_with(&t, "sav", &floor),
+            Err(MenuError::ItemNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_explained_strategy_and_candidates() {
+        let t = tree();
+        let opts = ResolveOptions::default();
+        let r = resolve_explained(&t, "File::New", &opts).unwrap();
+        assert_eq!((r.strategy, r.score), (MatchStrategy::ExactPath, None));
+        let r = resolve_explained(&t, "paste", &opts).unwrap();
+        assert_eq!(r.strategy, MatchStrategy::ExactTitle);
+        let r = resolve_explained(&t, "sav", &opts).unwrap();
+        assert_eq!(r.node.path, "File::Save As…");
+        assert_eq!(r.strategy, MatchStrategy::Fuzzy);
+        assert!(r.score.is_some_and(|s| s > 0));
+
+        // What an ambiguous query would pick from, best first.
+        let all = resolve_candidates(&t, "*::*e", &opts).unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(all
+            .iter()
+            .all(|r| r.strategy == MatchStrategy::WildcardPath));
+        let fuzzy = resolve_candidates(&t, "e", &opts).unwrap();
+        assert!(fuzzy.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_explain_verdicts() {
+        let t = tree();
+        let opts = ResolveOptions::default();
+        let e = explain(&t, "paste", &opts).unwrap();
+        assert_eq!((e.verdict, e.ratio), (Verdict::Unique, None));
+        let e = explain(&t, "*::*e", &opts).unwrap();
+        assert_eq!(e.verdict, Verdict::Tied);
+        assert!(!e.verdict.resolves());
+
+        let e = explain(&t, "fl", &opts).unwrap();
+        let ratio = e.ratio.unwrap();
+        let strict = ResolveOptions {
+            fuzzy_ratio: ratio + 1.0,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(
+            explain(&t, "fl", &strict).unwrap().verdict,
+            Verdict::TooClose
+        );
+        let lenient = ResolveOptions {
+            fuzzy_ratio: ratio,
+            ..ResolveOptions::default()
+        };
+        assert_eq!(
+            explain(&t, "fl", &lenient).unwrap().verdict,
+            Verdict::Confident
+        );
+    }
+
+    #[test]
+    fn test_typographic_normalization() {
+        let t = tree();
+        assert_eq!(
+            resolve(&t, "File::Save As...").unwrap().path,
+            "File::Save As…"
+        );
+        assert_eq!(resolve(&t, "save as...").unwrap().path, "File::Save As…");
+
+        let t = vec![node(
+            "Edit",
+            "Edit",
+            vec![node("Don’t Résumé", "Edit::Don’t Résumé", vec![])],
+        )];
+        assert!(resolve(&t, "Edit::Don't Resume").is_err());
+        let fold = ResolveOptions {
+            fold_diacritics: true,
+            ..ResolveOptions::default()
+        };
+        let result = resolve_with(&t, "Edit::Don't Resume", &fold).unwrap();
+        assert_eq!(result.path, "Edit::Don’t Résumé");
+    }
+
+    #[test]
+    fn test_aliases() {
+        let mut file = node(
+            "Ablage",
+            "Ablage",
+            vec![node("Sichern", "Ablage::Sichern", vec![])],
+        );
+        file.aliases.push("File".to_owned());
+        file.children[0].aliases.push("saveDocument:".to_owned());
+        let t = vec![file];
+        assert_eq!(
+            resolve(&t, "File::saveDocument:").unwrap().path,
+            "Ablage::Sichern"
+        );
+        assert_eq!(
+            resolve(&t, "Ablage::Sichern").unwrap().path,
+            "Ablage::Sichern"
+        );
+        assert_eq!(
+            resolve_all(&t, "File::*", &ResolveOptions::default())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_alternative_separators() {
+        let t = tree();
+        assert_eq!(
+            resolve(&t, "File > Save As…").unwrap().path,
+            "File::Save As…"
+        );
+        assert_eq!(resolve(&t, "file/save as…").unwrap().path, "File::Save As…");
+        assert_eq!(canonical_separators("Edit::A/B"), None);
+        assert_eq!(canonical_separators("Show/"), None);
+
+        // A title containing `/` still matches when the path reading fails.
+        let t = vec![node(
+            "View",
+            "View",
+            vec![node("Show/Hide", "View::Show/Hide", vec![])],
+        )];
+        assert_eq!(resolve(&t, "Show/Hide").unwrap().path, "View::Show/Hide");
+    }
+
+    #[test]
+    fn test_exact_and_case_sensitive() {
+        let t = tree();
+        let exact = ResolveOptions {
+            exact: true,
+            ..ResolveOptions::default()
+        };
+        assert!(resolve(&t, "sav as").is_ok());
+        assert!(matches!(
+            resolve_with(&t, "sav as", &exact),
+            Err(MenuError::ItemNotFound { .. })
+        ));
+        assert!(resolve_with(&t, "save as…", &exact).is_ok());
+
+        let case = ResolveOptions {
+            case_sensitive: true,
+            ..exact
+        };
+        assert!(resolve_with(&t, "file::save as…", &case).is_err());
+        assert!(resolve_with(&t, "File::Save As...", &case).is_err());
+        assert!(resolve_with(&t, "File::Save As…", &case).is_ok());
+    }
+
+    #[test]
+    fn test_not_found() {
+        let t = tree();
+        let result = resolve(&t, "File::NonExistent");
+        assert!(matches!(result, Err(MenuError::ItemNotFound { .. })));
+    }
+}
@@ -0,0 +1,463 @@
+/// Fuzzy and exact search over flat menu items.
+use std::collections::HashMap;
+use std::ops::Range;
+
+use nucleo_matcher::{
+    pattern::{CaseMatching, Normalization, Pattern},
+    Matcher, Utf32Str,
+};
+
+use super::flatten::FlatItem;
+use super::tree::{escape_title, PATH_SEP};
+
+/// A search result with its match score.
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// The matched item.
+    pub item: FlatItem,
+    /// Match score (higher = better match). 0 for exact search (unscored).
+    pub score: u32,
+    /// Matched spans of `item.path`, as sorted, non-overlapping char ranges.
+    pub ranges: Vec<Range<usize>>,
+    /// Whether the item's help text matched better than its path.
+    pub help_match: bool,
+    /// Matched Option-key alternates of this item, best first, with
+    /// [`SearchOptions::collapse_alternates`].
+    pub alternates: Vec<SearchResult>,
+}
+
+/// Search options.
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// Maximum number of results to return; 0 returns every match.
+    pub limit: usize,
+    /// Use exact substring match instead of fuzzy.
+    pub exact: bool,
+    /// Case-sensitive matching.
+    pub case_sensitive: bool,
+    /// Also match against each item's help (tooltip) text.
+    pub include_help: bool,
+    /// Drop fuzzy matches scoring below this.
+    pub min_score: u32,
+    /// Item filters, applied before matching so they don't eat into `limit`.
+    pub filters: SearchFilters,
+    /// Nest matched alternates under their matched primary, so they don't
+    /// eat into `limit`.
+    pub collapse_alternates: bool,
+}
+
+/// Attribute filters for [`search`]. The default accepts every item.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    /// Only enabled items.
+    pub enabled_only: bool,
+    /// Only items with a checkmark.
+    pub checked: bool,
+    /// Only items with this AX role (e.g. `AXMenuItem`).
+    pub role: Option<String>,
+    /// Only items at most this deep (1 = top-level menu bar items).
+    pub max_depth: Option<usize>,
+    /// Only items with a keyboard shortcut.
+    pub has_shortcut: bool,
+}
+
+impl SearchFilters {
+    /// Whether `item` passes every filter.
+    #[must_use]
+    pub fn accepts(&self, item: &FlatItem) -> bool {
+        (!self.enabled_only || item.enabled)
+            && (!self.checked || item.checked)
+            && self.role.as_ref().is_none_or(|r| item.role == *r)
+            && self.max_depth.is_none_or(|d| item.depth <= d)
+            && (!self.has_shortcut || item.shortcut.is_some())
+    }
+}
+
+impl SearchOptions {
+    /// The most results to return: `limit`, or unbounded when it is 0.
+    #[must_use]
+    pub fn max_results(&self) -> usize {
+        if self.limit == 0 {
+            usize::MAX
+        } else {
+            self.limit
+        }
+    }
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: 10,
+            exact: false,
+            case_sensitive: false,
+            include_help: false,
+            min_score: 0,
+            filters: SearchFilters::default(),
+            collapse_alternates: false,
+        }
+    }
+}
+
+/// Search menu items by query string.
+///
+/// Searches the `path` field (full path like "File::Save As…") which naturally
+/// gives higher scores when the query matches words at boundaries.
+///
+/// Queries use fzf syntax: whitespace-separated terms must all match, and a
+/// term prefixed with `!` excludes items containing it (`"export pdf !screen"`).
+/// Fuzzy mode gets this from nucleo's pattern parser; exact mode treats each
+/// term as a substring, as `fzf --exact` does.
+///
+/// Results are sorted by score descending (best match first).
+#[must_use]
+pub fn search(items: &[FlatItem], query: &str, opts: &SearchOptions) -> Vec<SearchResult> {
+    if query.is_empty() {
+        let results = items
+            .iter()
+            .filter(|item| opts.filters.accepts(item))
+            .map(|item| SearchResult {
+                item: item.clone(),
+                score: 0,
+                ranges: Vec::new(),
+                help_match: false,
+                alternates: Vec::new(),
+            })
+            .collect();
+        return limit(results, opts);
+    }
+
+    if opts.exact {
+        return exact_search(items, query, opts);
+    }
+
+    fuzzy_search(items, query, opts)
+}
+
+fn exact_search(items: &[FlatItem], query: &str, opts: &SearchOptions) -> Vec<SearchResult> {
+    let query = if opts.case_sensitive {
+        query.to_owned()
+    } else {
+        query.to_lowercase()
+    };
+    // (term, negated) pairs; a bare `!` is a literal.
+    let terms: Vec<(&str, bool)> = query
+        .split_whitespace()
+        .map(|t| match t.strip_prefix('!') {
+            Some(rest) if !rest.is_empty() => (rest, true),
+            _ => (t, false),
+        })
+        .collect();
+
+    let results: Vec<SearchResult> = items
+        .iter()
+        .filter(|item| opts.filters.accepts(item))
+        .filter_map(|item| {
+            let fold = |s: &str| {
+                if opts.case_sensitive {
+                    s.to_owned()
+                } else {
+                    s.to_lowercase()
+                }
+            };
+            let matches = |text: &str| {
+                terms
+                    .iter()
+                    .all(|(term, negated)| text.contains(term) != *negated)
+            };
+            if matches(&fold(&item.path)) {
+                Some((item, false))
+            } else {
+                let help = item.help.as_deref().filter(|_| opts.include_help)?;
+                matches(&fold(help)).then_some((item, true))
+            }
+        })
+        .map(|(item, help_match)| {
+            let path = if opts.case_sensitive {
+                item.path.clone()
+            } else {
+                item.path.to_lowercase()
+            };
+            let mut indices: Vec<u32> = Vec::new();
+            for (term, _) in terms.iter().filter(|(_, negated)| !negated) {
+                if let Some(byte) = path.find(term) {
+                    let start = path[..byte].chars().count();
+                    let end = start + term.chars().count();
+                    indices.extend((start..end).filter_map(|i| u32::try_from(i).ok()));
+                }
+            }
+            SearchResult {
+                item: item.clone(),
+                score: 0,
+                ranges: if help_match {
+                    Vec::new()
+                } else {
+                    to_ranges(indices)
+                },
+                help_match,
+                alternates: Vec::new(),
+            }
+        })
+        .collect();
+    limit(results, opts)
+}
+
+fn fuzzy_search(items: &[FlatItem], query: &str, opts: &SearchOptions) -> Vec<SearchResult> {
+    let case_matching = if opts.case_sensitive {
+        CaseMatching::Respect
+    } else {
+        CaseMatching::Smart
+    };
+
+    let pattern = Pattern::parse(query, case_matching, Normalization::Smart);
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT.match_paths());
+
+    let mut scored: Vec<SearchResult> = items
+        .iter()
+        .filter(|item| opts.filters.accepts(item))
+        .filter_map(|item| {
+            let mut buf = Vec::new();
+            let path_score = pattern.score(Utf32Str::new(&item.path, &mut buf), &mut matcher);
+            let help_score = item
+                .help
+                .as_deref()
+                .filter(|_| opts.include_help)
+                .and_then(|help| pattern.score(Utf32Str::new(help, &mut buf), &mut matcher));
+            let help_match = help_score > path_score;
+            help_score
+                .max(path_score)
+                .filter(|&score| score >= opts.min_score)
+                .map(|score| SearchResult {
+                    item: item.clone(),
+                    score,
+                    ranges: Vec::new(),
+                    help_match,
+                    alternates: Vec::new(),
+                })
+        })
+        .collect();
+
+    // Sort by score descending.
+    scored.sort_by(|a, b| b.score.cmp(&a.score));
+    let mut scored = limit(scored, opts);
+
+    // Match positions are costlier than scores, so only compute them for the
+    // results that survive the limit.
+    let mut locate = |result: &mut SearchResult| {
+        if result.help_match {
+            return;
+        }
+        let mut buf = Vec::new();
+        let haystack = Utf32Str::new(&result.item.path, &mut buf);
+        let mut indices = Vec::new();
+        pattern.indices(haystack, &mut matcher, &mut indices);
+        result.ranges = to_ranges(indices);
+    };
+    for result in &mut scored {
+        locate(result);
+        result.alternates.iter_mut().for_each(&mut locate);
+    }
+    scored
+}
+
+/// Collapse alternates if asked to, then cut `results` down to the limit.
+fn limit(results: Vec<SearchResult>, opts: &SearchOptions) -> Vec<SearchResult> {
+    let mut results = if opts.collapse_alternates {
+        collapse_alternates(results)
+    } else {
+        results
+    };
+    results.truncate(opts.max_results());
+    results
+}
+
+/// Move each alternate whose primary also matched into that primary's
+/// `alternates`. The pair keeps the primary's rank; alternates whose primary
+/// didn't match stay where they are.
+fn collapse_alternates(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let primary_path = |item: &FlatItem| {
+        let of = escape_title(item.alternate_of.as_deref().filter(|_| item.is_alternate)?);
+        Some(match &item.parent_path {
+            Some(parent) => format!("{parent}{PATH_SEP}{of}"),
+            None => of.into_owned(),
+        })
+    };
+
+    // First match wins when several items share a path.
+    let mut primaries: HashMap<&str, usize> = HashMap::new();
+    for (i, r) in results.iter().enumerate().rev() {
+        if !r.item.is_alternate {
+            primaries.insert(&r.item.path, i);
+        }
+    }
+    let owners: Vec<Option<usize>> = results
+        .iter()
+        .map(|r| {
+            let path = primary_path(&r.item)?;
+            primaries.get(path.as_str()).copied()
+        })
+        .collect();
+
+    let mut slots: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+    for (i, owner) in owners.iter().enumerate() {
+        if let Some(owner) = *owner {
+            if let Some(alt) = slots[i].take() {
+                if let Some(primary) = slots[owner].as_mut() {
+                    primary.alternates.push(alt);
+                }
+            }
+        }
+    }
+    slots.into_iter().flatten().collect()
+}
+
+/// Collapse matched char indices into sorted, contiguous ranges.
+fn to_ranges(mut indices: Vec<u32>) -> Vec<Range<usize>> {
+    indices.sort_unstable();
+    indices.dedup();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for i in indices {
+        let i = i as usize;
+        match ranges.last_mut() {
+            Some(last) if last.end == i => last.end = i + 1,
+            _ => ranges.push(i..i + 1),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::CheckState;
+
+    fn item(path: &str) -> FlatItem {
+        FlatItem {
+            title: path.rsplit("::").next().unwrap_or(path).to_owned(),
+            path: path.to_owned(),
+            parent_path: path.rsplit_once("::").map(|(parent, _)| parent.to_owned()),
+            enabled: true,
+            checked: false,
+            check_state: CheckState::Off,
+            radio_group: None,
+            shortcut: None,
+            virtual_key: None,
+            cmd_char: None,
+            cmd_modifiers: None,
+            cmd_glyph: None,
+            selected: None,
+            focused: None,
+            role: "AXMenuItem".to_owned(),
+            depth: path.split("::").count(),
+            index: 1,
+            id: String::new(),
+            children_count: 0,
+            descendants_count: 0,
+            enabled_descendants: 0,
+            is_alternate: false,
+            alternate_of: None,
+            help: None,
+        }
+    }
+
+    fn paths(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.item.path.as_str()).collect()
+    }
+
+    fn spans(result: &SearchResult) -> Vec<(usize, usize)> {
+        result.ranges.iter().map(|r| (r.start, r.end)).collect()
+    }
+
+    fn items() -> Vec<FlatItem> {
+        vec![
+            item("File::Export as PDF…"),
+            item("File::Export Screenshot as PDF…"),
+            item("File::Print…"),
+        ]
+    }
+
+    #[test]
+    fn test_fuzzy_and_negation() {
+        let results = search(&items(), "export pdf !screen", &SearchOptions::default());
+        assert_eq!(paths(&results), ["File::Export as PDF…"]);
+    }
+
+    #[test]
+    fn test_exact_terms_and_negation() {
+        let opts = SearchOptions {
+            exact: true,
+            ..SearchOptions::default()
+        };
+        let results = search(&items(), "pdf export", &opts);
+        assert_eq!(results.len(), 2);
+        let results = search(&items(), "pdf !screenshot", &opts);
+        assert_eq!(paths(&results), ["File::Export as PDF…"]);
+        assert_eq!(spans(&results[0]), [(16, 19)]);
+    }
+
+    #[test]
+    fn test_zero_limit_returns_every_match() {
+        for exact in [false, true] {
+            let opts = |limit| SearchOptions {
+                limit,
+                exact,
+                ..SearchOptions::default()
+            };
+            assert_eq!(search(&items(), "file", &opts(1)).len(), 1);
+            assert_eq!(search(&items(), "file", &opts(0)).len(), 3);
+            assert_eq!(search(&items(), "", &opts(0)).len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_collapse_alternates() {
+        let mut close_all = item("File::Close All");
+        close_all.is_alternate = true;
+        close_all.alternate_of = Some("Close".to_owned());
+        let items = vec![item("File::Close"), close_all, item("Window::Close Tab")];
+        let opts = |collapse_alternates| SearchOptions {
+            limit: 2,
+            collapse_alternates,
+            ..SearchOptions::default()
+        };
+
+        let results = search(&items, "close", &opts(false));
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.alternates.is_empty()));
+
+        let results = search(&items, "close", &opts(true));
+        assert_eq!(paths(&results), ["File::Close", "Window::Close Tab"]);
+        assert_eq!(paths(&results[0].alternates), ["File::Close All"]);
+        assert!(!results[0].alternates[0].ranges.is_empty());
+
+        // An alternate whose primary didn't match stays a result of its own.
+        let results = search(&items, "close all", &opts(true));
+        assert_eq!(paths(&results), ["File::Close All"]);
+    }
+
+    #[test]
+    fn test_fuzzy_match_ranges() {
+        let results = search(&items(), "print", &SearchOptions::default());
+        assert_eq!(results[0].item.path, "File::Print…");
+        assert_eq!(spans(&results[0]), [(6, 11)]);
+    }
+
+    #[test]
+    fn test_include_help() {
+        let mut items = items();
+        items[2].help = Some("Send the document to a printer".to_owned());
+        let opts = SearchOptions {
+            include_help: true,
+            ..SearchOptions::default()
+        };
+        assert!(search(&items, "printer", &SearchOptions::default()).is_empty());
+        let results = search(&items, "printer", &opts);
+        assert_eq!(paths(&results), ["File::Print…"]);
+        assert!(results[0].help_match);
+    }
+
+    #[test]
+    fn test_to_ranges_merges_runs() {
+        assert_eq!(to_ranges(vec![5, 1, 2, 2, 3]), [1..4, 5..6]);
+    }
+}
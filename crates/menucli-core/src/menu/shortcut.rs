@@ -4,6 +4,10 @@
 /// - `kAXMenuItemCmdChar`: The key character (e.g., "S", "N", "W").
 /// - `kAXMenuItemCmdModifiers`: A bitmask of modifier keys.
 ///
+/// Shortcuts on keys without a character (function keys, arrows, Delete,
+/// Escape, …) have no `kAXMenuItemCmdChar`; `kAXMenuItemCmdGlyph` names the
+/// key instead, as one of the `kMenu*Glyph` codes from Carbon's `Menus.h`.
+///
 /// Modifier bitmask (from Apple's `AXAttributeConstants.h`):
 /// - 0 = ⌘ only (default — no bit set means Command is implied)
 /// - bit 1 (0x1) = Shift (⇧)
@@ -58,6 +62,46 @@ pub fn format_shortcut(cmd_char: Option<&str>, modifiers: Option<i64>) -> Option
     Some(shortcut)
 }
 
+/// The key symbol for a `kAXMenuItemCmdGlyph` code, or `None` for glyphs
+/// that are not keys.
+#[must_use]
+pub fn glyph_key(glyph: i64) -> Option<&'static str> {
+    const F1_F12: [&str; 12] = [
+        "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+    ];
+    let key = match glyph {
+        0x02 => "⇥",
+        0x03 => "⇤",
+        0x04 => "⌤",
+        0x09 => "Space",
+        0x0A => "⌦",
+        0x0B | 0x0D => "↩",
+        0x17 => "⌫",
+        0x1B => "⎋",
+        0x1C => "⌧",
+        0x62 => "⇞",
+        0x63 => "⇪",
+        0x64 => "←",
+        0x65 => "→",
+        0x66 => "↖",
+        0x68 => "↑",
+        0x69 => "↘",
+        0x6A => "↓",
+        0x6B => "⇟",
+        0x6F..=0x7A => F1_F12[usize::try_from(glyph - 0x6F).ok()?],
+        0x87 => "F13",
+        0x88 => "F14",
+        0x89 => "F15",
+        0x8C => "⏏",
+        0x8F => "F16",
+        0x90 => "F17",
+        0x91 => "F18",
+        0x92 => "F19",
+        _ => return None,
+    };
+    Some(key)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +129,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_glyph_keys() {
+        assert_eq!(
+            format_shortcut(glyph_key(0x73), Some(0)),
+            Some("⌘F5".to_owned())
+        );
+        assert_eq!(
+            format_shortcut(glyph_key(0x17), Some(0x2 | 0x8)),
+            Some("⌥⌫".to_owned())
+        );
+        assert_eq!(glyph_key(0x92), Some("F19"));
+        assert_eq!(glyph_key(0x6A), Some("↓"));
+        assert_eq!(glyph_key(0x12), None);
+    }
+
     #[test]
     fn test_no_char() {
         assert_eq!(format_shortcut(None, Some(0)), None);
@@ -0,0 +1,1518 @@
+/// Recursive menu tree builder using the AX layer.
+///
+/// Performance strategy:
+/// 1. Use batch attribute fetching (`AXUIElementCopyMultipleAttributeValues`) to
+///    read all needed attributes per item in one IPC round-trip.
+/// 2. Walk sibling subtrees in parallel using `std::thread::scope`, at every
+///    level — a single giant menu fans out just like the menu bar does.
+/// 3. Cap the threads per app with a [`WalkBudget`] so the target app isn't
+///    flooded with concurrent AX requests; once the budget is spent, subtrees
+///    are walked on the current thread.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use accessibility_sys::{
+    kAXCancelAction, kAXPositionAttribute, kAXPressAction, kAXSizeAttribute,
+    kAXTitleChangedNotification, kAXUIElementDestroyedNotification, kAXValueChangedNotification,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::instrument;
+
+use crate::ax::app::{app_key, list_running_apps, RunningApp};
+use crate::ax::locale::english_titles;
+use crate::ax::{
+    attr_idx, AXElement, AXError, AttributeValue, HeldModifiers, Modifier, Observer,
+    DEFAULT_MESSAGING_TIMEOUT_SECS, MENU_ITEM_ATTRS,
+};
+use crate::menu::cancel::CancelToken;
+use crate::menu::id::assign_ids;
+use crate::menu::resolve::{find_segment, ResolveOptions};
+use crate::menu::shortcut::{format_shortcut, glyph_key};
+
+use super::errors::MenuError;
+
+/// Most threads walking one app's menu tree at once.
+const MAX_WALKERS_PER_APP: usize = 8;
+
+/// How long [`TreeOptions::open_menus`] waits for an opened submenu to fill.
+const OPEN_MENU_WAIT: Duration = Duration::from_millis(200);
+
+/// Submenus apps rebuild every time they are shown, so the items AX reports
+/// while they are closed can be stale: recent documents, open windows,
+/// services, and scripts.
+const DYNAMIC_SUBMENUS: &[&str] = &["Open Recent", "Window", "Services", "Scripts"];
+
+/// Apps whose extras are walked at once by [`sweep_extras`] by default.
+pub const DEFAULT_EXTRAS_JOBS: usize = 8;
+
+/// AX messaging timeout per app while sweeping all apps' extras, unless
+/// [`TreeOptions::ax_timeout`] overrides it.
+pub const EXTRAS_SWEEP_TIMEOUT_SECS: f32 = 0.25;
+
+/// Path separator used in full item paths.
+///
+/// Double-colon `::` is compact, shell-friendly (no quoting needed for simple
+/// paths like `File::Save`), and unambiguous in practice — real menu titles
+/// almost never contain `::`. When they do, `escape_title` replaces the
+/// literal `::` with `\::` so round-tripping is lossless.
+pub const PATH_SEP: &str = "::";
+
+/// Escape literal `::` in a menu title so it won't be confused with [`PATH_SEP`].
+///
+/// Titles containing `::` (extremely rare) get it replaced with `\::`.
+/// Titles without `::` are returned as-is (zero allocation).
+#[must_use]
+pub fn escape_title(title: &str) -> std::borrow::Cow<'_, str> {
+    if title.contains(PATH_SEP) {
+        std::borrow::Cow::Owned(title.replace("::", "\\::"))
+    } else {
+        std::borrow::Cow::Borrowed(title)
+    }
+}
+
+/// Split a full menu path on the unescaped `::` separator.
+///
+/// `\::` inside a segment is preserved (not treated as a split point).
+/// After splitting, call [`unescape_segment`] on each piece to get the raw title.
+#[must_use]
+pub fn split_path(path: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let bytes = path.as_bytes();
+    let len = bytes.len();
+    let mut i = 0;
+
+    while i < len {
+        if i + 2 <= len && &bytes[i..i + 2] == b"::" {
+            // Check if preceded by backslash (escaped).
+            if i > 0 && bytes[i - 1] == b'\\' {
+                // Escaped `\::` — skip, not a separator.
+                i += 2;
+            } else {
+                segments.push(&path[start..i]);
+                i += 2;
+                start = i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    segments.push(&path[start..]);
+    segments
+}
+
+/// The path of the item containing the one at `path`, or `None` for a
+/// top-level menu. Escaped `\::` in titles is not a split point.
+#[must_use]
+pub fn parent_path(path: &str) -> Option<&str> {
+    match split_path(path).as_slice() {
+        [] | [_] => None,
+        [.., last] => Some(&path[..path.len() - last.len() - PATH_SEP.len()]),
+    }
+}
+
+/// Remove escape sequences from a single path segment.
+///
+/// Converts `\::` back to `::`.
+#[must_use]
+pub fn unescape_segment(seg: &str) -> std::borrow::Cow<'_, str> {
+    if seg.contains("\\::") {
+        std::borrow::Cow::Owned(seg.replace("\\::", "::"))
+    } else {
+        std::borrow::Cow::Borrowed(seg)
+    }
+}
+
+/// A menu item's checkmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckState {
+    /// No mark.
+    #[default]
+    Off,
+    /// A checkmark (or any mark other than a dash).
+    On,
+    /// A dash: the setting is on for only part of the selection.
+    Mixed,
+}
+
+impl CheckState {
+    /// Read from `kAXMenuItemMarkChar`.
+    #[must_use]
+    pub fn from_mark(mark: Option<&str>) -> Self {
+        match mark.map(str::trim) {
+            None | Some("") => Self::Off,
+            Some("–" | "-" | "−") => Self::Mixed,
+            Some(_) => Self::On,
+        }
+    }
+
+    /// Whether `kAXMenuItemMarkChar` is the bullet macOS shows for the chosen
+    /// item of a radio group.
+    #[must_use]
+    pub fn is_radio_mark(mark: Option<&str>) -> bool {
+        mark.map(str::trim) == Some("•")
+    }
+
+    /// `On` if `checked`, else `Off`.
+    #[must_use]
+    pub fn from_checked(checked: bool) -> Self {
+        if checked {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+}
+
+/// A node in the menu tree.
+///
+/// Serializable for caching; the AX element is not, so a deserialized node
+/// cannot be pressed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuNode {
+    /// Display title of the item (e.g., "Save As…").
+    pub title: String,
+    /// Full path from root (e.g., "File::Save As…").
+    pub path: String,
+    /// Whether the item is enabled (clickable).
+    pub enabled: bool,
+    /// Whether the item has a checkmark (toggle state = on).
+    pub checked: bool,
+    /// The mark itself, telling a mixed state apart from on; `checked` is
+    /// true for both.
+    #[serde(default)]
+    pub check_state: CheckState,
+    /// For a radio-style item, the group it is one choice of: the parent path
+    /// and the 1-based separator-delimited section, e.g. `View::Sort By#1`.
+    #[serde(default)]
+    pub radio_group: Option<String>,
+    /// Whether the app marks `radio_group` with bullets, rather than it being
+    /// inferred from a single checkmark among submenu leaves.
+    #[serde(default)]
+    pub radio_bulleted: bool,
+    /// Formatted keyboard shortcut (e.g., "⇧⌘S"), if any.
+    pub shortcut: Option<String>,
+    /// Virtual key code of the shortcut's key (`kVK_*`), if it has one.
+    pub virtual_key: Option<u16>,
+    /// The shortcut's key character as AX reports it, before formatting;
+    /// `None` for keys named by [`cmd_glyph`](Self::cmd_glyph).
+    #[serde(default)]
+    pub cmd_char: Option<String>,
+    /// The shortcut's raw modifier mask (see [`format_shortcut`]).
+    #[serde(default)]
+    pub cmd_modifiers: Option<u8>,
+    /// The `kMenu*Glyph` code of a shortcut key without a character.
+    #[serde(default)]
+    pub cmd_glyph: Option<u16>,
+    /// Whether the item is highlighted; `None` where AX does not report it.
+    pub selected: Option<bool>,
+    /// Whether the item has keyboard focus; `None` where AX does not report it.
+    pub focused: Option<bool>,
+    /// AX role string (e.g., "AXMenuBarItem", "AXMenuItem").
+    pub role: String,
+    /// Depth from root (menu bar = 0, top-level items = 1, submenu items = 2+).
+    pub depth: usize,
+    /// 1-based position among its siblings, addressable as `#N` in paths.
+    pub index: usize,
+    /// Stable content-hash ID (see [`item_id`](super::id::item_id)).
+    pub id: String,
+    /// Child nodes (empty for leaf items).
+    pub children: Vec<MenuNode>,
+    /// The underlying AX element, kept for `click` / `toggle` operations.
+    /// `None` only in unit-test fixtures that never call press/toggle.
+    #[serde(skip)]
+    pub element: Option<AXElement>,
+    /// Whether this item is an Option-key alternate of another item.
+    pub is_alternate: bool,
+    /// If this item is an alternate, the title of the primary item it replaces.
+    pub alternate_of: Option<String>,
+    /// Help (tooltip) text, if the app provides any.
+    pub help: Option<String>,
+    /// Locale-independent names (`AXIdentifier`, English title) that path
+    /// segments also match. Empty unless [`TreeOptions::locale_aliases`] is set.
+    pub aliases: Vec<String>,
+}
+
+/// Options for tree building.
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    /// Whether to include alternate (Option-key) items in the output.
+    /// Alternates are always detected internally; this controls filtering.
+    pub include_alternates: bool,
+    /// Record each item's identifier and English title as [`MenuNode::aliases`],
+    /// so scripts written against English menus resolve on localized systems.
+    pub locale_aliases: bool,
+    /// Only walk the top-level menus with these titles (case-insensitive);
+    /// empty walks them all. Skipped menus cost no AX calls.
+    pub menus: Vec<String>,
+    /// Never walk the top-level menus with these titles (case-insensitive),
+    /// even if [`menus`](Self::menus) names them.
+    pub exclude_menus: Vec<String>,
+    /// Per-element AX messaging timeout in seconds; `None` uses
+    /// [`DEFAULT_MESSAGING_TIMEOUT_SECS`].
+    pub ax_timeout: Option<f32>,
+    /// Counters updated as items are walked, for a live progress display.
+    pub progress: Option<Arc<WalkProgress>>,
+    /// Stops the walk early when cancelled; the builders then return the
+    /// partial tree read so far.
+    pub cancel: CancelToken,
+    /// Apps walked at once when building every app's extras; `None` uses
+    /// [`DEFAULT_EXTRAS_JOBS`].
+    pub extras_jobs: Option<usize>,
+    /// Open submenus that report no items so apps that fill them lazily
+    /// populate them, and re-read [dynamic](is_dynamic_submenu) ones after
+    /// opening them, then close them again. Walks the tree on one thread,
+    /// since only one menu can be open at a time.
+    pub open_menus: bool,
+}
+
+impl TreeOptions {
+    /// The AX messaging timeout to set on application elements.
+    #[must_use]
+    pub fn messaging_timeout(&self) -> f32 {
+        self.ax_timeout.unwrap_or(DEFAULT_MESSAGING_TIMEOUT_SECS)
+    }
+
+    /// Whether the top-level menu titled `title` should be walked.
+    #[must_use]
+    pub fn wants_menu(&self, title: &str) -> bool {
+        let named = |list: &[String]| {
+            list.iter()
+                .any(|m| m.to_lowercase() == title.to_lowercase())
+        };
+        (self.menus.is_empty() || named(&self.menus)) && !named(&self.exclude_menus)
+    }
+
+    /// Whether any top-level menus are skipped (`menus` or `exclude_menus`).
+    #[must_use]
+    pub fn filters_menus(&self) -> bool {
+        !self.menus.is_empty() || !self.exclude_menus.is_empty()
+    }
+
+    /// The thread budget for walking one app's menus.
+    fn walk_budget(&self) -> WalkBudget {
+        WalkBudget::new(if self.open_menus {
+            0
+        } else {
+            MAX_WALKERS_PER_APP
+        })
+    }
+}
+
+/// Live counters for a tree walk, shared with whoever displays progress.
+///
+/// Every walker thread bumps the item count as it reads an item, so a reader
+/// on another thread can poll [`items`](Self::items) while the walk runs.
+#[derive(Debug, Default)]
+pub struct WalkProgress {
+    items: AtomicUsize,
+    menu: Mutex<String>,
+}
+
+impl WalkProgress {
+    /// Items read so far.
+    #[must_use]
+    pub fn items(&self) -> usize {
+        self.items.load(Ordering::Relaxed)
+    }
+
+    /// Title of the top-level menu most recently entered.
+    #[must_use]
+    pub fn menu(&self) -> String {
+        self.menu.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    fn record(&self, depth: usize, title: &str) {
+        self.items.fetch_add(1, Ordering::Relaxed);
+        if depth == 1 {
+            if let Ok(mut menu) = self.menu.lock() {
+                title.clone_into(&mut menu);
+            }
+        }
+    }
+}
+
+/// Build the full menu tree for an application, given its PID.
+///
+/// Convenience wrapper around [`build_tree_with_opts`] with alternates excluded.
+/// Submenus are walked in parallel threads, a bounded number per app.
+///
+/// # Errors
+///
+/// Returns `MenuError` if the AX API fails or permissions are missing.
+#[allow(dead_code)]
+pub fn build_tree(pid: i32, max_depth: Option<usize>) -> Result<Vec<MenuNode>, MenuError> {
+    build_tree_with_opts(
+        pid,
+        max_depth,
+        &TreeOptions {
+            include_alternates: false,
+            locale_aliases: false,
+            menus: Vec::new(),
+            exclude_menus: Vec::new(),
+            ax_timeout: None,
+            progress: None,
+            cancel: CancelToken::default(),
+            extras_jobs: None,
+            open_menus: false,
+        },
+    )
+}
+
+/// Build the full menu tree with options controlling alternate item inclusion.
+///
+/// # Errors
+///
+/// Returns `MenuError` if the AX API fails or permissions are missing.
+#[instrument(level = "debug", skip(opts))]
+pub fn build_tree_with_opts(
+    pid: i32,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+) -> Result<Vec<MenuNode>, MenuError> {
+    let app = AXElement::application(pid, opts.messaging_timeout());
+    let menubar = app.menu_bar()?;
+    let top_level = menubar.children()?;
+
+    if top_level.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Remember each menu's position in the full menu bar before filtering.
+    let (positions, elements): (Vec<usize>, Vec<AXElement>) = top_level
+        .into_iter()
+        .enumerate()
+        .filter(|(_, element)| !opts.filters_menus() || opts.wants_menu(&element_title(element)))
+        .unzip();
+
+    let budget = opts.walk_budget();
+    let mut nodes: Vec<MenuNode> = positions
+        .into_iter()
+        .zip(walk_siblings(elements, "", 1, max_depth, opts, &budget))
+        .filter_map(|(i, node)| node.ok().map(|n| MenuNode { index: i + 1, ..n }))
+        .collect();
+    if !opts.filters_menus() {
+        number_siblings(&mut nodes);
+    }
+    assign_ids(&mut nodes, &app_key(pid));
+    if opts.locale_aliases {
+        add_english_aliases(&mut nodes, &english_titles(pid));
+    }
+    Ok(nodes)
+}
+
+/// Build only the branch of the app menu tree that the exact `::` path runs
+/// through, for resolving it without walking the whole menu bar.
+///
+/// Every level along the path is listed in full (so `#N` segments and aliases
+/// resolve as in a full build), but only the item matching each segment is
+/// descended into. The item the path ends at gets its whole subtree. If a
+/// segment matches nothing, the branch simply stops there.
+///
+/// # Errors
+///
+/// Returns `MenuError` if the AX API fails or permissions are missing.
+#[instrument(level = "debug", skip(resolve_opts, opts))]
+pub fn build_branch(
+    pid: i32,
+    path: &str,
+    resolve_opts: &ResolveOptions,
+    opts: &TreeOptions,
+) -> Result<Vec<MenuNode>, MenuError> {
+    let app = AXElement::application(pid, opts.messaging_timeout());
+    let menubar = app.menu_bar()?;
+    let english = if opts.locale_aliases {
+        english_titles(pid)
+    } else {
+        HashMap::new()
+    };
+    let budget = opts.walk_budget();
+
+    let mut nodes: Vec<MenuNode> = menubar
+        .children()?
+        .into_iter()
+        .filter_map(|element| walk_element(element, String::new(), 1, Some(1), opts, &budget).ok())
+        .collect();
+    number_siblings(&mut nodes);
+    nodes.retain(|node| opts.wants_menu(&node.title));
+    add_english_aliases(&mut nodes, &english);
+
+    let segments = split_path(path);
+    let mut level = &mut nodes;
+    for (i, segment) in segments.iter().enumerate() {
+        let Some(pos) = find_segment(level, segment, resolve_opts) else {
+            break;
+        };
+        let node = &mut level[pos];
+        if let Some(element) = node.element.clone() {
+            let is_target = i + 1 == segments.len();
+            let max_depth = (!is_target).then_some(node.depth + 1);
+            node.children =
+                collect_children(&element, &node.path, node.depth, max_depth, opts, &budget);
+            add_english_aliases(&mut node.children, &english);
+        }
+        level = &mut node.children;
+    }
+
+    assign_ids(&mut nodes, &app_key(pid));
+    Ok(nodes)
+}
+
+/// Read [`MENU_ITEM_ATTRS`] from every element under `pid`'s menu bar on the
+/// current thread, and return how many elements were read.
+///
+/// With `batch`, each element costs one `AXUIElementCopyMultipleAttributeValues`
+/// call, as in [`build_tree_with_opts`]; without it, one call per attribute.
+/// Children are listed the same way in both modes, so timing the two measures
+/// exactly what batching saves.
+///
+/// # Errors
+///
+/// Returns `MenuError` if the AX API fails, or `MenuError::Cancelled`.
+#[instrument(level = "debug", skip(opts))]
+pub fn fetch_attributes(pid: i32, batch: bool, opts: &TreeOptions) -> Result<usize, MenuError> {
+    let app = AXElement::application(pid, opts.messaging_timeout());
+    let mut pending = app.menu_bar()?.children()?;
+    let mut count = 0;
+    while let Some(element) = pending.pop() {
+        opts.cancel.check()?;
+        if batch {
+            element.batch_attributes(MENU_ITEM_ATTRS)?;
+        } else {
+            for attr in MENU_ITEM_ATTRS {
+                element.attribute(attr)?;
+            }
+        }
+        count += 1;
+        pending.extend(element.children().unwrap_or_default());
+    }
+    Ok(count)
+}
+
+/// A built menu tree for one application.
+///
+/// This is the main entry point for library users: build it once, then resolve
+/// and press items by path or fuzzy query.
+///
+/// ```no_run
+/// use menucli_core::MenuTree;
+///
+/// let tree = MenuTree::build(1234)?;
+/// let item = tree.resolve("File::Save As…")?;
+/// println!("{} (enabled: {})", item.path, item.enabled);
+/// tree.press("save as")?;
+/// # Ok::<(), menucli_core::MenuError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MenuTree {
+    /// PID of the application the tree was built from.
+    pub pid: i32,
+    /// Top-level nodes (menu bar items, or status items for extras trees).
+    pub nodes: Vec<MenuNode>,
+}
+
+impl MenuTree {
+    /// Build the full app menu tree for `pid`, excluding alternate items.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the AX API fails or permissions are missing.
+    pub fn build(pid: i32) -> Result<Self, MenuError> {
+        Self::build_with(pid, None, &TreeOptions::default())
+    }
+
+    /// Build the app menu tree for `pid` with a depth limit and options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the AX API fails or permissions are missing.
+    pub fn build_with(
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Self, MenuError> {
+        let nodes = build_tree_with_opts(pid, max_depth, opts)?;
+        Ok(Self { pid, nodes })
+    }
+
+    /// Build the status bar (menu extras) tree for `pid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError` if the AX API fails or the app has no extras.
+    pub fn build_extras(
+        pid: i32,
+        max_depth: Option<usize>,
+        opts: &TreeOptions,
+    ) -> Result<Self, MenuError> {
+        let nodes = build_extras_tree(pid, max_depth, opts)?;
+        Ok(Self { pid, nodes })
+    }
+
+    /// Resolve an exact `::` path or a fuzzy query to a single node.
+    ///
+    /// See [`resolve`](crate::menu::resolve::resolve) for the strategy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::ItemNotFound` or `MenuError::AmbiguousMatch`.
+    pub fn resolve(&self, query: &str) -> Result<&MenuNode, MenuError> {
+        super::resolve::resolve(&self.nodes, query)
+    }
+
+    /// Resolve `query` and press the resulting item, returning it.
+    ///
+    /// # Errors
+    ///
+    /// Returns resolution errors, `MenuError::ItemDisabled`, or AX failures.
+    pub fn press(&self, query: &str) -> Result<&MenuNode, MenuError> {
+        let node = self.resolve(query)?;
+        press_node(node)?;
+        Ok(node)
+    }
+
+    /// Flatten the tree into pre-order `FlatItem`s.
+    #[must_use]
+    pub fn flatten(&self) -> Vec<super::flatten::FlatItem> {
+        super::flatten::flatten(&self.nodes)
+    }
+}
+
+/// Recursively walk a menu element and its children.
+#[instrument(level = "trace", skip_all, fields(depth = depth))]
+fn walk_element(
+    element: AXElement,
+    parent_path: String,
+    depth: usize,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+    budget: &WalkBudget,
+) -> Result<MenuNode, MenuError> {
+    opts.cancel.check()?;
+
+    // Batch-fetch all needed attributes in one IPC call.
+    let attrs = element.batch_attributes(MENU_ITEM_ATTRS)?;
+
+    let title = extract_string(&attrs, attr_idx::TITLE).unwrap_or_default();
+    if let Some(progress) = &opts.progress {
+        progress.record(depth, &title);
+    }
+    let enabled = extract_bool(&attrs, attr_idx::ENABLED).unwrap_or(true);
+    let mark_char = extract_string(&attrs, attr_idx::MARK_CHAR);
+    let cmd_char = extract_string(&attrs, attr_idx::CMD_CHAR).filter(|c| !c.trim().is_empty());
+    let cmd_glyph = extract_number(&attrs, attr_idx::CMD_GLYPH).filter(|&g| g != 0);
+    let cmd_key = cmd_char
+        .clone()
+        .or_else(|| cmd_glyph.and_then(glyph_key).map(str::to_owned));
+    let cmd_mods = extract_number(&attrs, attr_idx::CMD_MODIFIERS);
+    let role = extract_string(&attrs, attr_idx::ROLE).unwrap_or_default();
+    let help = extract_string(&attrs, attr_idx::HELP).filter(|h| !h.is_empty());
+    let selected = extract_bool(&attrs, attr_idx::SELECTED);
+    let focused = extract_bool(&attrs, attr_idx::FOCUSED);
+    let aliases = extract_string(&attrs, attr_idx::IDENTIFIER)
+        .filter(|id| opts.locale_aliases && !id.is_empty())
+        .into_iter()
+        .collect();
+
+    // Detect alternate items: if PRIMARY_UI_ELEMENT is present (non-None),
+    // this item is an Option-key alternate of another item.
+    let is_alternate = attrs
+        .get(attr_idx::PRIMARY_UI_ELEMENT)
+        .is_some_and(|v| v.is_some());
+
+    // A checkmark is indicated by a non-empty mark character ("✓", or "–" for mixed).
+    let check_state = CheckState::from_mark(mark_char.as_deref());
+    let checked = check_state != CheckState::Off;
+    // Placeholder until `mark_radio_group` sees the siblings and names the group.
+    let radio_group = CheckState::is_radio_mark(mark_char.as_deref()).then(String::new);
+
+    let shortcut = format_shortcut(cmd_key.as_deref(), cmd_mods);
+    let virtual_key = extract_number(&attrs, attr_idx::CMD_VIRTUAL_KEY)
+        .filter(|_| shortcut.is_some())
+        .and_then(|k| u16::try_from(k).ok());
+    // The raw components, kept for tools that map shortcuts to other formats.
+    let cmd_modifiers = cmd_mods
+        .filter(|_| shortcut.is_some())
+        .and_then(|m| u8::try_from(m).ok());
+    let cmd_glyph = cmd_glyph
+        .filter(|_| shortcut.is_some() && cmd_char.is_none())
+        .and_then(|g| u16::try_from(g).ok());
+
+    let escaped = escape_title(&title);
+    let path = if parent_path.is_empty() {
+        escaped.into_owned()
+    } else {
+        format!("{parent_path}{PATH_SEP}{escaped}")
+    };
+
+    // Recurse into children unless at max depth.
+    let children = if max_depth.is_none_or(|max| depth < max) {
+        collect_children(&element, &path, depth, max_depth, opts, budget)
+    } else {
+        Vec::new()
+    };
+
+    Ok(MenuNode {
+        title,
+        path,
+        enabled,
+        checked,
+        check_state,
+        radio_group,
+        radio_bulleted: false,
+        shortcut,
+        virtual_key,
+        cmd_char,
+        cmd_modifiers,
+        cmd_glyph,
+        selected,
+        focused,
+        role,
+        depth,
+        index: 0,          // Assigned by the parent once siblings are known
+        id: String::new(), // Assigned once the whole tree is built
+        children,
+        element: Some(element),
+        is_alternate,
+        alternate_of: None, // Populated during collect_children
+        help,
+        aliases,
+    })
+}
+
+/// Collect child menu nodes from an element.
+///
+/// AXMenu containers (role = "AXMenu") are transparent: we skip the container
+/// node itself and recurse directly into its children. This handles the macOS
+/// AX menu hierarchy:
+///
+/// ```text
+/// AXMenuBarItem ("File")
+///   └── AXMenu          ← transparent container, skipped
+///         ├── AXMenuItem ("New")
+///         ├── AXMenuItem ("Open")
+///         └── AXMenuItem ("Save")
+///               └── AXMenu    ← nested submenu container, also skipped
+///                     └── AXMenuItem ("Save As…")
+/// ```
+fn collect_children(
+    element: &AXElement,
+    parent_path: &str,
+    parent_depth: usize,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+    budget: &WalkBudget,
+) -> Vec<MenuNode> {
+    if opts.cancel.is_cancelled() {
+        return Vec::new();
+    }
+    let child_elements = match element.children() {
+        Ok(children) => children,
+        Err(_) => return Vec::new(),
+    };
+
+    // Unwrap AXMenu containers first (`Some` slots), so the real items (`None`
+    // slots) can be walked concurrently and then merged back in order.
+    let mut slots: Vec<Option<Vec<MenuNode>>> = Vec::with_capacity(child_elements.len());
+    let mut items: Vec<AXElement> = Vec::with_capacity(child_elements.len());
+    for child in child_elements {
+        // Peek at the role to detect AXMenu containers.
+        let role = child
+            .batch_attributes(&[accessibility_sys::kAXRoleAttribute])
+            .ok()
+            .and_then(|a| extract_string(&a, 0));
+
+        if role.as_deref() == Some("AXMenu") {
+            // AXMenu is a transparent container — recurse through it without
+            // incrementing depth or creating a node.
+            // Items a dynamic submenu lists while closed are not worth reading.
+            let reopen = opts.open_menus && owner_is_dynamic(parent_path);
+            let mut grandchildren = if reopen {
+                Vec::new()
+            } else {
+                collect_children(&child, parent_path, parent_depth, max_depth, opts, budget)
+            };
+            if grandchildren.is_empty() && opts.open_menus {
+                grandchildren = collect_opened(
+                    element,
+                    &child,
+                    parent_path,
+                    parent_depth,
+                    max_depth,
+                    opts,
+                    budget,
+                );
+            }
+            slots.push(Some(grandchildren));
+        } else {
+            slots.push(None);
+            items.push(child);
+        }
+    }
+    let mut walked = walk_siblings(
+        items,
+        parent_path,
+        parent_depth + 1,
+        max_depth,
+        opts,
+        budget,
+    )
+    .into_iter();
+
+    let mut child_nodes: Vec<MenuNode> = Vec::with_capacity(slots.len());
+    // Track the last non-alternate item title so we can set `alternate_of`.
+    let mut last_primary_title: Option<String> = None;
+    // Ranges of `child_nodes` between separators, for radio group detection.
+    let mut sections: Vec<(usize, usize)> = Vec::new();
+    let mut section_start = 0;
+
+    for slot in slots {
+        if let Some(grandchildren) = slot {
+            // Already grouped by the recursive call.
+            sections.push((section_start, child_nodes.len()));
+            child_nodes.extend(grandchildren);
+            section_start = child_nodes.len();
+            // Reset last_primary_title since we merged grandchildren.
+            last_primary_title = None;
+        } else if let Some(Ok(mut node)) = walked.next() {
+            // Skip separator items (empty title or role AXSeparator).
+            if node.title.is_empty() || node.role == "AXSeparator" {
+                sections.push((section_start, child_nodes.len()));
+                section_start = child_nodes.len();
+            } else if node.is_alternate {
+                // Set alternate_of to the last primary item's title.
+                node.alternate_of = last_primary_title.clone();
+                if opts.include_alternates {
+                    child_nodes.push(node);
+                }
+                // Don't update last_primary_title for alternates.
+            } else {
+                last_primary_title = Some(node.title.clone());
+                child_nodes.push(node);
+            }
+        }
+    }
+    sections.push((section_start, child_nodes.len()));
+
+    let own_sections = sections.into_iter().filter(|(start, end)| start < end);
+    for (i, (start, end)) in own_sections.enumerate() {
+        let group = format!("{parent_path}#{}", i + 1);
+        mark_radio_group(&mut child_nodes[start..end], &group);
+    }
+    number_siblings(&mut child_nodes);
+    child_nodes
+}
+
+/// Mark one separator-delimited run of sibling leaves as the radio group
+/// `group`, or clear the placeholders `walk_element` left on bulleted items.
+///
+/// A run is a radio group when any item shows a bullet (`•`), or when it sits
+/// in a submenu (depth 3+), has two or more leaves and none with submenus, and
+/// exactly one of them is checked — how apps using plain checkmarks present a
+/// choice such as "Sort By". Top-level menus are left out of the second rule,
+/// since unrelated settings there often happen to have one checkmark between
+/// them. Only the first rule sets [`MenuNode::radio_bulleted`].
+pub(crate) fn mark_radio_group(section: &mut [MenuNode], group: &str) {
+    let primaries = || section.iter().filter(|n| !n.is_alternate);
+    let bulleted = primaries().any(|n| n.radio_group.is_some());
+    let single_choice = primaries().count() >= 2
+        && primaries().all(|n| n.depth >= 3 && n.children.is_empty())
+        && primaries()
+            .filter(|n| n.check_state == CheckState::On)
+            .count()
+            == 1;
+    let radio = bulleted || single_choice;
+    for node in section.iter_mut() {
+        node.radio_group =
+            (radio && !node.is_alternate && node.children.is_empty()).then(|| group.to_owned());
+        node.radio_bulleted = bulleted && node.radio_group.is_some();
+    }
+}
+
+/// Whether `title` names a submenu whose items are only current once shown.
+#[must_use]
+pub fn is_dynamic_submenu(title: &str) -> bool {
+    DYNAMIC_SUBMENUS
+        .iter()
+        .any(|d| d.eq_ignore_ascii_case(title.trim()))
+}
+
+/// Whether the `::` path `query` runs through a dynamic submenu, so building
+/// its tree should open that submenu (see [`TreeOptions::open_menus`]).
+#[must_use]
+pub fn names_dynamic_submenu(query: &str) -> bool {
+    let segments = split_path(query);
+    let parents = segments.len().saturating_sub(1);
+    segments[..parents]
+        .iter()
+        .any(|seg| is_dynamic_submenu(&unescape_segment(seg)))
+}
+
+/// Whether the item at `path` owns a dynamic submenu.
+fn owner_is_dynamic(path: &str) -> bool {
+    split_path(path)
+        .last()
+        .is_some_and(|seg| is_dynamic_submenu(&unescape_segment(seg)))
+}
+
+/// Open the submenu `menu` by pressing the item that owns it, collect
+/// the items the app fills it with, then close it again. Lazily built
+/// submenus are empty until shown, and [dynamic](is_dynamic_submenu) ones
+/// may list stale items.
+fn collect_opened(
+    owner: &AXElement,
+    menu: &AXElement,
+    parent_path: &str,
+    parent_depth: usize,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+    budget: &WalkBudget,
+) -> Vec<MenuNode> {
+    if owner.perform_action(kAXPressAction).is_err() {
+        return Vec::new();
+    }
+    // Closes the menu however the walk below ends.
+    let _close = CloseMenu(menu);
+
+    let deadline = Instant::now() + OPEN_MENU_WAIT;
+    while menu.children().is_ok_and(|c| c.is_empty()) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    collect_children(menu, parent_path, parent_depth, max_depth, opts, budget)
+}
+
+/// Cancels (closes) a menu opened by [`collect_opened`] when dropped.
+struct CloseMenu<'a>(&'a AXElement);
+
+impl Drop for CloseMenu<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.perform_action(kAXCancelAction);
+    }
+}
+
+/// Walk sibling elements at `depth`, handing each subtree to a new thread
+/// while `budget` has room and walking it on this thread otherwise. Results
+/// keep the input order.
+fn walk_siblings(
+    elements: Vec<AXElement>,
+    parent_path: &str,
+    depth: usize,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+    budget: &WalkBudget,
+) -> Vec<Result<MenuNode, MenuError>> {
+    std::thread::scope(|s| {
+        let walks: Vec<Walk<'_>> = elements
+            .into_iter()
+            .map(|element| {
+                let path = parent_path.to_owned();
+                match budget.acquire() {
+                    Some(permit) => Walk::Spawned(s.spawn(move || {
+                        let _permit = permit;
+                        walk_element(element, path, depth, max_depth, opts, budget)
+                    })),
+                    None => Walk::Done(Box::new(walk_element(
+                        element, path, depth, max_depth, opts, budget,
+                    ))),
+                }
+            })
+            .collect();
+        walks
+            .into_iter()
+            .map(|walk| match walk {
+                Walk::Spawned(handle) => handle
+                    .join()
+                    .unwrap_or(Err(MenuError::AX(AXError::InvalidElement))),
+                Walk::Done(node) => *node,
+            })
+            .collect()
+    })
+}
+
+/// A sibling subtree being walked on its own thread, or already walked.
+enum Walk<'scope> {
+    Spawned(std::thread::ScopedJoinHandle<'scope, Result<MenuNode, MenuError>>),
+    Done(Box<Result<MenuNode, MenuError>>),
+}
+
+/// Caps the extra threads walking one app's menu tree.
+struct WalkBudget {
+    free: AtomicUsize,
+}
+
+impl WalkBudget {
+    fn new(threads: usize) -> Self {
+        Self {
+            free: AtomicUsize::new(threads),
+        }
+    }
+
+    /// Take a thread slot, if any is free; it is returned when the permit drops.
+    fn acquire(&self) -> Option<WalkPermit<'_>> {
+        self.free
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .ok()
+            .map(|_| WalkPermit(self))
+    }
+}
+
+/// A thread slot taken from a [`WalkBudget`].
+struct WalkPermit<'a>(&'a WalkBudget);
+
+impl Drop for WalkPermit<'_> {
+    fn drop(&mut self) {
+        self.0.free.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// Set each node's 1-based `index` among its (visible) siblings.
+pub(crate) fn number_siblings(nodes: &mut [MenuNode]) {
+    for (i, node) in nodes.iter_mut().enumerate() {
+        node.index = i + 1;
+    }
+}
+
+/// Add each node's English title (looked up by its localized title) to its aliases.
+fn add_english_aliases(nodes: &mut [MenuNode], english: &HashMap<String, String>) {
+    if english.is_empty() {
+        return;
+    }
+    for node in nodes {
+        if let Some(title) = english.get(&node.title) {
+            node.aliases.push(title.clone());
+        }
+        add_english_aliases(&mut node.children, english);
+    }
+}
+
+/// Perform the AX press action on a `MenuNode`.
+///
+/// # Errors
+///
+/// Returns `MenuError::ItemDisabled` if the item is disabled.
+/// Returns `MenuError::AX` for underlying AX failures.
+pub fn press_node(node: &MenuNode) -> Result<(), MenuError> {
+    press_node_with(node, &[])
+}
+
+/// The modifiers held while pressing `node`: `requested`, plus Option for an
+/// alternate, since apps check the live modifier state when the press
+/// arrives and without Option many run the primary item instead.
+#[must_use]
+pub fn press_modifiers(node: &MenuNode, requested: &[Modifier]) -> Vec<Modifier> {
+    let mut keys: Vec<Modifier> = Vec::with_capacity(requested.len() + 1);
+    let alternate = node.is_alternate.then_some(Modifier::Option);
+    for &key in requested.iter().chain(&alternate) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Like [`press_node`], holding [`press_modifiers`] down during the press.
+///
+/// # Errors
+///
+/// Returns `MenuError::ItemDisabled` if the item is disabled,
+/// `MenuError::AX(AXError::EventFailed)` if the keys cannot be held, and
+/// `MenuError::AX` for other AX failures.
+#[instrument(level = "debug", skip_all, fields(path = %node.path))]
+pub fn press_node_with(node: &MenuNode, modifiers: &[Modifier]) -> Result<(), MenuError> {
+    if !node.enabled {
+        return Err(MenuError::ItemDisabled {
+            path: node.path.clone(),
+        });
+    }
+    let element = node
+        .element
+        .as_ref()
+        .ok_or(MenuError::AX(crate::ax::errors::AXError::InvalidElement))?;
+    let keys = press_modifiers(node, modifiers);
+    let _held = (!keys.is_empty())
+        .then(|| HeldModifiers::hold(&keys))
+        .transpose()?;
+    // SAFETY: kAXPressAction is a valid action constant.
+    element.perform_action(kAXPressAction)?;
+    Ok(())
+}
+
+/// The AX actions `node`'s element supports (`AXPress`, `AXCancel`, …).
+///
+/// # Errors
+///
+/// Returns `MenuError::AX(AXError::InvalidElement)` if the node has no live
+/// element, or other AX failures.
+pub fn node_actions(node: &MenuNode) -> Result<Vec<String>, MenuError> {
+    let element = node
+        .element
+        .as_ref()
+        .ok_or(MenuError::AX(AXError::InvalidElement))?;
+    Ok(element.action_names()?)
+}
+
+/// Perform the named AX action on `node`, after checking that its element
+/// supports it. Pressing a disabled item fails as [`press_node`] does.
+///
+/// # Errors
+///
+/// Returns `MenuError::ActionUnsupported` if the element does not list the
+/// action, `MenuError::ItemDisabled` for `AXPress` on a disabled item, and
+/// `MenuError::AX` for AX failures.
+#[instrument(level = "debug", skip_all, fields(path = %node.path, action))]
+pub fn perform_node_action(node: &MenuNode, action: &str) -> Result<(), MenuError> {
+    let supported = node_actions(node)?;
+    check_action(node, action, supported)?;
+    if action == kAXPressAction {
+        return press_node(node);
+    }
+    let element = node
+        .element
+        .as_ref()
+        .ok_or(MenuError::AX(AXError::InvalidElement))?;
+    element.perform_action(action)?;
+    Ok(())
+}
+
+/// Where an open menu is on screen, in global display coordinates: points
+/// from the top-left corner of the main display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MenuFrame {
+    /// Left edge, in points from the left of the main display.
+    pub x: f64,
+    /// Top edge, in points down from the top of the main display.
+    pub y: f64,
+    /// Width in points.
+    pub width: f64,
+    /// Height in points.
+    pub height: f64,
+}
+
+/// The on-screen frame of the menu `node` opens, once it is open; `None`
+/// if `node` has no submenu or it is not showing.
+///
+/// # Errors
+///
+/// Returns `MenuError::AX(AXError::InvalidElement)` if the node has no live
+/// element, or other AX failures.
+pub fn submenu_frame(node: &MenuNode) -> Result<Option<MenuFrame>, MenuError> {
+    let element = node
+        .element
+        .as_ref()
+        .ok_or(MenuError::AX(AXError::InvalidElement))?;
+    let Some(menu) = element.children()?.into_iter().next() else {
+        return Ok(None);
+    };
+    let position = menu.attribute(kAXPositionAttribute)?;
+    let size = menu.attribute(kAXSizeAttribute)?;
+    Ok(match (position, size) {
+        (Some(AttributeValue::Point { x, y }), Some(AttributeValue::Size { width, height }))
+            if width > 0.0 && height > 0.0 =>
+        {
+            Some(MenuFrame {
+                x,
+                y,
+                width,
+                height,
+            })
+        }
+        _ => None,
+    })
+}
+
+/// Fail unless `action` is one of the `supported` actions of `node`.
+///
+/// # Errors
+///
+/// Returns `MenuError::ActionUnsupported`, listing the supported actions.
+pub fn check_action(
+    node: &MenuNode,
+    action: &str,
+    supported: Vec<String>,
+) -> Result<(), MenuError> {
+    if supported.iter().any(|a| a == action) {
+        Ok(())
+    } else {
+        Err(MenuError::ActionUnsupported {
+            path: node.path.clone(),
+            action: action.to_owned(),
+            supported,
+        })
+    }
+}
+
+/// Read the attribute named `attr` (`AXHelp`, `AXPosition`, …) from `node`'s
+/// element, as JSON: `null` if the element does not support it or it has no
+/// value.
+///
+/// # Errors
+///
+/// Returns `MenuError::AX(AXError::InvalidElement)` if the node has no live
+/// element, or other AX failures.
+pub fn node_attribute(node: &MenuNode, attr: &str) -> Result<Value, MenuError> {
+    let element = node
+        .element
+        .as_ref()
+        .ok_or(MenuError::AX(AXError::InvalidElement))?;
+    Ok(element
+        .attribute(attr)?
+        .map_or(Value::Null, |value| value.to_json()))
+}
+
+/// The attribute named `attr` as read when `node` was built: the title,
+/// role, enabled state, help text, and raw shortcut parts. `null` for any
+/// other attribute.
+#[must_use]
+pub fn node_field(node: &MenuNode, attr: &str) -> Value {
+    match attr {
+        "AXTitle" => json!(node.title),
+        "AXRole" => json!(node.role),
+        "AXEnabled" => json!(node.enabled),
+        "AXHelp" => json!(node.help),
+        "AXMenuItemCmdChar" => json!(node.cmd_char),
+        "AXMenuItemCmdModifiers" => json!(node.cmd_modifiers),
+        "AXMenuItemCmdGlyph" => json!(node.cmd_glyph),
+        "AXMenuItemCmdVirtualKey" => json!(node.virtual_key),
+        "AXSelected" => json!(node.selected),
+        "AXFocused" => json!(node.focused),
+        _ => Value::Null,
+    }
+}
+
+/// Re-read the checkmark of a node's AX element, without walking any menus.
+///
+/// # Errors
+///
+/// Returns `MenuError::AX(AXError::InvalidElement)` if the element is gone
+/// (e.g. the app rebuilt the menu), or other AX failures.
+pub fn read_checked(node: &MenuNode) -> Result<bool, MenuError> {
+    let element = node
+        .element
+        .as_ref()
+        .ok_or(MenuError::AX(AXError::InvalidElement))?;
+    let attrs = element.batch_attributes(&[accessibility_sys::kAXMenuItemMarkCharAttribute])?;
+    Ok(extract_string(&attrs, 0).is_some_and(|s| !s.is_empty()))
+}
+
+/// Notifications a menu item may post when its checkmark changes.
+const CHECK_NOTIFICATIONS: &[&str] = &[
+    kAXValueChangedNotification,
+    kAXTitleChangedNotification,
+    kAXUIElementDestroyedNotification,
+];
+
+/// How often [`press_and_wait`] re-reads the checkmark between notifications.
+const CHECK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Press a node and wait for its checkmark to change.
+///
+/// The mark character is re-read whenever an `AXObserver` on the element posts
+/// a notification, and otherwise every [`CHECK_POLL_INTERVAL`], so apps that
+/// cannot be observed are not held to the full `timeout`. The wait ends as
+/// soon as the mark differs from `node.checked`, or after `timeout` with the
+/// state read then.
+///
+/// Returns `None` if the element could not be read after the press, e.g.
+/// because it was destroyed when the app rebuilt its menu. Only the press
+/// itself can fail, so a caller may safely retry on error.
+///
+/// # Errors
+///
+/// Returns the same errors as [`press_node`].
+#[instrument(level = "debug", skip(node), fields(path = %node.path))]
+pub fn press_and_wait(
+    pid: i32,
+    node: &MenuNode,
+    timeout: Duration,
+) -> Result<Option<bool>, MenuError> {
+    // Register before pressing so a fast update is not missed.
+    let observer = node
+        .element
+        .as_ref()
+        .and_then(|el| Observer::watch(pid, el, CHECK_NOTIFICATIONS).ok());
+    press_node(node)?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let slice = remaining.min(CHECK_POLL_INTERVAL);
+        if let Some(observer) = &observer {
+            if observer.wait(slice).as_deref() == Some(kAXUIElementDestroyedNotification) {
+                return Ok(None);
+            }
+        } else {
+            std::thread::sleep(slice);
+        }
+        match read_checked(node) {
+            Ok(checked) if checked != node.checked => return Ok(Some(checked)),
+            Ok(_) => {}
+            Err(_) => return Ok(None),
+        }
+    }
+
+    Ok(read_checked(node).ok())
+}
+
+/// An extras tree result, associating menu nodes with the owning app.
+#[derive(Debug, Clone)]
+pub struct ExtrasResult {
+    /// Name of the app that owns these extras.
+    pub app_name: String,
+    /// PID of the owning app.
+    pub app_pid: i32,
+    /// Bundle identifier of the owning app, if it has one.
+    pub bundle_id: Option<String>,
+    /// Menu nodes for the extras items.
+    pub nodes: Vec<MenuNode>,
+}
+
+/// Build the extras (status bar) tree for a single app, given its PID.
+///
+/// Uses `visible_children` to respect menu bar managers (Bartender/Ice).
+///
+/// # Errors
+///
+/// Returns `MenuError` if the AX API fails or the app has no extras.
+#[instrument(level = "debug", skip(opts))]
+pub fn build_extras_tree(
+    pid: i32,
+    max_depth: Option<usize>,
+    opts: &TreeOptions,
+) -> Result<Vec<MenuNode>, MenuError> {
+    let app = AXElement::application(pid, opts.messaging_timeout());
+    let extras_bar = app.extras_menu_bar()?;
+    // Use visible_children to respect system hiding (Bartender/Ice).
+    let top_level = extras_bar
+        .visible_children()
+        .or_else(|_| extras_bar.children())?;
+
+    if top_level.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let elements: Vec<AXElement> = top_level
+        .into_iter()
+        .filter(|element| !opts.filters_menus() || opts.wants_menu(&element_title(element)))
+        .collect();
+    let budget = opts.walk_budget();
+    let mut nodes: Vec<MenuNode> = walk_siblings(elements, "", 1, max_depth, opts, &budget)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|node| !node.title.is_empty())
+        .collect();
+
+    number_siblings(&mut nodes);
+    assign_ids(&mut nodes, &app_key(pid));
+    if opts.locale_aliases {
+        add_english_aliases(&mut nodes, &english_titles(pid));
+    }
+    Ok(nodes)
+}
+
+/// Build extras trees for all running apps.
+///
+/// See [`sweep_extras`] for concurrency and timeouts. Apps without extras are
+/// silently skipped.
+pub fn build_all_extras(max_depth: Option<usize>, opts: &TreeOptions) -> Vec<ExtrasResult> {
+    sweep_extras(&list_running_apps(), opts, |pid, opts| {
+        build_extras_tree(pid, max_depth, opts)
+    })
+}
+
+/// Build the extras of each of `apps` with `build`, several apps at a time.
+///
+/// At most [`TreeOptions::extras_jobs`] apps (default
+/// [`DEFAULT_EXTRAS_JOBS`]) are walked at once, and unless `opts` sets an AX
+/// timeout, each app gets only [`EXTRAS_SWEEP_TIMEOUT_SECS`] per call so one
+/// hung app cannot stall the sweep. Results keep the order of `apps`; apps
+/// whose build fails or yields nothing are skipped.
+#[instrument(level = "debug", skip_all, fields(apps = apps.len()))]
+pub fn sweep_extras<F>(apps: &[RunningApp], opts: &TreeOptions, build: F) -> Vec<ExtrasResult>
+where
+    F: Fn(i32, &TreeOptions) -> Result<Vec<MenuNode>, MenuError> + Sync,
+{
+    let opts = TreeOptions {
+        ax_timeout: Some(opts.ax_timeout.unwrap_or(EXTRAS_SWEEP_TIMEOUT_SECS)),
+        ..opts.clone()
+    };
+    let jobs = opts
+        .extras_jobs
+        .unwrap_or(DEFAULT_EXTRAS_JOBS)
+        .clamp(1, apps.len().max(1));
+    let next = AtomicUsize::new(0);
+
+    let mut found: Vec<(usize, Vec<MenuNode>)> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= apps.len() || opts.cancel.is_cancelled() {
+                            return done;
+                        }
+                        if let Ok(nodes) = build(apps[i].pid, &opts) {
+                            done.push((i, nodes));
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_default())
+            .collect()
+    });
+    found.sort_by_key(|(i, _)| *i);
+
+    found
+        .into_iter()
+        .filter(|(_, nodes)| !nodes.is_empty())
+        .map(|(i, nodes)| ExtrasResult {
+            app_name: apps[i].name.clone(),
+            app_pid: apps[i].pid,
+            bundle_id: apps[i].bundle_id.clone(),
+            nodes,
+        })
+        .collect()
+}
+
+// --- Attribute extraction helpers ---
+
+/// Read just the title of `element` (empty if unavailable).
+fn element_title(element: &AXElement) -> String {
+    element
+        .batch_attributes(&[accessibility_sys::kAXTitleAttribute])
+        .ok()
+        .and_then(|a| extract_string(&a, 0))
+        .unwrap_or_default()
+}
+
+fn extract_string(attrs: &[Option<AttributeValue>], idx: usize) -> Option<String> {
+    match attrs.get(idx)?.as_ref()? {
+        AttributeValue::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn extract_bool(attrs: &[Option<AttributeValue>], idx: usize) -> Option<bool> {
+    match attrs.get(idx)?.as_ref()? {
+        AttributeValue::Bool(b) => Some(*b),
+        _ => None,
+    }
+}
+
+fn extract_number(attrs: &[Option<AttributeValue>], idx: usize) -> Option<i64> {
+    match attrs.get(idx)?.as_ref()? {
+        AttributeValue::Number(n) => Some(*n),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_path() {
+        assert_eq!(parent_path("File"), None);
+        assert_eq!(parent_path("File::Save"), Some("File"));
+        assert_eq!(parent_path("Format::Font::Bold"), Some("Format::Font"));
+        assert_eq!(parent_path("Go::A\\::B::C"), Some("Go::A\\::B"));
+        assert_eq!(parent_path("Go::A\\::B"), Some("Go"));
+    }
+
+    #[test]
+    fn test_check_state_from_mark() {
+        assert_eq!(CheckState::from_mark(None), CheckState::Off);
+        assert_eq!(CheckState::from_mark(Some("")), CheckState::Off);
+        assert_eq!(CheckState::from_mark(Some("✓")), CheckState::On);
+        assert_eq!(CheckState::from_mark(Some("•")), CheckState::On);
+        assert_eq!(CheckState::from_mark(Some("–")), CheckState::Mixed);
+        assert!(CheckState::is_radio_mark(Some("•")));
+        assert!(!CheckState::is_radio_mark(Some("✓")));
+    }
+
+    fn leaf(title: &str, depth: usize, check_state: CheckState) -> MenuNode {
+        MenuNode {
+            title: title.to_owned(),
+            path: title.to_owned(),
+            enabled: true,
+            checked: check_state != CheckState::Off,
+            check_state,
+            radio_group: None,
+            radio_bulleted: false,
+            shortcut: None,
+            virtual_key: None,
+            cmd_char: None,
+            cmd_modifiers: None,
+            cmd_glyph: None,
+            selected: None,
+            focused: None,
+            role: "AXMenuItem".to_owned(),
+            depth,
+            index: 0,
+            id: String::new(),
+            children: Vec::new(),
+            element: None,
+            is_alternate: false,
+            alternate_of: None,
+            help: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_mark_radio_group() {
+        // One checkmark among submenu leaves: a choice.
+        let mut sort_by = vec![
+            leaf("Name", 3, CheckState::On),
+            leaf("Kind", 3, CheckState::Off),
+        ];
+        mark_radio_group(&mut sort_by, "View::Sort By#1");
+        assert!(sort_by
+            .iter()
+            .all(|n| n.radio_group.as_deref() == Some("View::Sort By#1") && !n.radio_bulleted));
+
+        // The same in a top-level menu is just two settings.
+        let mut view = vec![
+            leaf("Show Sidebar", 2, CheckState::On),
+            leaf("Show Path Bar", 2, CheckState::Off),
+        ];
+        mark_radio_group(&mut view, "View#1");
+        assert!(view.iter().all(|n| n.radio_group.is_none()));
+
+        // A bullet marks the run wherever it is.
+        view[0].radio_group = Some(String::new());
+        mark_radio_group(&mut view, "View#1");
+        assert!(view
+            .iter()
+            .all(|n| n.radio_group.as_deref() == Some("View#1") && n.radio_bulleted));
+
+        // Two checkmarks: independent settings.
+        let mut both = vec![
+            leaf("Bold", 3, CheckState::On),
+            leaf("Italic", 3, CheckState::On),
+        ];
+        mark_radio_group(&mut both, "Format::Font#1");
+        assert!(both.iter().all(|n| n.radio_group.is_none()));
+    }
+
+    #[test]
+    fn test_names_dynamic_submenu() {
+        assert!(names_dynamic_submenu("File::Open Recent::notes.txt"));
+        assert!(names_dynamic_submenu("window::Untitled"));
+        assert!(names_dynamic_submenu("TextEdit::Services::Make Sticky"));
+        // The submenu itself is the target, not a level to read through.
+        assert!(!names_dynamic_submenu("File::Open Recent"));
+        assert!(!names_dynamic_submenu("File::Save As…"));
+        assert!(!names_dynamic_submenu("open recent"));
+    }
+}
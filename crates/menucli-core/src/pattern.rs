@@ -0,0 +1,78 @@
+//! Name patterns shared by `--app glob:`/`re:`, `apps --filter`, and
+//! wildcard menu paths, so they agree on wildcards, anchoring, and case.
+
+use regex::{Regex, RegexBuilder};
+
+/// Compile `glob` into a regex matching whole strings: `*` stands for any run
+/// of characters, `?` for exactly one, and everything else for itself.
+///
+/// Matching runs in time linear in the text, whatever the number of `*`s.
+///
+/// # Errors
+///
+/// Returns the regex error if the glob is too long to compile.
+pub fn glob(glob: &str, case_sensitive: bool) -> Result<Regex, regex::Error> {
+    let mut source = String::with_capacity(glob.len() + 2);
+    source.push('^');
+    for c in glob.chars() {
+        match c {
+            '*' => source.push_str(".*"),
+            '?' => source.push('.'),
+            c => source.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    source.push('$');
+    build(&source, case_sensitive)
+}
+
+/// Compile a regular expression, found anywhere in the text unless it
+/// anchors itself.
+///
+/// # Errors
+///
+/// Returns the regex error if `source` is not a valid regular expression.
+pub fn regex(source: &str, case_sensitive: bool) -> Result<Regex, regex::Error> {
+    build(source, case_sensitive)
+}
+
+fn build(source: &str, case_sensitive: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(source)
+        .case_insensitive(!case_sensitive)
+        .dot_matches_new_line(true)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches_whole_string() {
+        let pattern = glob("chrom*", false).unwrap();
+        assert!(!pattern.is_match("Google Chrome"));
+        assert!(pattern.is_match("Chromium"));
+        assert!(glob("*chrom*", false).unwrap().is_match("Google Chrome"));
+    }
+
+    #[test]
+    fn test_glob_wildcards_and_literals() {
+        let pattern = glob("Blender 4.?", false).unwrap();
+        assert!(pattern.is_match("blender 4.2"));
+        assert!(!pattern.is_match("Blender 412"));
+        assert!(!pattern.is_match("Blender 4.10"));
+        assert!(!glob("Save", true).unwrap().is_match("save"));
+    }
+
+    #[test]
+    fn test_glob_many_stars_stays_fast() {
+        let pattern = glob(&format!("{}b", "*a".repeat(30)), false).unwrap();
+        assert!(!pattern.is_match(&"a".repeat(40)));
+    }
+
+    #[test]
+    fn test_regex_is_unanchored() {
+        assert!(regex("^adobe", false).unwrap().is_match("Adobe Photoshop"));
+        assert!(regex("photo", false).unwrap().is_match("Adobe Photoshop"));
+        assert!(regex("(", false).is_err());
+    }
+}
@@ -0,0 +1,922 @@
+/// Shared serializable output types for all commands.
+///
+/// These types are what gets written to stdout — either as JSON or rendered
+/// as a table. They are decoupled from the internal `MenuNode` / `FlatItem` types.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ax::{ActivationPolicy, ElementInfo, Modifier};
+use crate::menu::flatten::{descendants_count, enabled_descendants};
+use crate::menu::search::SearchResult;
+use crate::menu::tree::{parent_path, split_path, CheckState, ExtrasResult};
+use crate::menu::{Explanation, FlatItem, MatchStrategy, MenuNode, Resolution, Verdict};
+
+/// Current JSON schema version, emitted as `schema_version` on every output
+/// object. Bump it whenever a field is renamed or removed, and add a shim for
+/// the previous version in the CLI's output layer.
+///
+/// Version 1 is the original, unversioned output.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A menu item in flat (list) representation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+// Each bool is its own JSON field.
+#[allow(clippy::struct_excessive_bools)]
+pub struct MenuItemOutput {
+    /// Stable ID, accepted by `click --id` / `state --id`.
+    pub id: String,
+    /// Display title (leaf name, e.g., "Save As…").
+    pub title: String,
+    /// Full path from root (e.g., "File::Save As…").
+    pub path: String,
+    /// Full path of the containing item; absent for top-level menus.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_path: Option<String>,
+    /// Whether the item is enabled (clickable).
+    pub enabled: bool,
+    /// Whether the item has a checkmark (toggle state = on).
+    pub checked: bool,
+    /// The checkmark: "on", "off", or "mixed" (a dash).
+    #[serde(default)]
+    pub check_state: CheckState,
+    /// Whether this is one choice of a radio group (a bullet, or the only
+    /// checkmark among submenu siblings).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_radio: bool,
+    /// The radio group, as the parent path and section (e.g. `View::Sort By#1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Formatted keyboard shortcut (e.g., "⇧⌘S"), or null.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortcut: Option<String>,
+    /// Virtual key code of the shortcut's key (`kVK_*`), independent of the
+    /// keyboard layout, for tools that synthesize the keystroke.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub virtual_key: Option<u16>,
+    /// The shortcut's key character as AX reports it (`kAXMenuItemCmdChar`),
+    /// unformatted; absent for keys named by `cmd_glyph`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_char: Option<String>,
+    /// The shortcut's raw `kAXMenuItemCmdModifiers` mask: Shift 1, Option 2,
+    /// Control 4, and 8 when Command is *not* held.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_modifiers_mask: Option<u8>,
+    /// The `kAXMenuItemCmdGlyph` code of a shortcut key without a character
+    /// (function keys, arrows, Delete, …).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_glyph: Option<u16>,
+    /// Whether the item is highlighted, where AX reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<bool>,
+    /// Whether the item has keyboard focus, where AX reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused: Option<bool>,
+    /// AX role string (e.g., "AXMenuItem", "AXMenuBarItem").
+    pub role: String,
+    /// Number of direct children.
+    pub children_count: usize,
+    /// Number of items below this one, at any depth.
+    #[serde(default)]
+    pub descendants_count: usize,
+    /// How many of those are enabled; 0 with `descendants_count` above 0
+    /// means the whole submenu is disabled.
+    #[serde(default)]
+    pub enabled_descendants: usize,
+    /// Depth from root (1 = top-level menu bar item, 2+ = nested).
+    pub depth: usize,
+    /// 1-based position among its siblings (`#N` path segment).
+    pub index: usize,
+    /// Whether this item is an Option-key alternate.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_alternate: bool,
+    /// Title of the primary item this alternate replaces, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate_of: Option<String>,
+    /// Name of the app that owns this item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// PID of the app that owns this item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_pid: Option<i32>,
+    /// Bundle identifier of the app that owns this item, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// Help (tooltip) text, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// Modifier keys `click` held down while pressing the item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<Modifier>,
+    /// When `list` read the menus, as an RFC 3339 UTC timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+    /// When `click` pressed the item, or `state` read it, as an RFC 3339 UTC
+    /// timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_at: Option<String>,
+}
+
+impl MenuItemOutput {
+    /// Build from a `FlatItem`, optionally attributed to the app that owns it.
+    #[must_use]
+    pub fn from_flat(f: FlatItem, app: Option<&ExtrasResult>) -> Self {
+        Self {
+            id: f.id,
+            title: f.title,
+            path: f.path,
+            parent_path: f.parent_path,
+            enabled: f.enabled,
+            checked: f.checked,
+            check_state: f.check_state,
+            is_radio: f.radio_group.is_some(),
+            group: f.radio_group,
+            shortcut: f.shortcut,
+            virtual_key: f.virtual_key,
+            cmd_char: f.cmd_char,
+            cmd_modifiers_mask: f.cmd_modifiers,
+            cmd_glyph: f.cmd_glyph,
+            selected: f.selected,
+            focused: f.focused,
+            role: f.role,
+            children_count: f.children_count,
+            descendants_count: f.descendants_count,
+            enabled_descendants: f.enabled_descendants,
+            depth: f.depth,
+            index: f.index,
+            is_alternate: f.is_alternate,
+            alternate_of: f.alternate_of,
+            app_name: app.map(|a| a.app_name.clone()),
+            app_pid: app.map(|a| a.app_pid),
+            bundle_id: app.and_then(|a| a.bundle_id.clone()),
+            help: f.help,
+            modifiers: Vec::new(),
+            generated_at: None,
+            performed_at: None,
+        }
+    }
+
+    /// Build from a single (resolved) `MenuNode`, without app attribution.
+    #[must_use]
+    pub fn from_node(node: &MenuNode) -> Self {
+        Self {
+            id: node.id.clone(),
+            title: node.title.clone(),
+            path: node.path.clone(),
+            parent_path: parent_path(&node.path).map(str::to_owned),
+            enabled: node.enabled,
+            checked: node.checked,
+            check_state: node.check_state,
+            is_radio: node.radio_group.is_some(),
+            group: node.radio_group.clone(),
+            shortcut: node.shortcut.clone(),
+            virtual_key: node.virtual_key,
+            cmd_char: node.cmd_char.clone(),
+            cmd_modifiers_mask: node.cmd_modifiers,
+            cmd_glyph: node.cmd_glyph,
+            selected: node.selected,
+            focused: node.focused,
+            role: node.role.clone(),
+            children_count: node.children.len(),
+            descendants_count: descendants_count(node),
+            enabled_descendants: enabled_descendants(node),
+            depth: node.depth,
+            index: node.index,
+            is_alternate: node.is_alternate,
+            alternate_of: node.alternate_of.clone(),
+            app_name: None,
+            app_pid: None,
+            bundle_id: None,
+            help: node.help.clone(),
+            modifiers: Vec::new(),
+            generated_at: None,
+            performed_at: None,
+        }
+    }
+}
+
+/// A menu item in tree representation (nested).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+// Each bool is its own JSON field.
+#[allow(clippy::struct_excessive_bools)]
+pub struct MenuTreeOutput {
+    /// Stable ID, accepted by `click --id` / `state --id`.
+    #[serde(default)]
+    pub id: String,
+    /// Display title.
+    pub title: String,
+    /// Full path from root.
+    pub path: String,
+    /// Whether the item is enabled.
+    pub enabled: bool,
+    /// Whether the item has a checkmark.
+    pub checked: bool,
+    /// The checkmark: "on", "off", or "mixed" (a dash).
+    #[serde(default)]
+    pub check_state: CheckState,
+    /// Whether this is one choice of a radio group (a bullet, or the only
+    /// checkmark among submenu siblings).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_radio: bool,
+    /// The radio group, as the parent path and section (e.g. `View::Sort By#1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Formatted keyboard shortcut, or null.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shortcut: Option<String>,
+    /// Virtual key code of the shortcut's key, or null.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub virtual_key: Option<u16>,
+    /// The shortcut's key character as AX reports it (`kAXMenuItemCmdChar`),
+    /// unformatted; absent for keys named by `cmd_glyph`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_char: Option<String>,
+    /// The shortcut's raw `kAXMenuItemCmdModifiers` mask: Shift 1, Option 2,
+    /// Control 4, and 8 when Command is *not* held.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_modifiers_mask: Option<u8>,
+    /// The `kAXMenuItemCmdGlyph` code of a shortcut key without a character
+    /// (function keys, arrows, Delete, …).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_glyph: Option<u16>,
+    /// Whether the item is highlighted, where AX reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<bool>,
+    /// Whether the item has keyboard focus, where AX reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused: Option<bool>,
+    /// AX role string.
+    pub role: String,
+    /// 1-based position among its siblings (`#N` path segment).
+    #[serde(default)]
+    pub index: usize,
+    /// Nested children.
+    pub children: Vec<MenuTreeOutput>,
+    /// Whether this item is an Option-key alternate.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_alternate: bool,
+    /// Title of the primary item this alternate replaces, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alternate_of: Option<String>,
+    /// Help (tooltip) text, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// When `list --tree` read the menus, as an RFC 3339 UTC timestamp; set
+    /// on top-level menus only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+}
+
+impl MenuTreeOutput {
+    /// Build from a `MenuNode` and its whole subtree.
+    #[must_use]
+    pub fn from_node(node: &MenuNode) -> Self {
+        Self {
+            id: node.id.clone(),
+            title: node.title.clone(),
+            path: node.path.clone(),
+            enabled: node.enabled,
+            checked: node.checked,
+            check_state: node.check_state,
+            is_radio: node.radio_group.is_some(),
+            group: node.radio_group.clone(),
+            shortcut: node.shortcut.clone(),
+            virtual_key: node.virtual_key,
+            cmd_char: node.cmd_char.clone(),
+            cmd_modifiers_mask: node.cmd_modifiers,
+            cmd_glyph: node.cmd_glyph,
+            selected: node.selected,
+            focused: node.focused,
+            role: node.role.clone(),
+            index: node.index,
+            children: node.children.iter().map(Self::from_node).collect(),
+            is_alternate: node.is_alternate,
+            alternate_of: node.alternate_of.clone(),
+            help: node.help.clone(),
+            generated_at: None,
+        }
+    }
+}
+
+/// A search result with match score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+// Each bool is its own JSON field.
+#[allow(clippy::struct_excessive_bools)]
+pub struct SearchResultOutput {
+    /// Stable ID, accepted by `click --id` / `state --id`.
+    pub id: String,
+    /// The matched item's title.
+    pub title: String,
+    /// The matched item's full path.
+    pub path: String,
+    /// Whether the item is enabled.
+    pub enabled: bool,
+    /// Whether the item has a checkmark.
+    pub checked: bool,
+    /// The checkmark: "on", "off", or "mixed" (a dash).
+    #[serde(default)]
+    pub check_state: CheckState,
+    /// Whether this is one choice of a radio group (a bullet, or the only
+    /// checkmark among submenu siblings).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_radio: bool,
+    /// The radio group, as the parent path and section (e.g. `View::Sort By#1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Formatted keyboard shortcut, or null.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortcut: Option<String>,
+    /// Virtual key code of the shortcut's key, or null.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub virtual_key: Option<u16>,
+    /// The shortcut's key character as AX reports it (`kAXMenuItemCmdChar`),
+    /// unformatted; absent for keys named by `cmd_glyph`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_char: Option<String>,
+    /// The shortcut's raw `kAXMenuItemCmdModifiers` mask: Shift 1, Option 2,
+    /// Control 4, and 8 when Command is *not* held.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_modifiers_mask: Option<u8>,
+    /// The `kAXMenuItemCmdGlyph` code of a shortcut key without a character
+    /// (function keys, arrows, Delete, …).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd_glyph: Option<u16>,
+    /// Whether the item is highlighted, where AX reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<bool>,
+    /// Whether the item has keyboard focus, where AX reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused: Option<bool>,
+    /// 1-based position among its siblings (`#N` path segment).
+    #[serde(default)]
+    pub index: usize,
+    /// Match score (higher = better). 0 for exact matches.
+    pub score: u32,
+    /// Matched spans of `path` as `[start, end)` character offsets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub match_ranges: Vec<[usize; 2]>,
+    /// Whether this item is an Option-key alternate.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_alternate: bool,
+    /// Title of the primary item this alternate replaces, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate_of: Option<String>,
+    /// Help (tooltip) text, when the query matched it (`--include-help`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// Name of the app that owns this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// PID of the app that owns this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_pid: Option<i32>,
+    /// Bundle identifier of the app that owns this item, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// Matched alternates of this item (`--collapse-alternates`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<SearchResultOutput>,
+    /// When `search` read the menus, as an RFC 3339 UTC timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+}
+
+impl SearchResultOutput {
+    /// Build from a scored `SearchResult`.
+    #[must_use]
+    pub fn from_result(r: &SearchResult) -> Self {
+        Self {
+            id: r.item.id.clone(),
+            title: r.item.title.clone(),
+            path: r.item.path.clone(),
+            enabled: r.item.enabled,
+            checked: r.item.checked,
+            check_state: r.item.check_state,
+            is_radio: r.item.radio_group.is_some(),
+            group: r.item.radio_group.clone(),
+            shortcut: r.item.shortcut.clone(),
+            virtual_key: r.item.virtual_key,
+            cmd_char: r.item.cmd_char.clone(),
+            cmd_modifiers_mask: r.item.cmd_modifiers,
+            cmd_glyph: r.item.cmd_glyph,
+            selected: r.item.selected,
+            focused: r.item.focused,
+            index: r.item.index,
+            score: r.score,
+            match_ranges: r.ranges.iter().map(|r| [r.start, r.end]).collect(),
+            is_alternate: r.item.is_alternate,
+            alternate_of: r.item.alternate_of.clone(),
+            help: r.help_match.then(|| r.item.help.clone()).flatten(),
+            app_name: None,
+            app_pid: None,
+            bundle_id: None,
+            alternates: r.alternates.iter().map(Self::from_result).collect(),
+            generated_at: None,
+        }
+    }
+}
+
+/// Item counts for `list --count`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuCountOutput {
+    /// Number of items across all menus.
+    pub total: usize,
+    /// Per top-level menu counts, in menu bar order.
+    pub menus: Vec<MenuCount>,
+}
+
+/// Number of items under one top-level menu (including the menu itself).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuCount {
+    /// Top-level menu title.
+    pub menu: String,
+    /// Number of items.
+    pub count: usize,
+}
+
+impl MenuCountOutput {
+    /// Tally flat items by the first segment of their path.
+    #[must_use]
+    pub fn from_items(items: &[MenuItemOutput]) -> Self {
+        let mut menus: Vec<MenuCount> = Vec::new();
+        for item in items {
+            let menu = split_path(&item.path)[0];
+            match menus.iter_mut().find(|m| m.menu == menu) {
+                Some(m) => m.count += 1,
+                None => menus.push(MenuCount {
+                    menu: menu.to_owned(),
+                    count: 1,
+                }),
+            }
+        }
+        Self {
+            total: items.len(),
+            menus,
+        }
+    }
+}
+
+/// Results of `menucli bench`. Times are medians over all iterations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchOutput {
+    /// PID of the benchmarked app.
+    pub pid: i32,
+    /// Times each measurement was repeated.
+    pub iterations: u32,
+    /// Items in the full tree.
+    pub items: usize,
+    /// Full tree build time.
+    pub build: BenchTiming,
+    /// Items read per second in the median full build.
+    pub items_per_sec: f64,
+    /// Build time of each top-level menu on its own, in menu bar order.
+    pub menus: Vec<MenuBenchOutput>,
+    /// Elements read by each attribute fetch pass (menu containers included).
+    pub fetched: usize,
+    /// Attribute reads with one batch call per item.
+    pub fetch_batch: BenchTiming,
+    /// Attribute reads with one call per attribute.
+    pub fetch_single: BenchTiming,
+    /// How many times faster batch fetching was (`fetch_single / fetch_batch`).
+    pub batch_speedup: f64,
+}
+
+/// Spread of one measurement across iterations, in milliseconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BenchTiming {
+    /// Fastest run.
+    pub min_ms: f64,
+    /// Median run.
+    pub median_ms: f64,
+    /// Slowest run.
+    pub max_ms: f64,
+}
+
+impl BenchTiming {
+    /// Summarize per-iteration durations.
+    #[must_use]
+    pub fn from_samples(samples: &[std::time::Duration]) -> Self {
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let median = match ms.len() {
+            0 => 0.0,
+            n if n % 2 == 0 => f64::midpoint(ms[n / 2 - 1], ms[n / 2]),
+            n => ms[n / 2],
+        };
+        Self {
+            min_ms: ms.first().copied().unwrap_or_default(),
+            median_ms: median,
+            max_ms: ms.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Build time of one top-level menu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuBenchOutput {
+    /// Top-level menu title.
+    pub menu: String,
+    /// Items in the menu (including the menu itself).
+    pub items: usize,
+    /// Build time of just this menu.
+    pub build: BenchTiming,
+}
+
+/// Running application info.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppInfoOutput {
+    /// Localized app name.
+    pub name: String,
+    /// Process ID.
+    pub pid: i32,
+    /// Bundle identifier, or null if unavailable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// Whether this is the frontmost application.
+    pub frontmost: bool,
+    /// Regular app, agent (`accessory`), or background process (`prohibited`).
+    pub activation_policy: ActivationPolicy,
+    /// Launch time in seconds since the Unix epoch, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_date: Option<u64>,
+    /// Whether the app has a readable menu bar; only set when probed
+    /// (`apps --with-menu`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_menu: Option<bool>,
+    /// Whether the app has status bar extras; only set when probed
+    /// (`apps --with-extras`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_extras: Option<bool>,
+}
+
+/// An item a query resolves to, and how (`menucli resolve`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolutionOutput {
+    /// The query, as given.
+    pub query: String,
+    /// Stable ID, accepted by `click --id` / `state --id`.
+    pub id: String,
+    /// The item's title.
+    pub title: String,
+    /// The item's full path.
+    pub path: String,
+    /// Whether the item is enabled.
+    pub enabled: bool,
+    /// The strategy that matched the item.
+    pub strategy: MatchStrategy,
+    /// Fuzzy match score (higher = better); absent for exact strategies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u32>,
+}
+
+impl ResolutionOutput {
+    /// Build from how `query` resolved.
+    #[must_use]
+    pub fn from_resolution(query: &str, r: &Resolution) -> Self {
+        Self {
+            query: query.to_owned(),
+            id: r.node.id.clone(),
+            title: r.node.title.clone(),
+            path: r.node.path.clone(),
+            enabled: r.node.enabled,
+            strategy: r.strategy,
+            score: r.score,
+        }
+    }
+}
+
+/// How a query resolves, step by step (`menucli explain`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainOutput {
+    /// The query, as given.
+    pub query: String,
+    /// The first strategy that matched anything; later ones were not tried.
+    pub strategy: MatchStrategy,
+    /// Whether the query resolves to one item, the first candidate; otherwise
+    /// `click` and friends fail as ambiguous.
+    pub resolved: bool,
+    /// How the candidates were narrowed down to one, or why they were not.
+    pub verdict: Verdict,
+    /// The best fuzzy score over the runner-up's, when several items matched
+    /// fuzzily.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence_ratio: Option<f32>,
+    /// The ratio a fuzzy match needs to auto-resolve (`--fuzzy-ratio`).
+    pub required_ratio: f32,
+    /// The verdict, in words.
+    pub reason: String,
+    /// Total number of candidates, including any not listed.
+    pub candidates_count: usize,
+    /// The candidates, best first.
+    pub candidates: Vec<ResolutionOutput>,
+}
+
+impl ExplainOutput {
+    /// Build from how `query` resolved under `required_ratio`, listing at
+    /// most `limit` candidates (0 for all).
+    #[must_use]
+    pub fn from_explanation(
+        query: &str,
+        e: &Explanation,
+        required_ratio: f32,
+        limit: usize,
+    ) -> Self {
+        let strategy = e.candidates[0].strategy;
+        let score = |i: usize| e.candidates.get(i).and_then(|r| r.score).unwrap_or(0);
+        let count = e.candidates.len();
+        let reason = match (e.verdict, e.ratio) {
+            (Verdict::Unique, _) => format!("only one item matched by {}", strategy.name()),
+            (Verdict::Confident, Some(ratio)) => format!(
+                "the best score, {}, is {ratio:.1}x the runner-up's {}, at least the \
+                 {required_ratio:.1}x needed to auto-resolve",
+                score(0),
+                score(1)
+            ),
+            (_, Some(ratio)) => format!(
+                "the best score, {}, is only {ratio:.1}x the runner-up's {}; auto-resolve \
+                 needs {required_ratio:.1}x",
+                score(0),
+                score(1)
+            ),
+            _ if strategy == MatchStrategy::WildcardPath => {
+                format!("{count} items match the wildcard path; --all acts on every one")
+            }
+            _ => format!(
+                "{count} items matched by {} and no --prefer rule told them apart",
+                strategy.name()
+            ),
+        };
+        let listed = if limit == 0 { count } else { limit.min(count) };
+        Self {
+            query: query.to_owned(),
+            strategy,
+            resolved: e.verdict.resolves(),
+            verdict: e.verdict,
+            confidence_ratio: e.ratio,
+            required_ratio,
+            reason,
+            candidates_count: count,
+            candidates: e.candidates[..listed]
+                .iter()
+                .map(|r| ResolutionOutput::from_resolution(query, r))
+                .collect(),
+        }
+    }
+}
+
+/// What has keyboard focus, as reported by `menucli focused`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusedOutput {
+    /// Name of the focused app.
+    pub app_name: String,
+    /// PID of the focused app.
+    pub app_pid: i32,
+    /// Bundle identifier of the focused app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// The focused UI element; absent if the app reports none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<ElementInfo>,
+    /// The menu item highlighted in an open menu, with `--highlighted`;
+    /// absent when no menu item is highlighted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlighted: Option<MenuItemOutput>,
+    /// When the focus was read, as an RFC 3339 UTC timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+}
+
+/// A menu item's AX actions, and the one `menucli action` performed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionOutput {
+    /// Stable ID, accepted by `click --id` / `action --id`.
+    pub id: String,
+    /// The item's title.
+    pub title: String,
+    /// The item's full path.
+    pub path: String,
+    /// The action performed; absent when only listing them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Every action the item supports.
+    pub actions: Vec<String>,
+    /// Name of the app that owns this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// PID of the app that owns this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_pid: Option<i32>,
+    /// When the action was performed, as an RFC 3339 UTC timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_at: Option<String>,
+    /// Where `--screenshot` saved the opened menu.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<String>,
+}
+
+/// One AX attribute of a menu item, as read by `menucli attr`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeOutput {
+    /// Stable ID, accepted by `click --id` / `attr --id`.
+    pub id: String,
+    /// The item's title.
+    pub title: String,
+    /// The item's full path.
+    pub path: String,
+    /// The attribute name, e.g. `AXPosition`.
+    pub attribute: String,
+    /// The attribute's value: `null` if the item does not have it, and
+    /// objects for points (`x`, `y`), sizes (`width`, `height`), rectangles,
+    /// ranges (`location`, `length`), and elements (`role`, `title`).
+    pub value: serde_json::Value,
+    /// Name of the app that owns this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// PID of the app that owns this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_pid: Option<i32>,
+}
+
+/// Result of a toggle operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToggleOutput {
+    /// Full path of the toggled item.
+    pub path: String,
+    /// Checkmark state before the toggle.
+    pub checked_before: bool,
+    /// Checkmark state after the toggle (or same as before on `--dry-run`).
+    pub checked_after: bool,
+    /// Whether this was a dry-run (no actual action performed).
+    pub dry_run: bool,
+    /// When the toggle was pressed (or, without a press, checked), as an
+    /// RFC 3339 UTC timestamp.
+    #[serde(default)]
+    pub performed_at: String,
+}
+
+/// A structured error envelope for JSON error output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorOutput {
+    /// Always `false`.
+    pub ok: bool,
+    /// Error details.
+    pub error: ErrorDetail,
+}
+
+/// Error detail in the JSON error envelope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorDetail {
+    /// Machine-readable error code (snake_case).
+    pub code: String,
+    /// Human-readable error message.
+    pub message: String,
+    /// Optional list of candidates (for ambiguous match errors).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<Vec<String>>,
+    /// Name of the app the command targeted, once it was found.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// PID of that app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<i32>,
+    /// How the query was matched, for item resolution errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy_tried: Option<MatchStrategy>,
+    /// A suggestion for fixing the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl ErrorOutput {
+    /// Construct from a `MenuError`.
+    #[must_use]
+    pub fn from_menu_error(err: &crate::menu::MenuError) -> Self {
+        use crate::ax::AXError;
+        use crate::menu::MenuError;
+        let (code, message, candidates) = match err {
+            MenuError::AccessDenied => ("permission_denied".to_owned(), err.to_string(), None),
+            MenuError::AppNotFound { .. } => ("app_not_found".to_owned(), err.to_string(), None),
+            MenuError::ItemNotFound { .. } => ("item_not_found".to_owned(), err.to_string(), None),
+            MenuError::AmbiguousMatch { candidates, .. } => (
+                "ambiguous_match".to_owned(),
+                err.to_string(),
+                Some(candidates.clone()),
+            ),
+            MenuError::ItemDisabled { .. } => ("item_disabled".to_owned(), err.to_string(), None),
+            MenuError::ActionUnsupported { supported, .. } => (
+                "action_unsupported".to_owned(),
+                err.to_string(),
+                Some(supported.clone()),
+            ),
+            MenuError::NotToggleable { .. } => ("not_toggleable".to_owned(), err.to_string(), None),
+            MenuError::MixedState { .. } => ("mixed_state".to_owned(), err.to_string(), None),
+            MenuError::RadioSelected { .. } => ("radio_selected".to_owned(), err.to_string(), None),
+            MenuError::StateNotReached { .. } => {
+                ("state_not_reached".to_owned(), err.to_string(), None)
+            }
+            MenuError::AssertionFailed { .. } => {
+                ("assertion_failed".to_owned(), err.to_string(), None)
+            }
+            MenuError::ConditionNotMet { .. } => {
+                ("condition_not_met".to_owned(), err.to_string(), None)
+            }
+            MenuError::InvalidInput { .. } => ("invalid_input".to_owned(), err.to_string(), None),
+            MenuError::InvalidFixture { .. } => {
+                ("invalid_fixture".to_owned(), err.to_string(), None)
+            }
+            MenuError::HookFailed { .. } => ("hook_failed".to_owned(), err.to_string(), None),
+            MenuError::ScreenshotFailed { .. } => {
+                ("screenshot_failed".to_owned(), err.to_string(), None)
+            }
+            MenuError::NoResults => ("no_results".to_owned(), err.to_string(), None),
+            MenuError::Cancelled => ("cancelled".to_owned(), err.to_string(), None),
+            MenuError::TimedOut => ("timeout".to_owned(), err.to_string(), None),
+            MenuError::AX(AXError::AmbiguousApp { candidates, .. }) => (
+                "ambiguous_app".to_owned(),
+                err.to_string(),
+                Some(candidates.clone()),
+            ),
+            MenuError::AX(AXError::InvalidAppPattern { .. }) => {
+                ("invalid_input".to_owned(), err.to_string(), None)
+            }
+            MenuError::AX(_) => ("ax_error".to_owned(), err.to_string(), None),
+        };
+        Self {
+            ok: false,
+            error: ErrorDetail {
+                code,
+                message,
+                candidates,
+                app: None,
+                pid: None,
+                strategy_tried: err.strategy(),
+                hint: err.hint().map(str::to_owned),
+            },
+        }
+    }
+
+    /// Name the app the failed command was working on.
+    #[must_use]
+    pub fn with_target(mut self, app: Option<String>, pid: i32) -> Self {
+        self.error.app = app;
+        self.error.pid = Some(pid);
+        self
+    }
+}
+
+/// `time` as an RFC 3339 UTC timestamp with milliseconds
+/// (`2024-05-01T09:30:00.250Z`). Times before the Unix epoch read as the epoch.
+#[must_use]
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Howard Hinnant's `civil_from_days`, for days since 1970-01-01.
+    let z = days + 719_468;
+    let (era, doe) = (z / 146_097, z % 146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + yoe + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        since.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn at(secs: u64, millis: u64) -> String {
+        format_rfc3339(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn test_format_rfc3339_epoch() {
+        assert_eq!(at(0, 0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_rfc3339(UNIX_EPOCH - Duration::from_secs(1)),
+            "1970-01-01T00:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn test_format_rfc3339_leap_day() {
+        assert_eq!(at(1_709_210_096, 789), "2024-02-29T12:34:56.789Z");
+    }
+
+    #[test]
+    fn test_format_rfc3339_after_century_leap_day() {
+        // 2000 is a leap year despite being a century.
+        assert_eq!(at(951_868_799, 0), "2000-02-29T23:59:59.000Z");
+        assert_eq!(at(951_868_800, 0), "2000-03-01T00:00:00.000Z");
+    }
+}
@@ -1,5 +1,19 @@
 /// CLI argument definitions via clap derive.
-use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::builder::BoolishValueParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCompleter;
+use serde::Deserialize;
+
+use super::complete;
+use super::deadline::parse_timeout;
+use super::logging::parse_log_level;
+use crate::ax::Modifier;
+use crate::menu::resolve::FUZZY_AUTO_RESOLVE_RATIO;
+use crate::menu::{Prefer, ResolveOptions, SearchFilters};
+use crate::types::SCHEMA_VERSION;
 
 /// menucli — query and interact with macOS app menu bars.
 #[derive(Debug, Parser)]
@@ -11,35 +25,194 @@ use clap::{Parser, Subcommand, ValueEnum};
 )]
 pub struct Cli {
     /// Output format. Auto-detects: table when TTY, json when piped.
-    #[arg(long, global = true, value_name = "FORMAT", default_value = "auto")]
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_OUTPUT",
+        value_name = "FORMAT",
+        default_value = "auto"
+    )]
     pub output: OutputFormat,
 
     /// Shorthand for --output json.
     #[arg(long, global = true, conflicts_with = "output")]
     pub json: bool,
 
+    /// Shorthand for --output path0: NUL-separated paths.
+    #[arg(short = '0', global = true, conflicts_with = "json")]
+    pub null: bool,
+
     /// Comma-separated field names to include in output (projection).
     /// Available fields vary by command (see --help for each subcommand).
-    #[arg(long, global = true, value_name = "FIELDS")]
+    #[arg(long, global = true, env = "MENUCLI_FIELDS", value_name = "FIELDS")]
     pub fields: Option<String>,
 
     /// Omit table headers (useful for awk/cut processing).
-    #[arg(long, global = true)]
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_NO_HEADER",
+        value_parser = BoolishValueParser::new()
+    )]
     pub no_header: bool,
 
     /// Print AX API call timing to stderr for debugging.
-    #[arg(long, global = true)]
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_DEBUG",
+        value_parser = BoolishValueParser::new()
+    )]
     pub debug: bool,
 
+    /// Like --debug, but print each timing span as a JSON line (name, id,
+    /// parent, start, duration) for tools that aggregate timings.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_DEBUG_JSON",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub debug_json: bool,
+
+    /// Log to stderr at this level: error, warn, info, debug, trace, or
+    /// `RUST_LOG`-style directives (`menucli_core=trace`). Overrides `RUST_LOG`.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_LOG_LEVEL",
+        value_name = "LEVEL",
+        value_parser = parse_log_level
+    )]
+    pub log_level: Option<String>,
+
+    /// Format of log records on stderr.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_LOG_FORMAT",
+        value_name = "FORMAT",
+        default_value = "compact"
+    )]
+    pub log_format: LogFormat,
+
     /// Include Option-key alternate menu items in output.
     /// Alternates are always detected internally; this flag reveals them.
-    #[arg(long, global = true)]
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_ALTERNATES",
+        value_parser = BoolishValueParser::new()
+    )]
     pub alternates: bool,
 
+    /// Pin the JSON schema version (default: latest). Older versions are
+    /// rendered through compatibility shims, so scripts keep working as
+    /// fields are added or renamed.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_OUTPUT_VERSION",
+        value_name = "N",
+        default_value_t = SCHEMA_VERSION,
+        value_parser = clap::value_parser!(u32).range(1..=i64::from(SCHEMA_VERSION))
+    )]
+    pub output_version: u32,
+
+    /// Seconds to wait for each AX call before giving up on an unresponsive
+    /// app (default: 1). Raise it for slow apps; lower it to sweep status
+    /// bar items across all apps quickly.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_AX_TIMEOUT",
+        value_name = "SECS",
+        value_parser = parse_ax_timeout
+    )]
+    pub ax_timeout: Option<f32>,
+
+    /// Retry tree building and presses this many times when the app is too
+    /// busy to answer (AX timeout), instead of failing.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_RETRIES",
+        value_name = "N",
+        default_value_t = 0
+    )]
+    pub retries: u32,
+
+    /// Milliseconds to wait before the first retry; doubled for each further one.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_RETRY_DELAY",
+        value_name = "MS",
+        default_value_t = 100
+    )]
+    pub retry_delay: u64,
+
+    /// Give up after this long (`5s`, `500ms`, `2m`) with exit code 124.
+    /// `list`/`search --partial` print what was read by then, marked
+    /// `"truncated": true`. Not applied to `rpc`.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_TIMEOUT",
+        value_name = "DURATION",
+        value_parser = parse_timeout
+    )]
+    pub timeout: Option<Duration>,
+
+    /// Never walk these top-level menus (comma-separated titles), for menus
+    /// that are slow to read or that no command should touch.
+    #[arg(
+        long,
+        global = true,
+        env = "MENUCLI_EXCLUDE_MENUS",
+        value_name = "MENUS",
+        value_delimiter = ','
+    )]
+    pub exclude_menus: Vec<String>,
+
+    /// Print nothing on stdout for `click`, `toggle`, `state` and `resolve`, leaving
+    /// the exit code to report the outcome. Errors still go to stderr, except
+    /// a failed `state --assert`, which is the answer rather than an error.
+    #[arg(
+        short,
+        long,
+        global = true,
+        env = "MENUCLI_QUIET",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub quiet: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+impl Cli {
+    /// The `--output` format, or `path0` with `-0`.
+    #[must_use]
+    pub fn output_format(&self) -> OutputFormat {
+        if self.null {
+            OutputFormat::Path0
+        } else {
+            self.output
+        }
+    }
+}
+
+/// Log record formats (`--log-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum LogFormat {
+    /// One human-readable line per record.
+    #[default]
+    Compact,
+    /// One JSON object per record.
+    Json,
+}
+
 /// Output format variants.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum OutputFormat {
@@ -56,8 +229,14 @@ pub enum OutputFormat {
     Table,
     /// Full path only, one per line (for piping to other commands).
     Path,
+    /// Full path only, each followed by a NUL byte (for `xargs -0` and
+    /// `fzf --read0`; safe with titles containing newlines).
+    Path0,
     /// ID/title only, one per line.
     Id,
+    /// Standalone HTML page: a collapsible tree for `--tree`, otherwise a
+    /// sortable table.
+    Html,
 }
 
 /// All subcommands.
@@ -69,23 +248,171 @@ pub enum Command {
     Search(SearchArgs),
     /// Click (activate) a menu item.
     Click(ClickArgs),
+    /// Perform a named AX action on a menu item (AXShowMenu, AXCancel,
+    /// AXPress, …), for items that need more than a press; without --name,
+    /// list the actions it supports.
+    Action(ActionArgs),
+    /// Read one AX attribute of a menu item (AXHelp, AXPosition, …) as JSON,
+    /// including attributes menucli has no field for.
+    Attr(AttrArgs),
     /// Toggle a checkmark menu item and report the new state.
     Toggle(ToggleArgs),
-    /// Get the current state of a specific menu item.
+    /// Get the current state of one or more menu items.
     State(StateArgs),
+    /// Show which menu item a query would target, and how, without acting on
+    /// it — to check a script's queries before relying on them.
+    Resolve(ResolveQueryArgs),
+    /// Trace how a query resolves: the strategy that matched, every candidate
+    /// with its score, the fuzzy confidence ratio, and why auto-resolve did or
+    /// did not pick one — to debug ambiguous matches.
+    Explain(ExplainArgs),
+    /// Render one item as a compact text panel — state, shortcut, help, and
+    /// its siblings — for fzf's --preview. Reuses a recently walked tree.
+    Preview(PreviewArgs),
     /// List running applications with their PIDs.
+    ///
+    /// Fields for --fields: name, pid, bundle_id, frontmost,
+    /// activation_policy, launch_date, has_menu, has_extras.
     Apps(AppsArgs),
+    /// Report what has keyboard focus: the focused app and UI element and,
+    /// with --highlighted, the menu item selected in an open menu.
+    Focused(FocusedArgs),
     /// Check if Accessibility permission is granted.
-    CheckAccess,
+    CheckAccess(CheckAccessArgs),
+    /// Measure how fast an application's menus can be read.
+    Bench(BenchArgs),
+    /// Serve JSON-RPC 2.0 on stdin/stdout (one request per line).
+    /// Methods mirror the subcommands; menu trees are cached between calls.
+    Rpc,
+    /// Run an alias from the config file's `[aliases]` table.
+    Do(DoArgs),
+    /// Print a shell completion script to stdout.
+    Completions(CompletionsArgs),
+    /// Print the man page (roff) to stdout, or write one per subcommand.
+    Man(ManArgs),
+}
+
+impl Command {
+    /// The `--app` of a command that works on one app's menus — `Some(None)`
+    /// for the frontmost app. `None` when there is no such app, including
+    /// when the menus come from a `--from-file` snapshot or several apps.
+    // The inner `None` is meaningful: it is how `resolve_target` spells "frontmost".
+    #[allow(clippy::option_option)]
+    #[must_use]
+    pub fn target(&self) -> Option<Option<&str>> {
+        let (app, snapshot) = match self {
+            Self::List(args) => (single_app(&args.app)?, args.from_file.is_some()),
+            Self::Search(args) => (single_app(&args.app)?, args.from_file.is_some()),
+            Self::State(args) => (single_app(&args.app)?, args.from_file.is_some()),
+            Self::Click(args) => (args.app.as_deref(), false),
+            Self::Toggle(args) => (args.app.as_deref(), false),
+            Self::Action(args) => (args.item.lookup.app.as_deref(), false),
+            Self::Attr(args) => (args.item.lookup.app.as_deref(), false),
+            Self::Resolve(args) => (args.lookup.app.as_deref(), false),
+            Self::Explain(args) => (args.lookup.app.as_deref(), false),
+            Self::Preview(args) => (args.app.as_deref(), false),
+            Self::Bench(args) => (args.app.as_deref(), false),
+            Self::Apps(_)
+            | Self::Focused(_)
+            | Self::CheckAccess(_)
+            | Self::Rpc
+            | Self::Do(_)
+            | Self::Completions(_)
+            | Self::Man(_) => return None,
+        };
+        (!snapshot).then_some(app)
+    }
+
+    /// Drop the `--app` of a command reading a `--from-file` snapshot, which
+    /// holds its own app. Only a default (from `MENUCLI_APP` or the config
+    /// file) can get here: `--app` itself is rejected while parsing.
+    pub fn clear_snapshot_app(&mut self) {
+        let (app, snapshot) = match self {
+            Self::List(args) => (&mut args.app, args.from_file.is_some()),
+            Self::Search(args) => (&mut args.app, args.from_file.is_some()),
+            Self::State(args) => (&mut args.app, args.from_file.is_some()),
+            _ => return,
+        };
+        if snapshot {
+            app.clear();
+        }
+    }
+}
+
+/// The one app a repeatable `--app` names, `Some(None)` for the frontmost;
+/// `None` when it names several.
+// The inner `None` is meaningful, as in `Command::target`.
+#[allow(clippy::option_option)]
+fn single_app(apps: &[String]) -> Option<Option<&str>> {
+    match apps {
+        [] => Some(None),
+        [app] => Some(Some(app.as_str())),
+        _ => None,
+    }
+}
+
+/// Arguments for `menucli do`. The alias is expanded into the command it
+/// names before anything runs (see `cli::config`).
+#[derive(Debug, Parser)]
+pub struct DoArgs {
+    /// Alias name.
+    pub name: String,
+
+    /// More flags for the aliased command (e.g. --dry-run).
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+/// Arguments for `menucli completions`.
+#[derive(Debug, Parser)]
+#[command(after_help = "Install, e.g.:
+  echo 'source <(menucli completions zsh)' >> ~/.zshrc
+  menucli completions bash > ~/.local/share/bash-completion/completions/menucli
+  menucli completions fish > ~/.config/fish/completions/menucli.fish
+  menucli completions nushell | save -f ~/.config/nushell/menucli.nu")]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for.
+    pub shell: CompletionShell,
+}
+
+/// Arguments for `menucli man`.
+#[derive(Debug, Parser)]
+pub struct ManArgs {
+    /// Write `menucli.1` and a `menucli-COMMAND.1` page per subcommand into
+    /// DIR instead of printing `menucli.1`.
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+}
+
+/// Shells `menucli completions` can generate for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
 }
 
 /// Arguments for `menucli list`.
 #[derive(Debug, Parser)]
 pub struct ListArgs {
-    /// Target application: name, PID, or bundle ID.
-    /// Defaults to the frontmost application.
-    #[arg(long, value_name = "NAME|PID|BUNDLE_ID")]
-    pub app: Option<String>,
+    /// Target application: name, PID, bundle ID, `.app` bundle path, or a
+    /// `glob:` / `re:` pattern matched against names and bundle IDs. Defaults
+    /// to the frontmost application. Repeat it to list several apps at once,
+    /// each item attributed to its app.
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
+    pub app: Vec<String>,
+
+    #[command(flatten)]
+    pub focus: FocusArgs,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
 
     /// Output as flat list with full path notation (default when not a TTY).
     #[arg(long)]
@@ -99,30 +426,103 @@ pub struct ListArgs {
     #[arg(long)]
     pub enabled_only: bool,
 
+    /// Only include leaf items, leaving out menus and submenus.
+    #[arg(long, conflicts_with = "tree")]
+    pub leaf_only: bool,
+
+    /// Only include items with a checkmark.
+    #[arg(long, conflicts_with = "tree")]
+    pub checked_only: bool,
+
+    /// Only include items with a keyboard shortcut.
+    #[arg(long, conflicts_with = "tree")]
+    pub with_shortcut: bool,
+
+    /// Only include items with this AX role.
+    #[arg(long, value_name = "ROLE", conflicts_with = "tree")]
+    pub role: Option<String>,
+
+    /// Print how many items match instead of the items (per menu in a table,
+    /// the bare total with --output path).
+    #[arg(long, conflicts_with = "tree")]
+    pub count: bool,
+
     /// Maximum recursion depth (default: unlimited).
     #[arg(long, value_name = "N")]
     pub depth: Option<usize>,
 
+    /// Only walk these top-level menus (comma-separated titles, e.g. "File,Edit").
+    #[arg(long, value_name = "MENUS", value_delimiter = ',')]
+    pub menu: Vec<String>,
+
     /// List status bar / menu extras (right-side menu bar) instead of app menus.
     /// Without --app, scans all running apps.
     #[arg(long)]
     pub extras: bool,
+
+    /// On Ctrl-C, print the items read so far (then exit 130) instead of
+    /// only an error.
+    #[arg(long)]
+    pub partial: bool,
+
+    /// Exit with code 5 instead of printing an empty result.
+    #[arg(long)]
+    pub fail_if_empty: bool,
+
+    /// With --extras and no --app: how many apps to scan at once (default: 8).
+    #[arg(long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /// Read the menu tree from a saved `list --tree --json` snapshot (or a
+    /// `MENUCLI_FIXTURE`-style file) instead of a live app.
+    #[arg(long, value_name = "FILE", conflicts_with = "extras")]
+    pub from_file: Option<PathBuf>,
 }
 
 /// Arguments for `menucli search`.
 #[derive(Debug, Parser)]
 pub struct SearchArgs {
-    /// Search query string.
+    /// Search query string. Space-separated terms must all match; prefix a
+    /// term with ! to exclude it (e.g., "export pdf !screen").
     pub query: String,
 
-    /// Target application.
-    #[arg(long, value_name = "NAME|PID|BUNDLE_ID")]
-    pub app: Option<String>,
+    /// Target application. Repeat it to search several apps at once.
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
+    pub app: Vec<String>,
+
+    #[command(flatten)]
+    pub focus: FocusArgs,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
 
-    /// Maximum number of results to return.
+    /// Only consider items inside this menu or submenu path.
+    #[arg(long, value_name = "PATH")]
+    pub within: Option<String>,
+
+    /// Maximum number of results to return; 0 returns every match.
     #[arg(long, value_name = "N", default_value = "10")]
     pub limit: usize,
 
+    /// Return every match (same as --limit 0), for tools that do their own
+    /// ranking.
+    #[arg(long, conflicts_with = "limit")]
+    pub no_limit: bool,
+
+    /// Nest each Option-key alternate under its primary item (e.g. "Close
+    /// All" under "Close") instead of giving it a result of its own, so
+    /// alternates don't count toward --limit. Implies --alternates.
+    #[arg(long)]
+    pub collapse_alternates: bool,
+
     /// Use exact substring match instead of fuzzy.
     #[arg(long)]
     pub exact: bool,
@@ -131,73 +531,807 @@ pub struct SearchArgs {
     #[arg(long)]
     pub case_sensitive: bool,
 
+    /// Also match against items' help (tooltip) text.
+    #[arg(long)]
+    pub include_help: bool,
+
+    /// Drop fuzzy matches scoring below this, before --limit applies. A
+    /// matched character is worth about 16, plus bonuses for word starts and
+    /// runs, minus gaps: a tight match for a 5-letter query scores 80 or more.
+    #[arg(long, value_name = "SCORE", default_value_t = 0)]
+    pub min_score: u32,
+
+    #[command(flatten)]
+    pub filters: SearchFilterArgs,
+
     /// Search status bar / menu extras instead of app menus.
     #[arg(long)]
     pub extras: bool,
+
+    /// On Ctrl-C, print the items read so far (then exit 130) instead of
+    /// only an error.
+    #[arg(long)]
+    pub partial: bool,
+
+    /// Exit with code 5 instead of printing an empty result.
+    #[arg(long)]
+    pub fail_if_empty: bool,
+
+    /// With --extras and no --app: how many apps to scan at once (default: 8).
+    #[arg(long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /// Read the menu tree from a saved `list --tree --json` snapshot (or a
+    /// `MENUCLI_FIXTURE`-style file) instead of a live app.
+    #[arg(long, value_name = "FILE", conflicts_with = "extras")]
+    pub from_file: Option<PathBuf>,
+}
+
+/// Which app is frontmost around a command, shared by the commands that read menus.
+#[derive(Debug, Args)]
+pub struct FocusArgs {
+    /// Bring the app to the front and wait for its menu bar before reading
+    /// it; some apps (Electron, Java) expose stale or no menus in the background.
+    #[arg(long)]
+    pub activate: bool,
+
+    /// Afterwards, re-activate the app that was frontmost before menucli ran
+    /// if --activate or the command itself moved focus away from it.
+    #[arg(long)]
+    pub restore_focus: bool,
+
+    /// If the --app is not running, launch it (by name, bundle ID, or `.app`
+    /// path) and wait for its menu bar before going on.
+    #[arg(long)]
+    pub launch: bool,
+}
+
+/// How menus are walked, shared by the commands that read menus.
+#[derive(Debug, Args)]
+pub struct WalkArgs {
+    /// Open submenus that list no items so apps that fill them lazily
+    /// (Open Recent, Services) populate them; walks the menus one at a time.
+    #[arg(long)]
+    pub open_menus: bool,
+}
+
+/// Disk cache of menu trees, shared by `list` and `search`.
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    /// Reuse the menu tree saved by a recent run instead of walking the app's
+    /// menus, and save fresh walks (in `~/.cache/menucli`, or `$MENUCLI_CACHE_DIR`).
+    /// Relaunching or updating the app invalidates it.
+    #[arg(long, overrides_with = "no_cache")]
+    pub cache: bool,
+
+    /// Always walk the live menus (overrides --cache).
+    #[arg(long, overrides_with = "cache")]
+    pub no_cache: bool,
+
+    /// Seconds a cached tree stays valid.
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    pub cache_ttl: u64,
+}
+
+/// Path resolution tuning shared by `click`, `toggle`, `state`, and `resolve`.
+#[derive(Debug, Args)]
+pub struct ResolveArgs {
+    /// Break ties between same-titled matches with these rules, in order
+    /// (comma-separated: enabled, leaf, shallow).
+    #[arg(long, value_name = "RULES", value_delimiter = ',')]
+    pub prefer: Vec<Prefer>,
+
+    /// Auto-pick the best fuzzy match only if it outscores the runner-up by
+    /// this factor; lower it for apps that keep reporting ambiguous matches.
+    #[arg(long, value_name = "RATIO", default_value_t = FUZZY_AUTO_RESOLVE_RATIO)]
+    pub fuzzy_ratio: f32,
+
+    /// Ignore fuzzy matches scoring below this.
+    #[arg(long, value_name = "SCORE", default_value_t = 0)]
+    pub min_score: u32,
+
+    /// Also fuzzy-match against items' help (tooltip) text.
+    #[arg(long)]
+    pub include_help: bool,
+
+    /// Ignore accents when comparing titles exactly (e.g., "Resume" matches "Résumé").
+    #[arg(long)]
+    pub fold_diacritics: bool,
+
+    /// Only accept exact path or title matches (no fuzzy resolution).
+    #[arg(long)]
+    pub exact: bool,
+
+    /// Like --exact, but titles must match byte-for-byte (case, `…`, quotes).
+    #[arg(long)]
+    pub exact_case: bool,
+
+    /// Also match path segments against items' identifiers and their titles
+    /// in this language, read from the app bundle (for localized apps).
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<Lang>,
+}
+
+/// Languages `--lang` can match menu titles in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    /// English (the app's development localization).
+    En,
+}
+
+impl ResolveArgs {
+    /// Convert to the library's resolution options.
+    #[must_use]
+    pub fn options(&self) -> ResolveOptions {
+        ResolveOptions {
+            prefer: self.prefer.clone(),
+            fuzzy_ratio: self.fuzzy_ratio,
+            min_score: self.min_score,
+            include_help: self.include_help,
+            fold_diacritics: self.fold_diacritics,
+            exact: self.exact || self.exact_case,
+            case_sensitive: self.exact_case,
+        }
+    }
+}
+
+/// Item filters for `menucli search`, applied before scoring and `--limit`.
+#[derive(Debug, Args)]
+pub struct SearchFilterArgs {
+    /// Only enabled items.
+    #[arg(long)]
+    pub enabled_only: bool,
+
+    /// Only items with a checkmark.
+    #[arg(long)]
+    pub checked: bool,
+
+    /// Only items with this AX role.
+    #[arg(long, value_name = "ROLE")]
+    pub role: Option<String>,
+
+    /// Only items at most this many levels deep (1 = top-level menus).
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Only items with a keyboard shortcut.
+    #[arg(long)]
+    pub has_shortcut: bool,
+}
+
+impl SearchFilterArgs {
+    /// Convert to the library's search filters.
+    #[must_use]
+    pub fn filters(&self) -> SearchFilters {
+        SearchFilters {
+            enabled_only: self.enabled_only,
+            checked: self.checked,
+            role: self.role.clone(),
+            max_depth: self.max_depth,
+            has_shortcut: self.has_shortcut,
+        }
+    }
 }
 
 /// Arguments for `menucli click`.
 #[derive(Debug, Parser)]
 pub struct ClickArgs {
-    /// Menu item path or partial match.
-    /// Examples: "File::Save As…", "Save As", "save as"
-    pub path: String,
+    /// Menu item paths or partial matches, clicked in order. `>` and `/` also
+    /// separate path segments when the query has no `::`.
+    /// Examples: "File::Save As…", "File > Save As…", "Save As", "save as"
+    #[arg(
+        required_unless_present_any = ["id", "stdin"],
+        add = ArgValueCompleter::new(complete::paths)
+    )]
+    pub paths: Vec<String>,
+
+    /// Also read paths from stdin, one per line (e.g. `list --output path`).
+    #[arg(long, conflicts_with = "id")]
+    pub stdin: bool,
+
+    /// Stable item ID (the `id` field of list/search output) instead of a path.
+    #[arg(long, conflicts_with = "paths")]
+    pub id: Option<String>,
+
+    /// Wait this long between clicks when clicking several items.
+    #[arg(long, value_name = "DURATION", value_parser = parse_timeout)]
+    pub delay: Option<Duration>,
+
+    /// After clicking, block until this holds: `state:PATH=STATE` (checked,
+    /// unchecked, enabled, disabled, exists, gone) or `window-title:TEXT`.
+    #[arg(long, value_name = "CONDITION", value_parser = parse_wait_condition)]
+    pub wait_for: Option<WaitCondition>,
+
+    /// Give up on `--wait-for` after this long (exit 1, `condition_not_met`).
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = parse_timeout,
+        default_value = "5s",
+        requires = "wait_for"
+    )]
+    pub wait_timeout: Duration,
 
     /// Target application.
-    #[arg(long, value_name = "NAME|PID|BUNDLE_ID")]
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
     pub app: Option<String>,
 
+    #[command(flatten)]
+    pub focus: FocusArgs,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
+
+    /// Only consider items inside this menu or submenu path.
+    #[arg(long, value_name = "PATH")]
+    pub within: Option<String>,
+
+    #[command(flatten)]
+    pub resolve: ResolveArgs,
+
     /// Preview the resolved item without clicking it.
     #[arg(long)]
     pub dry_run: bool,
 
-    /// Require exact path match (no fuzzy resolution).
-    #[arg(long)]
-    pub exact: bool,
-
     /// Click a status bar / menu extras item instead of an app menu item.
     #[arg(long)]
     pub extras: bool,
+
+    /// When a wildcard path, or an --app pattern, matches several items or
+    /// apps, use the first.
+    #[arg(long, conflicts_with = "all")]
+    pub first: bool,
+
+    /// When a wildcard path matches several items, click all of them.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Hold these modifier keys while clicking (comma-separated: command,
+    /// shift, option, control); many apps vary a command by modifier.
+    #[arg(long, value_name = "KEYS", value_delimiter = ',')]
+    pub modifiers: Vec<Modifier>,
+}
+
+/// A post-condition `click --wait-for` blocks on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitCondition {
+    /// `state:PATH=STATE`: the item at the exact path `PATH` is in `STATE`.
+    State {
+        /// Exact `::` path of the item.
+        path: String,
+        /// The state to wait for.
+        state: ItemCondition,
+    },
+    /// `window-title:TEXT`: a window's title contains `TEXT` (ignoring case).
+    WindowTitle(String),
+}
+
+impl std::fmt::Display for WaitCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::State { path, state } => write!(f, "state:{path}={}", state.name()),
+            Self::WindowTitle(text) => write!(f, "window-title:{text}"),
+        }
+    }
+}
+
+/// Item states `--wait-for state:PATH=STATE` can wait for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ItemCondition {
+    /// Shows a checkmark.
+    Checked,
+    /// Shows no mark.
+    Unchecked,
+    /// Is enabled.
+    Enabled,
+    /// Is disabled.
+    Disabled,
+    /// Is in the menu.
+    Exists,
+    /// Is no longer in the menu.
+    Gone,
+}
+
+impl ItemCondition {
+    /// The condition's name as written.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Checked => "checked",
+            Self::Unchecked => "unchecked",
+            Self::Enabled => "enabled",
+            Self::Disabled => "disabled",
+            Self::Exists => "exists",
+            Self::Gone => "gone",
+        }
+    }
+}
+
+/// Parse `--wait-for`.
+fn parse_wait_condition(s: &str) -> Result<WaitCondition, String> {
+    if let Some(text) = s.strip_prefix("window-title:") {
+        let text = text.trim().trim_matches('"');
+        if !text.is_empty() {
+            return Ok(WaitCondition::WindowTitle(text.to_owned()));
+        }
+    } else if let Some((path, state)) = s.strip_prefix("state:").and_then(|r| r.rsplit_once('=')) {
+        if let Ok(state) = ItemCondition::from_str(state.trim(), true) {
+            if !path.trim().is_empty() {
+                return Ok(WaitCondition::State {
+                    path: path.trim().to_owned(),
+                    state,
+                });
+            }
+        }
+    }
+    Err(format!(
+        "expected state:PATH=STATE (checked, unchecked, enabled, disabled, exists, gone) \
+         or window-title:TEXT, got '{s}'"
+    ))
 }
 
 /// Arguments for `menucli toggle`.
 #[derive(Debug, Parser)]
 pub struct ToggleArgs {
     /// Menu item path or partial match.
+    #[arg(add = ArgValueCompleter::new(complete::paths))]
     pub path: String,
 
     /// Target application.
-    #[arg(long, value_name = "NAME|PID|BUNDLE_ID")]
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
     pub app: Option<String>,
 
+    #[command(flatten)]
+    pub focus: FocusArgs,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
+
+    /// Only consider items inside this menu or submenu path.
+    #[arg(long, value_name = "PATH")]
+    pub within: Option<String>,
+
+    #[command(flatten)]
+    pub resolve: ResolveArgs,
+
     /// Show current state without toggling.
     #[arg(long)]
     pub dry_run: bool,
 
+    /// End in this state: skip the press if the item is already there, press
+    /// again if the first press changed nothing, and fail if it still differs.
+    #[arg(long, value_name = "STATE")]
+    pub expect: Option<ExpectState>,
+
     /// Toggle a status bar / menu extras item.
     #[arg(long)]
     pub extras: bool,
 }
 
-/// Arguments for `menucli state`.
+/// Arguments for `menucli resolve`.
 #[derive(Debug, Parser)]
-pub struct StateArgs {
-    /// Menu item path or partial match.
+pub struct ResolveQueryArgs {
+    /// Menu item path or partial match, as click, toggle, or state take it.
+    #[arg(add = ArgValueCompleter::new(complete::paths))]
     pub path: String,
 
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+
+    /// List every item the query matches, best first, instead of failing
+    /// when it is ambiguous.
+    #[arg(long)]
+    pub all: bool,
+}
+
+/// Where a single-item command looks for its item, shared by `resolve`,
+/// `explain`, `action`, and `attr`.
+#[derive(Debug, Args)]
+pub struct LookupArgs {
     /// Target application.
-    #[arg(long, value_name = "NAME|PID|BUNDLE_ID")]
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
     pub app: Option<String>,
 
+    #[command(flatten)]
+    pub focus: FocusArgs,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
+
+    /// Only consider items inside this menu or submenu path.
+    #[arg(long, value_name = "PATH")]
+    pub within: Option<String>,
+
+    #[command(flatten)]
+    pub resolve: ResolveArgs,
+
+    /// Look among status bar / menu extras items instead of app menu items.
+    #[arg(long)]
+    pub extras: bool,
+}
+
+/// Arguments for `menucli action`.
+#[derive(Debug, Parser)]
+pub struct ActionArgs {
+    #[command(flatten)]
+    pub item: ItemArgs,
+
+    /// The AX action to perform, e.g. AXShowMenu, AXCancel, or AXPress. It
+    /// must be one the item supports.
+    #[arg(long, value_name = "ACTION")]
+    pub name: Option<String>,
+
+    /// Open the item's menu with AXShowMenu (the only --name it takes),
+    /// capture it to this PNG file, then close the menu again. Needs Screen
+    /// Recording permission.
+    #[arg(long, value_name = "FILE")]
+    pub screenshot: Option<PathBuf>,
+}
+
+/// The one item a command reads or acts on, by path or `--id`, and where
+/// to look for it.
+#[derive(Debug, Args)]
+pub struct ItemArgs {
+    /// Menu item path or partial match, as click takes it.
+    #[arg(required_unless_present = "id", add = ArgValueCompleter::new(complete::paths))]
+    pub path: Option<String>,
+
+    /// Stable item ID (the `id` field of list/search output) instead of a path.
+    #[arg(long, conflicts_with = "path")]
+    pub id: Option<String>,
+
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+
+    /// When a wildcard path, or an --app pattern, matches several items or
+    /// apps, use the first.
+    #[arg(long)]
+    pub first: bool,
+}
+
+/// Arguments for `menucli attr`.
+#[derive(Debug, Parser)]
+pub struct AttrArgs {
+    #[command(flatten)]
+    pub item: ItemArgs,
+
+    /// The AX attribute to read, e.g. AXHelp, AXPosition, or AXSize.
+    #[arg(
+        value_name = "ATTRIBUTE",
+        required_unless_present = "attr",
+        conflicts_with = "attr"
+    )]
+    pub attribute: Option<String>,
+
+    /// The AX attribute to read, as a flag (needed with --id).
+    #[arg(long, value_name = "ATTRIBUTE")]
+    pub attr: Option<String>,
+}
+
+impl AttrArgs {
+    /// The attribute to read, given either way.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.attribute
+            .as_deref()
+            .or(self.attr.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Arguments for `menucli preview`.
+#[derive(Debug, Parser)]
+pub struct PreviewArgs {
+    /// Menu item path, as `list --output path` prints it (fzf's `{}`).
+    #[arg(add = ArgValueCompleter::new(complete::paths))]
+    pub path: String,
+
+    /// Target application.
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
+    pub app: Option<String>,
+
+    /// Preview a status bar / menu extras item instead of an app menu item.
+    #[arg(long)]
+    pub extras: bool,
+
+    /// Always walk the live menus instead of reusing a recent walk from the
+    /// disk cache.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Seconds a cached tree stays valid. Longer than --cache-ttl's default,
+    /// since one fzf session previews many items of the same tree.
+    #[arg(long, value_name = "SECS", default_value_t = 120)]
+    pub cache_ttl: u64,
+}
+
+/// Arguments for `menucli explain`.
+#[derive(Debug, Parser)]
+pub struct ExplainArgs {
+    /// Menu item path or partial match, as click, toggle, or state take it.
+    #[arg(add = ArgValueCompleter::new(complete::paths))]
+    pub path: String,
+
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+
+    /// Maximum number of candidates to list; 0 lists every one.
+    #[arg(long, value_name = "N", default_value = "10")]
+    pub limit: usize,
+}
+
+/// Checkmark states `toggle --expect` can require.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpectState {
+    /// Checked.
+    On,
+    /// Unchecked.
+    Off,
+}
+
+impl ExpectState {
+    /// The `checked` value this state stands for.
+    #[must_use]
+    pub fn checked(self) -> bool {
+        self == Self::On
+    }
+}
+
+/// Arguments for `menucli state`.
+#[derive(Debug, Parser)]
+pub struct StateArgs {
+    /// Menu item paths or partial matches, all resolved against one tree.
+    #[arg(
+        required_unless_present_any = ["id", "paths_from"],
+        add = ArgValueCompleter::new(complete::paths)
+    )]
+    pub paths: Vec<String>,
+
+    /// Also read paths from FILE (`-` for stdin), one per line.
+    #[arg(long, value_name = "FILE", value_parser = read_path_list, conflicts_with = "id")]
+    pub paths_from: Option<PathList>,
+
+    /// Stable item ID (the `id` field of list/search output) instead of a path.
+    #[arg(long, conflicts_with = "paths")]
+    pub id: Option<String>,
+
+    /// Target application. Repeat it to read the paths in several apps at
+    /// once; an app lacking one of them is left out.
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
+    pub app: Vec<String>,
+
+    #[command(flatten)]
+    pub focus: FocusArgs,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
+
+    /// Only consider items inside this menu or submenu path.
+    #[arg(long, value_name = "PATH")]
+    pub within: Option<String>,
+
+    #[command(flatten)]
+    pub resolve: ResolveArgs,
+
     /// Get state of a status bar / menu extras item.
     #[arg(long)]
     pub extras: bool,
+
+    /// When a wildcard path, or an --app pattern, matches several items or
+    /// apps, use the first.
+    #[arg(long, conflicts_with = "all")]
+    pub first: bool,
+
+    /// When a wildcard path matches several items, report all of them.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Fail (exit 6, `assertion_failed`) unless every matched item meets
+    /// these conditions; a missing item fails as not found.
+    #[arg(long, value_name = "CONDITION", value_delimiter = ',')]
+    pub assert: Vec<StateAssertion>,
+
+    /// Read the menu tree from a saved `list --tree --json` snapshot (or a
+    /// `MENUCLI_FIXTURE`-style file) instead of a live app.
+    #[arg(long, value_name = "FILE", conflicts_with = "extras")]
+    pub from_file: Option<PathBuf>,
+}
+
+impl StateArgs {
+    /// The positional paths followed by those from `--paths-from`.
+    #[must_use]
+    pub fn all_paths(&self) -> Vec<&str> {
+        let from_file = self.paths_from.iter().flat_map(|list| &list.0);
+        self.paths
+            .iter()
+            .chain(from_file)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Menu paths read by `--paths-from`.
+#[derive(Debug, Clone)]
+pub struct PathList(pub Vec<String>);
+
+/// Parse `--paths-from`.
+fn read_path_list(file: &str) -> Result<PathList, String> {
+    read_lines(file).map(PathList)
+}
+
+/// Read `file` (or stdin for `-`) as one path per line, skipping blank lines.
+pub(crate) fn read_lines(file: &str) -> Result<Vec<String>, String> {
+    let text = if file == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(file)
+    }
+    .map_err(|e| format!("cannot read '{file}': {e}"))?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Conditions `state --assert` can check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StateAssertion {
+    /// Shows a checkmark (a dash does not count).
+    Checked,
+    /// Is enabled.
+    Enabled,
+    /// Resolves at all.
+    Exists,
+}
+
+impl StateAssertion {
+    /// The flag value, for error messages.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Checked => "checked",
+            Self::Enabled => "enabled",
+            Self::Exists => "exists",
+        }
+    }
+}
+
+/// Arguments for `menucli focused`.
+#[derive(Debug, Parser)]
+pub struct FocusedArgs {
+    /// Also report the menu item highlighted in the focused app's open menu,
+    /// if any. Reads the app's whole menu bar.
+    #[arg(long)]
+    pub highlighted: bool,
+
+    /// Look for the highlighted item among the app's status bar extras
+    /// instead of its menu bar.
+    #[arg(long, requires = "highlighted")]
+    pub extras: bool,
 }
 
 /// Arguments for `menucli apps`.
 #[derive(Debug, Parser)]
+// Each bool is its own flag.
+#[allow(clippy::struct_excessive_bools)]
 pub struct AppsArgs {
+    /// Also list background processes and agents that have no name, shown by
+    /// bundle ID or executable name instead.
+    #[arg(long)]
+    pub all: bool,
+
     /// Show only the frontmost application.
     #[arg(long)]
     pub frontmost: bool,
+
+    /// Show only apps whose name or bundle ID contains PATTERN, ignoring case.
+    /// A `*` (any run of characters) or `?` (one character) makes it a glob
+    /// that must match the whole name (`'chrom*'`), as with `--app glob:`.
+    #[arg(long, value_name = "PATTERN")]
+    pub filter: Option<String>,
+
+    /// Treat --filter as a regular expression (still ignoring case).
+    #[arg(long, requires = "filter")]
+    pub regex: bool,
+
+    /// Show only apps with a menu bar menucli can read. Each app is probed
+    /// with a short AX timeout (--ax-timeout overrides it).
+    #[arg(long)]
+    pub with_menu: bool,
+
+    /// Show only apps with status bar extras, probed like --with-menu.
+    #[arg(long)]
+    pub with_extras: bool,
+
+    /// Order of the listed apps.
+    #[arg(long, value_name = "KEY", default_value = "name")]
+    pub sort: AppSort,
+}
+
+/// Orders `menucli apps --sort` can list apps in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum AppSort {
+    /// By name.
+    #[default]
+    Name,
+    /// By process ID, oldest processes first.
+    Pid,
+    /// The frontmost app first, then by name.
+    Frontmost,
+    /// Most recently launched first; apps without a launch date last.
+    LaunchDate,
+}
+
+/// Arguments for `menucli check-access`.
+#[derive(Debug, Parser)]
+pub struct CheckAccessArgs {
+    /// If permission is missing, open System Settings at Privacy & Security →
+    /// Accessibility and reveal the terminal app in Finder, ready to drag into
+    /// the list.
+    #[arg(long)]
+    pub open_settings: bool,
+}
+
+/// Arguments for `menucli bench`.
+#[derive(Debug, Parser)]
+pub struct BenchArgs {
+    /// Target application: name, PID, bundle ID, `.app` bundle path, or a
+    /// `glob:` / `re:` pattern matched against names and bundle IDs. Defaults
+    /// to the frontmost application.
+    #[arg(
+        long,
+        value_name = "NAME|PID|BUNDLE_ID",
+        env = "MENUCLI_APP",
+        add = ArgValueCompleter::new(complete::apps)
+    )]
+    pub app: Option<String>,
+
+    /// Times to repeat each measurement; the median is reported.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 5,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub iterations: u32,
+}
+
+/// Parse `--ax-timeout`: a positive, finite number of seconds.
+fn parse_ax_timeout(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Ok(secs),
+        _ => Err(format!("expected a positive number of seconds, got '{s}'")),
+    }
 }
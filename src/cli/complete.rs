@@ -0,0 +1,87 @@
+/// Dynamic completion candidates, served through `clap_complete`'s
+/// `CompleteEnv` when a shell runs `COMPLETE=<shell> menucli -- <words>`.
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use clap_complete::CompletionCandidate;
+
+use crate::backend::cache::default_dir;
+use crate::backend::{self, AxBackend, CacheBackend};
+use crate::menu::tree::TreeOptions;
+use crate::menu::{flatten, MenuError};
+
+/// How long completion reuses a walked menu tree. Longer than `--cache-ttl`'s
+/// default: a stale candidate costs far less than a slow TAB.
+const TREE_TTL: Duration = Duration::from_secs(300);
+
+/// Running application names, for `--app`.
+pub fn apps(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Ok(backend) = backend::from_env() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = backend.list_apps().into_iter().map(|a| a.name).collect();
+    names.sort_unstable();
+    names.dedup();
+    candidates(names, current)
+}
+
+/// Menu item paths of the app the command line names (or `MENUCLI_APP`, or
+/// the frontmost app), from the disk cache when a recent walk is there.
+///
+/// An app that cannot be read just has nothing to offer, since an error
+/// message would land in the middle of the user's command line.
+pub fn paths(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Ok(backend) = backend::from_env() else {
+        return Vec::new();
+    };
+    let app = typed_app().or_else(|| std::env::var("MENUCLI_APP").ok());
+    candidates(
+        menu_paths(backend.as_ref(), app.as_deref()).unwrap_or_default(),
+        current,
+    )
+}
+
+/// Every titled item path of `app`.
+fn menu_paths(backend: &dyn AxBackend, app: Option<&str>) -> Result<Vec<String>, MenuError> {
+    let opts = TreeOptions::default();
+    let pid = backend.resolve_target(app).map_err(MenuError::from)?;
+    let tree = match default_dir() {
+        Some(dir) => CacheBackend::new(backend, dir, TREE_TTL).build_tree(pid, None, &opts)?,
+        None => backend.build_tree(pid, None, &opts)?,
+    };
+    Ok(flatten(&tree)
+        .into_iter()
+        .filter(|item| !item.title.is_empty())
+        .map(|item| item.path)
+        .collect())
+}
+
+/// The last `--app` on the command line being completed: the shell passes
+/// its words after `--`.
+fn typed_app() -> Option<String> {
+    let words: Vec<String> = std::env::args_os()
+        .map(|word| word.to_string_lossy().into_owned())
+        .skip_while(|word| word != "--")
+        .skip(1)
+        .collect();
+    let mut app = None;
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if word == "--app" {
+            app = words.next().cloned();
+        } else if let Some(value) = word.strip_prefix("--app=") {
+            app = Some(value.to_owned());
+        }
+    }
+    app
+}
+
+/// The `values` starting with `current`, ignoring case.
+fn candidates(values: Vec<String>, current: &OsStr) -> Vec<CompletionCandidate> {
+    let prefix = current.to_string_lossy().to_lowercase();
+    values
+        .into_iter()
+        .filter(|value| value.to_lowercase().starts_with(&prefix))
+        .map(CompletionCandidate::new)
+        .collect()
+}
@@ -0,0 +1,338 @@
+/// Flag defaults from a TOML config file, for options users would otherwise
+/// repeat on every invocation.
+///
+/// The file is `$MENUCLI_CONFIG`, else `$XDG_CONFIG_HOME/menucli/config.toml`,
+/// else `~/.config/menucli/config.toml`. Each key becomes the default of the
+/// flag with the same name, so the flag's own parser checks the value and a
+/// flag given on the command line still wins:
+///
+/// ```toml
+/// output = "json"
+/// default_app = "Safari"   # --app
+/// ax_timeout = 2.5
+/// fuzzy_ratio = 1.5
+/// min_score = 40
+/// exclude_menus = ["Help", "History"]
+/// cache = true
+/// cache_ttl = 60
+///
+/// [aliases]
+/// mute-mic = { app = "zoom.us", path = "Meeting::Mute Audio" }
+/// sidebar = { app = "Finder", path = "View::Show Sidebar", command = "toggle", flags = ["--expect", "on"] }
+///
+/// [app."com.adobe.Photoshop"]
+/// ax_timeout = 5
+/// open_menus = false
+///
+/// [hooks]
+/// post_toggle = "jq -r .path >> ~/menucli.log"
+/// ```
+///
+/// `menucli do NAME` runs an alias as if its command line had been typed.
+/// An `[app."BUNDLE_ID"]` profile applies on top of the top-level keys when a
+/// command targets that app. `[hooks]` are described in `cli::hooks`.
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+use serde::Deserialize;
+
+use super::args::{Cli, Command};
+use super::hooks::Hooks;
+use crate::backend::AxBackend;
+
+/// Environment variable naming the config file to read.
+pub const CONFIG_ENV: &str = "MENUCLI_CONFIG";
+
+/// The keys a config file may set.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// `--output`.
+    pub output: Option<String>,
+    /// `--app`, for every command that takes one.
+    pub default_app: Option<String>,
+    /// `--ax-timeout`.
+    pub ax_timeout: Option<f32>,
+    /// `--fuzzy-ratio`.
+    pub fuzzy_ratio: Option<f32>,
+    /// `--min-score`.
+    pub min_score: Option<u32>,
+    /// `--exclude-menus`.
+    pub exclude_menus: Vec<String>,
+    /// `--cache` for `list` and `search` (`--no-cache` still turns it off).
+    pub cache: Option<bool>,
+    /// `--cache-ttl`.
+    pub cache_ttl: Option<u64>,
+    /// `[aliases]`: the commands `menucli do NAME` runs.
+    pub aliases: BTreeMap<String, Alias>,
+    /// `[hooks]`: shell commands run around actions.
+    pub hooks: Hooks,
+    /// `[app."BUNDLE_ID"]`: per-app overrides of the keys above.
+    #[serde(rename = "app")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings for one app, for apps that need different knobs than the rest.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// `--ax-timeout`.
+    pub ax_timeout: Option<f32>,
+    /// `--exclude-menus`.
+    pub exclude_menus: Vec<String>,
+    /// `--open-menus`.
+    pub open_menus: Option<bool>,
+    /// `--fuzzy-ratio`.
+    pub fuzzy_ratio: Option<f32>,
+    /// `--min-score`.
+    pub min_score: Option<u32>,
+}
+
+/// A named menu action: `menucli do NAME` runs
+/// `menucli COMMAND PATH --app APP FLAGS...`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Alias {
+    /// Target application; the frontmost one if unset.
+    pub app: Option<String>,
+    /// Menu path or query, exactly as the command would take it.
+    pub path: String,
+    /// The command to run it with.
+    #[serde(default)]
+    pub command: AliasCommand,
+    /// Further flags for the command, e.g. `["--expect", "on"]`.
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+/// Commands an alias can run.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasCommand {
+    /// `menucli click`.
+    #[default]
+    Click,
+    /// `menucli toggle`.
+    Toggle,
+    /// `menucli state`.
+    State,
+}
+
+impl AliasCommand {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Click => "click",
+            Self::Toggle => "toggle",
+            Self::State => "state",
+        }
+    }
+}
+
+impl Config {
+    /// Read the config file, exiting with a usage error if it cannot be read
+    /// or has unknown keys.
+    #[must_use]
+    pub fn load() -> Self {
+        load().unwrap_or_else(|message| Cli::command().error(ErrorKind::Io, message).exit())
+    }
+
+    /// Parse the command line with this config's defaults applied and
+    /// `do NAME` expanded, exiting with a usage error if it does not parse
+    /// or names no alias.
+    #[must_use]
+    pub fn parse(&self) -> Cli {
+        self.parse_with(None)
+    }
+
+    /// Parse the command line again with the profile of the app `cli`'s
+    /// command targets, if the config has one. The app is looked up through
+    /// `backend`; if that fails, `cli` is returned as is and the command
+    /// reports the problem itself.
+    #[must_use]
+    pub fn apply_profile(&self, cli: Cli, backend: &dyn AxBackend) -> Cli {
+        if self.profiles.is_empty() {
+            return cli;
+        }
+        let Some(target) = cli.command.target() else {
+            return cli;
+        };
+        let Ok(pid) = backend.resolve_target(target) else {
+            return cli;
+        };
+        let profile = backend
+            .list_apps()
+            .into_iter()
+            .find(|app| app.pid == pid)
+            .and_then(|app| app.bundle_id)
+            .and_then(|id| self.profiles.get(&id));
+        match profile {
+            Some(profile) => self.parse_with(Some(profile)),
+            None => cli,
+        }
+    }
+
+    fn parse_with(&self, profile: Option<&Profile>) -> Cli {
+        let mut defaults = self.defaults();
+        defaults.extend(profile.map(Profile::defaults).unwrap_or_default());
+        let args: Vec<OsString> = std::env::args_os().collect();
+        let cli = parse_from(&defaults, &args);
+        let Command::Do(alias) = &cli.command else {
+            return cli;
+        };
+        match expand(self, &alias.name, &args) {
+            Ok(expanded) => parse_from(&defaults, &expanded),
+            Err(message) => Cli::command()
+                .error(ErrorKind::InvalidValue, message)
+                .exit(),
+        }
+    }
+
+    /// The flag defaults this config sets, by argument ID.
+    fn defaults(&self) -> Vec<(&'static str, String)> {
+        flag_values([
+            ("output", self.output.clone()),
+            ("app", self.default_app.clone()),
+            ("ax_timeout", self.ax_timeout.map(|v| v.to_string())),
+            ("fuzzy_ratio", self.fuzzy_ratio.map(|v| v.to_string())),
+            ("min_score", self.min_score.map(|v| v.to_string())),
+            ("exclude_menus", list(&self.exclude_menus)),
+            ("cache", self.cache.map(|v| v.to_string())),
+            ("cache_ttl", self.cache_ttl.map(|v| v.to_string())),
+        ])
+    }
+}
+
+impl Profile {
+    /// The flag defaults this profile sets, by argument ID.
+    fn defaults(&self) -> Vec<(&'static str, String)> {
+        flag_values([
+            ("ax_timeout", self.ax_timeout.map(|v| v.to_string())),
+            ("exclude_menus", list(&self.exclude_menus)),
+            ("open_menus", self.open_menus.map(|v| v.to_string())),
+            ("fuzzy_ratio", self.fuzzy_ratio.map(|v| v.to_string())),
+            ("min_score", self.min_score.map(|v| v.to_string())),
+        ])
+    }
+}
+
+/// Keep the keys that are set.
+fn flag_values<const N: usize>(
+    values: [(&'static str, Option<String>); N],
+) -> Vec<(&'static str, String)> {
+    values
+        .into_iter()
+        .filter_map(|(id, value)| value.map(|v| (id, v)))
+        .collect()
+}
+
+/// A list key as the value of a comma-delimited flag; `None` if empty.
+fn list(values: &[String]) -> Option<String> {
+    (!values.is_empty()).then(|| values.join(","))
+}
+
+fn parse_from(defaults: &[(&'static str, String)], args: &[OsString]) -> Cli {
+    let mut command = with_defaults(Cli::command(), defaults);
+    let matches = command
+        .try_get_matches_from_mut(args)
+        .unwrap_or_else(|err| err.exit());
+    // `--from-file` cannot be declared to conflict with `--app`: clap would
+    // also reject an app that only comes from `MENUCLI_APP`.
+    if let Some((_, sub)) = matches.subcommand() {
+        let snapshot = matches!(sub.try_get_one::<PathBuf>("from_file"), Ok(Some(_)));
+        if snapshot && sub.value_source("app") == Some(ValueSource::CommandLine) {
+            command
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "the argument '--from-file <FILE>' cannot be used with '--app <NAME|PID|BUNDLE_ID>'",
+                )
+                .exit();
+        }
+    }
+    let mut cli =
+        Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.format(&mut command).exit());
+    cli.command.clear_snapshot_app();
+    cli
+}
+
+/// The command line `do NAME` stands for: the alias's command, path, app and
+/// flags, followed by every other argument given (global flags and extras).
+fn expand(config: &Config, name: &str, args: &[OsString]) -> Result<Vec<OsString>, String> {
+    let Some(alias) = config.aliases.get(name) else {
+        let known: Vec<&str> = config.aliases.keys().map(String::as_str).collect();
+        return Err(if known.is_empty() {
+            format!("no alias '{name}': the config file has no [aliases]")
+        } else {
+            format!("no alias '{name}' (defined: {})", known.join(", "))
+        });
+    };
+
+    let mut rest: Vec<OsString> = args.iter().skip(1).cloned().collect();
+    if let Some(i) = rest.iter().position(|arg| arg == "do") {
+        rest.remove(i);
+        if let Some(j) = rest[i..].iter().position(|arg| arg == name) {
+            rest.remove(i + j);
+        }
+    }
+    let app_given = rest
+        .iter()
+        .any(|arg| arg == "--app" || arg.to_string_lossy().starts_with("--app="));
+
+    let mut expanded: Vec<OsString> = vec![
+        args.first().cloned().unwrap_or_else(|| "menucli".into()),
+        alias.command.name().into(),
+        alias.path.clone().into(),
+    ];
+    if let Some(app) = alias.app.as_ref().filter(|_| !app_given) {
+        expanded.extend(["--app".into(), app.into()]);
+    }
+    expanded.extend(alias.flags.iter().map(OsString::from));
+    expanded.extend(rest);
+    Ok(expanded)
+}
+
+/// Read the config file. A missing file at the default location is an empty
+/// config; one named by `$MENUCLI_CONFIG` must exist.
+fn load() -> Result<Config, String> {
+    let var = |name| std::env::var_os(name).filter(|v| !v.is_empty());
+    if let Some(path) = var(CONFIG_ENV) {
+        return read(Path::new(&path));
+    }
+    let path = var("XDG_CONFIG_HOME")
+        .map(|d| PathBuf::from(d).join("menucli"))
+        .or_else(|| var("HOME").map(|h| PathBuf::from(h).join(".config/menucli")))
+        .map(|d| d.join("config.toml"));
+    match path {
+        Some(path) if path.exists() => read(&path),
+        _ => Ok(Config::default()),
+    }
+}
+
+fn read(path: &Path) -> Result<Config, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read config {}: {e}", path.display()))?;
+    toml::from_str(&text).map_err(|e| format!("invalid config {}: {e}", path.display()))
+}
+
+/// Set `defaults` on every argument of `command` and its subcommands that has
+/// one of their IDs.
+fn with_defaults(command: clap::Command, defaults: &[(&'static str, String)]) -> clap::Command {
+    let mut command = defaults.iter().fold(command, |command, (id, value)| {
+        if command.get_arguments().any(|arg| arg.get_id() == id) {
+            command.mut_arg(*id, |arg| arg.default_value(value.clone()))
+        } else {
+            command
+        }
+    });
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_owned())
+        .collect();
+    for name in names {
+        command = command.mut_subcommand(name, |sub| with_defaults(sub, defaults));
+    }
+    command
+}
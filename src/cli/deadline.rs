@@ -0,0 +1,45 @@
+/// `--timeout`: a deadline for the whole command.
+use std::time::Duration;
+
+use crate::menu::CancelToken;
+
+/// How long a command may keep running after its deadline to stop its walk
+/// and print what it read, before the process is ended outright.
+const GRACE: Duration = Duration::from_secs(1);
+
+/// Expire `cancel` once `timeout` has passed, so walks stop and the command
+/// fails with `MenuError::TimedOut` (or prints `--partial` results first).
+///
+/// Work that never checks the token, such as a single AX call to a wedged
+/// app, cannot be stopped that way; if the command is still running
+/// [`GRACE`] later, `overrun` is called and should end the process.
+pub fn arm(timeout: Duration, cancel: CancelToken, overrun: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(move || {
+        std::thread::sleep(timeout);
+        cancel.expire();
+        std::thread::sleep(GRACE);
+        overrun();
+    });
+}
+
+/// Parse `--timeout`: a positive duration such as `5s`, `500ms`, `2m`, or a
+/// bare number of seconds.
+pub(crate) fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(n) = s.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = s.strip_suffix('s') {
+        (n, 1.0)
+    } else if let Some(n) = s.strip_suffix('m') {
+        (n, 60.0)
+    } else {
+        (s, 1.0)
+    };
+    let invalid = || format!("expected a positive duration like 5s or 500ms, got '{s}'");
+    match number.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() && n > 0.0 => Duration::try_from_secs_f64(n * scale)
+            .ok()
+            .filter(|d| !d.is_zero())
+            .ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
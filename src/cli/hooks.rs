@@ -0,0 +1,110 @@
+/// Shell commands the config file's `[hooks]` table runs around actions.
+///
+/// Each hook runs through `sh -c` with the action's JSON on stdin and
+/// `MENUCLI_HOOK`, `MENUCLI_PID` and `MENUCLI_PATHS` (newline-separated) in
+/// its environment. Its stdout is sent to stderr so menucli's own stdout
+/// stays parseable.
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::menu::MenuError;
+
+/// The `[hooks]` table: a shell command per hook point.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    /// Before `click` presses anything, with the resolved items. Exiting
+    /// non-zero cancels the click.
+    pub pre_click: Option<String>,
+    /// After `click` pressed every item, with the clicked items.
+    pub post_click: Option<String>,
+    /// After `toggle` pressed its item, with the toggle result.
+    pub post_toggle: Option<String>,
+}
+
+/// The points an action can run a hook at.
+#[derive(Debug, Clone, Copy)]
+pub enum Hook {
+    PreClick,
+    PostClick,
+    PostToggle,
+}
+
+impl Hook {
+    fn name(self) -> &'static str {
+        match self {
+            Self::PreClick => "pre_click",
+            Self::PostClick => "post_click",
+            Self::PostToggle => "post_toggle",
+        }
+    }
+}
+
+impl Hooks {
+    /// Run `hook` if one is configured, feeding it `input` as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MenuError::HookFailed` if the command cannot be started or
+    /// exits non-zero.
+    pub fn run<T: Serialize + ?Sized>(
+        &self,
+        hook: Hook,
+        pid: i32,
+        paths: &[&str],
+        input: &T,
+    ) -> Result<(), MenuError> {
+        let command = match hook {
+            Hook::PreClick => &self.pre_click,
+            Hook::PostClick => &self.post_click,
+            Hook::PostToggle => &self.post_toggle,
+        };
+        let Some(command) = command else {
+            return Ok(());
+        };
+        debug!(hook = hook.name(), command, "running hook");
+        let failed = |reason: String| MenuError::HookFailed {
+            hook: hook.name().to_owned(),
+            reason,
+        };
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("MENUCLI_HOOK", hook.name())
+            .env("MENUCLI_PID", pid.to_string())
+            .env("MENUCLI_PATHS", paths.join("\n"))
+            .stdin(Stdio::piped())
+            .stdout(std::io::stderr())
+            .spawn()
+            .map_err(|e| failed(e.to_string()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores its input may exit before reading it.
+            let _ = serde_json::to_writer(&mut stdin, input);
+            let _ = stdin.write_all(b"\n");
+        }
+        let status = child.wait().map_err(|e| failed(e.to_string()))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(failed(status.to_string()))
+        }
+    }
+
+    /// Like [`run`](Self::run) for hooks after the action, whose failure
+    /// cannot undo it: failures are reported on stderr rather than returned.
+    pub fn run_after<T: Serialize + ?Sized>(
+        &self,
+        hook: Hook,
+        pid: i32,
+        paths: &[&str],
+        input: &T,
+    ) {
+        if let Err(err) = self.run(hook, pid, paths, input) {
+            eprintln!("Warning: {err}");
+        }
+    }
+}
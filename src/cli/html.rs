@@ -0,0 +1,159 @@
+/// HTML output: a standalone page with a collapsible menu tree or a sortable
+/// table, for attaching a browsable menu dump to a bug report.
+use std::fmt::Write;
+
+use crate::menu::CheckState;
+use crate::types::MenuTreeOutput;
+
+const STYLE: &str = "\
+body{font:14px -apple-system,BlinkMacSystemFont,sans-serif;margin:2em;color:#1d1d1f}
+h1{font-size:1.4em}h2{font-size:1.1em;margin-top:1.5em}
+footer{margin-top:2em;color:#86868b;font-size:.85em}
+table{border-collapse:collapse}
+th,td{padding:4px 10px;border-bottom:1px solid #d2d2d7;text-align:left;vertical-align:top}
+th{cursor:pointer;user-select:none;background:#f5f5f7}
+th[data-order=asc]::after{content:' ▲'}th[data-order=desc]::after{content:' ▼'}
+details,.leaf{margin-left:1.2em}summary{cursor:pointer}
+kbd{font:12px ui-monospace,monospace;color:#6e6e73;margin-left:.6em}
+mark{background:#fff3b0}.disabled{color:#aeaeb2}.alt{color:#6e6e73}
+";
+
+const SCRIPT: &str = "\
+document.querySelectorAll('th').forEach(th => th.addEventListener('click', () => {
+  const body = th.closest('table').tBodies[0];
+  const asc = th.dataset.order !== 'asc';
+  th.parentNode.querySelectorAll('th').forEach(h => delete h.dataset.order);
+  th.dataset.order = asc ? 'asc' : 'desc';
+  const text = row => row.cells[th.cellIndex].textContent;
+  [...body.rows]
+    .sort((a, b) => text(a).localeCompare(text(b), undefined, {numeric: true}) * (asc ? 1 : -1))
+    .forEach(row => body.append(row));
+}));
+";
+
+/// Escape `text` for use in element content and attribute values.
+#[must_use]
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape `text`, wrapping its `[start, end)` char spans in `<mark>`.
+#[must_use]
+pub fn mark(text: &str, ranges: &[[usize; 2]]) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        if ranges.iter().any(|r| r[0] == i) {
+            out.push_str("<mark>");
+        }
+        out.push_str(&escape(c.encode_utf8(&mut [0; 4])));
+        if ranges.iter().any(|r| r[1] == i + 1) {
+            out.push_str("</mark>");
+        }
+    }
+    out
+}
+
+/// A complete page titled `title`, around already-rendered `body` markup.
+#[must_use]
+pub fn page(title: &str, body: &str, generated_at: &str) -> String {
+    let title = escape(title);
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n{body}<footer>Generated by menucli {} at {generated_at}</footer>\n\
+         <script>\n{SCRIPT}</script>\n</body>\n</html>",
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+/// A section heading.
+#[must_use]
+pub fn heading(text: &str) -> String {
+    format!("<h2>{}</h2>\n", escape(text))
+}
+
+/// One term and its description, for a `<dl>` list.
+#[must_use]
+pub fn term(term: &str, description: &str) -> String {
+    format!(
+        "<dt>{}</dt><dd>{}</dd>\n",
+        escape(term),
+        escape(description)
+    )
+}
+
+/// A sortable table: click a header to sort by that column. `rows` hold
+/// already-escaped markup, one cell per header.
+#[must_use]
+pub fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::from("<table>\n<thead><tr>");
+    for h in headers {
+        let _ = write!(out, "<th>{}</th>", escape(h));
+    }
+    out.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            let _ = write!(out, "<td>{cell}</td>");
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n");
+    out
+}
+
+/// Menus as nested `<details>` elements, expanded, so each submenu can be
+/// collapsed on its own.
+#[must_use]
+pub fn tree(nodes: &[MenuTreeOutput]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        tree_node(node, &mut out);
+    }
+    out
+}
+
+fn tree_node(node: &MenuTreeOutput, out: &mut String) {
+    let mut label = escape(&node.title);
+    match node.check_state {
+        CheckState::On => label.insert_str(0, "✓ "),
+        CheckState::Mixed => label.insert_str(0, "– "),
+        CheckState::Off => {}
+    }
+    if let Some(shortcut) = &node.shortcut {
+        let _ = write!(label, "<kbd>{}</kbd>", escape(shortcut));
+    }
+    let class = match (node.enabled, node.is_alternate) {
+        (false, _) => " class=\"disabled\"",
+        (true, true) => " class=\"alt\"",
+        (true, false) => "",
+    };
+    let path = escape(&node.path);
+
+    if node.children.is_empty() {
+        let _ = writeln!(
+            out,
+            "<div class=\"leaf\" title=\"{path}\"><span{class}>{label}</span></div>"
+        );
+        return;
+    }
+    let _ = writeln!(
+        out,
+        "<details open><summary title=\"{path}\"><span{class}>{label}</span></summary>"
+    );
+    for child in &node.children {
+        tree_node(child, out);
+    }
+    out.push_str("</details>\n");
+}
@@ -0,0 +1,34 @@
+/// Ctrl-C handling: cancel in-flight tree walks instead of dying mid-IPC.
+use std::sync::OnceLock;
+
+use crate::menu::CancelToken;
+
+/// The token the SIGINT handler cancels.
+static TOKEN: OnceLock<CancelToken> = OnceLock::new();
+
+/// Install a SIGINT handler that cancels the returned token.
+///
+/// The first Ctrl-C stops any walk holding the token, which then returns what
+/// it has read; the command exits with code 130. A second Ctrl-C terminates
+/// the process immediately.
+pub fn install() -> CancelToken {
+    let token = TOKEN.get_or_init(CancelToken::default).clone();
+    // SAFETY: `on_sigint` only performs async-signal-safe work.
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+    token
+}
+
+extern "C" fn on_sigint(_signal: libc::c_int) {
+    if let Some(token) = TOKEN.get() {
+        token.cancel();
+    }
+    // SAFETY: restoring the default disposition is async-signal-safe.
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
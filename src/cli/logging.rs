@@ -0,0 +1,134 @@
+/// Diagnostics on stderr via `tracing`: log records filtered by `--log-level`
+/// or `RUST_LOG`, and span timings for `--debug` / `--debug-json`.
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use serde::Serialize;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use super::args::{Cli, LogFormat};
+
+/// Install the global subscriber for this run.
+///
+/// Log records are off unless `--log-level` or `RUST_LOG` enables them, so
+/// stderr stays reserved for errors. `--debug` additionally prints how long
+/// each debug-level span took as it closes.
+pub fn init(cli: &Cli) {
+    let filter = cli
+        .log_level
+        .as_deref()
+        .map(EnvFilter::new)
+        .or_else(|| EnvFilter::try_from_default_env().ok());
+    let logs = filter.map(|filter| {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_ansi(std::io::stderr().is_terminal());
+        match cli.log_format {
+            LogFormat::Compact => layer.compact().with_filter(filter).boxed(),
+            LogFormat::Json => layer.json().with_filter(filter).boxed(),
+        }
+    });
+    let timings = (cli.debug || cli.debug_json)
+        .then(|| SpanTimings::new(cli.debug_json).with_filter(LevelFilter::DEBUG));
+
+    let _ = tracing_subscriber::registry()
+        .with(logs)
+        .with(timings)
+        .try_init();
+}
+
+/// Parse `--log-level`: a level (`debug`) or `RUST_LOG`-style directives
+/// (`menucli_core=trace,warn`).
+pub(crate) fn parse_log_level(s: &str) -> Result<String, String> {
+    EnvFilter::try_new(s)
+        .map(|_| s.to_owned())
+        .map_err(|e| e.to_string())
+}
+
+/// Prints each span's duration to stderr when it closes: a `[debug]` line,
+/// or with `json` one object per span —
+/// `{"name":"build_tree","id":3,"parent":1,"start_ms":0.4,"duration_ms":212.7}`,
+/// where `parent` is the span that was current when this one started.
+struct SpanTimings {
+    json: bool,
+    /// Span start times are reported relative to this.
+    epoch: Instant,
+    /// Span IDs that, unlike `tracing`'s, are never reused within a run.
+    next_id: AtomicU64,
+}
+
+/// Stored in each span's extensions while it is open.
+struct Started {
+    id: u64,
+    at: Instant,
+}
+
+/// One `--debug-json` line.
+#[derive(Serialize)]
+struct SpanRecord<'a> {
+    name: &'a str,
+    id: u64,
+    parent: Option<u64>,
+    /// Milliseconds since timing started.
+    start_ms: f64,
+    duration_ms: f64,
+}
+
+impl SpanTimings {
+    fn new(json: bool) -> Self {
+        Self {
+            json,
+            epoch: Instant::now(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+}
+
+impl<S> Layer<S> for SpanTimings
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Started {
+                id: self.next_id.fetch_add(1, Ordering::Relaxed),
+                at: Instant::now(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(started) = extensions.get::<Started>() else {
+            return;
+        };
+        let ms = started.at.elapsed().as_secs_f64() * 1000.0;
+        if !self.json {
+            eprintln!("[debug] {}: {ms:.2}ms", span.name());
+            return;
+        }
+        let parent = span
+            .parent()
+            .and_then(|p| p.extensions().get::<Started>().map(|s| s.id));
+        let record = SpanRecord {
+            name: span.name(),
+            id: started.id,
+            parent,
+            start_ms: (started.at - self.epoch).as_secs_f64() * 1000.0,
+            duration_ms: ms,
+        };
+        if let Ok(line) = serde_json::to_string(&record) {
+            eprintln!("{line}");
+        }
+    }
+}
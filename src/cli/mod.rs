@@ -1,6 +1,14 @@
 /// CLI layer: argument parsing and output formatting.
 pub mod args;
+pub mod complete;
+pub mod config;
+pub mod deadline;
+pub mod hooks;
+pub mod html;
+pub mod interrupt;
+pub mod logging;
 pub mod output;
+pub mod progress;
 
 pub use args::{Cli, OutputFormat};
-pub use output::{write_error, OutputCtx};
+pub use output::{version_value, write_error, OutputCtx};
@@ -1,12 +1,22 @@
 /// Output formatting: JSON, table, path/id modes. TTY detection.
 use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use comfy_table::{presets::UTF8_BORDERS_ONLY, Cell, Table};
+use comfy_table::{presets::UTF8_BORDERS_ONLY, Table};
 use serde::Serialize;
+use serde_json::Value;
 
 use super::args::OutputFormat;
+use super::hooks::Hooks;
+use super::html;
+use crate::ax::ActivationPolicy;
+use crate::menu::tree::{ExtrasResult, WalkProgress};
+use crate::menu::{CancelToken, CheckState};
 use crate::types::{
-    AppInfoOutput, MenuItemOutput, MenuTreeOutput, SearchResultOutput, ToggleOutput,
+    format_rfc3339, ActionOutput, AppInfoOutput, AttributeOutput, BenchOutput, BenchTiming,
+    ExplainOutput, FocusedOutput, MenuCountOutput, MenuItemOutput, MenuTreeOutput,
+    ResolutionOutput, SearchResultOutput, ToggleOutput, SCHEMA_VERSION,
 };
 
 /// Resolve the effective output format, handling `--json` flag and TTY auto-detection.
@@ -27,14 +37,52 @@ pub fn resolve_format(fmt: OutputFormat, json_flag: bool) -> OutputFormat {
 }
 
 /// Output context passed to all formatters.
+// Each bool is its own CLI flag.
+#[allow(clippy::struct_excessive_bools)]
 pub struct OutputCtx {
     pub format: OutputFormat,
     pub fields: Option<Vec<String>>,
     pub no_header: bool,
-    /// When true, print AX timing spans to stderr.
+    /// When true, span timings are printed to stderr (see `cli::logging`).
     pub debug: bool,
     /// When true, include alternate (Option-key) menu items in output.
     pub alternates: bool,
+    /// JSON schema version to render (`--output-version`).
+    pub output_version: u32,
+    /// Per-element AX messaging timeout in seconds (`--ax-timeout`).
+    pub ax_timeout: Option<f32>,
+    /// Cancelled by Ctrl-C; tree walks stop early once it is.
+    pub cancel: CancelToken,
+    /// `--quiet`: `click`, `toggle` and `state` print nothing on stdout.
+    pub quiet: bool,
+    /// Top-level menus no command walks (`--exclude-menus`).
+    pub exclude_menus: Vec<String>,
+    /// Commands the config file runs around actions.
+    pub hooks: Hooks,
+    /// The app the command resolved, for the error envelope.
+    pub target: Target,
+}
+
+/// The app a command works on, once it has resolved one: its name (if still
+/// running) and PID. Shared with the `--timeout` thread, which may report a
+/// failure while the command is still running.
+#[derive(Debug, Clone, Default)]
+pub struct Target(Arc<Mutex<Option<(Option<String>, i32)>>>);
+
+impl Target {
+    /// Record the resolved app.
+    pub fn set(&self, name: Option<String>, pid: i32) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some((name, pid));
+    }
+
+    /// The recorded app, if any.
+    #[must_use]
+    pub fn get(&self) -> Option<(Option<String>, i32)> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
 }
 
 impl OutputCtx {
@@ -47,6 +95,7 @@ impl OutputCtx {
         no_header: bool,
         debug: bool,
         alternates: bool,
+        output_version: u32,
     ) -> Self {
         let format = resolve_format(fmt, json_flag);
         let fields = fields.map(|f| f.split(',').map(str::trim).map(str::to_owned).collect());
@@ -56,13 +105,31 @@ impl OutputCtx {
             no_header,
             debug,
             alternates,
+            output_version,
+            ax_timeout: None,
+            cancel: CancelToken::default(),
+            quiet: false,
+            exclude_menus: Vec::new(),
+            hooks: Hooks::default(),
+            target: Target::default(),
         }
     }
 
-    /// Start a named debug timer. Prints elapsed on drop only when `--debug` is set.
+    /// Progress counters for a tree walk, when a spinner can be shown: stderr
+    /// is a terminal and `--debug` is not writing timings to it.
     #[must_use]
-    pub fn timer(&self, label: &'static str) -> DebugTimer {
-        DebugTimer::new(label, self.debug)
+    pub fn walk_progress(&self) -> Option<Arc<WalkProgress>> {
+        (!self.debug && std::io::stderr().is_terminal()).then(Arc::default)
+    }
+
+    /// Wrap each item for rendering in the pinned schema version, marked
+    /// truncated if the walk behind it was cut short.
+    fn versioned<'a, T>(&self, items: &'a [T]) -> Vec<Versioned<'a, T>> {
+        let truncated = self.cancel.is_cancelled();
+        items
+            .iter()
+            .map(|i| Versioned::new(i, self.output_version).truncated(truncated))
+            .collect()
     }
 
     /// Whether a field should be included in output.
@@ -71,6 +138,19 @@ impl OutputCtx {
             .as_ref()
             .map_or(true, |f| f.iter().any(|n| n == name))
     }
+
+    /// Whether `--fields` names a field that tables leave out by default.
+    fn requested_field(&self, name: &str) -> bool {
+        self.fields
+            .as_ref()
+            .is_some_and(|f| f.iter().any(|n| n == name))
+    }
+}
+
+/// Whether the attributed `pids` name more than one app.
+fn several_apps(pids: impl Iterator<Item = Option<i32>>) -> bool {
+    let mut pids = pids.flatten();
+    pids.next().is_some_and(|first| pids.any(|p| p != first))
 }
 
 // --- Flat menu item output ---
@@ -78,13 +158,11 @@ impl OutputCtx {
 /// Write a list of `MenuItemOutput` to stdout.
 pub fn write_menu_items(items: &[MenuItemOutput], ctx: &OutputCtx) {
     match ctx.format {
-        OutputFormat::Json => print_json(items),
-        OutputFormat::Compact => print_compact_json(items),
-        OutputFormat::Ndjson => print_ndjson(items),
-        OutputFormat::Path => {
-            for item in items {
-                println!("{}", item.path);
-            }
+        OutputFormat::Json => print_json(&ctx.versioned(items)),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(items)),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(items)),
+        OutputFormat::Path | OutputFormat::Path0 => {
+            print_paths(items.iter().map(|i| i.path.as_str()), ctx.format);
         }
         OutputFormat::Id => {
             for item in items {
@@ -92,44 +170,80 @@ pub fn write_menu_items(items: &[MenuItemOutput], ctx: &OutputCtx) {
             }
         }
         OutputFormat::Table | OutputFormat::Auto => write_menu_items_table(items, ctx),
+        OutputFormat::Html => write_menu_items_html(items, ctx),
+    }
+}
+
+/// The mark shown for an item's checkmark in tables and trees.
+fn check_mark(state: CheckState) -> &'static str {
+    match state {
+        CheckState::On => "✓",
+        CheckState::Mixed => "–",
+        CheckState::Off => "",
     }
 }
 
 fn write_menu_items_table(items: &[MenuItemOutput], ctx: &OutputCtx) {
+    let (headers, rows) = menu_item_columns(items, ctx);
     let mut table = Table::new();
     table.load_preset(UTF8_BORDERS_ONLY);
+    if !ctx.no_header {
+        table.set_header(headers);
+    }
+    for row in rows {
+        table.add_row(row);
+    }
+    println!("{table}");
+}
+
+fn write_menu_items_html(items: &[MenuItemOutput], ctx: &OutputCtx) {
+    let (headers, rows) = menu_item_columns(items, ctx);
+    // Name the page after the app, when there is just one.
+    let title = match items.first().and_then(|i| i.app_name.as_deref()) {
+        Some(name) if !several_apps(items.iter().map(|i| i.app_pid)) => format!("{name} menus"),
+        _ => "menucli".to_owned(),
+    };
+    print_html(&title, &html::table(&headers, &escaped(&rows)));
+}
 
-    // Show APP column only when items have app attribution (extras across all apps).
-    let show_app = items.iter().any(|i| i.app_name.is_some());
+/// The headers and rows of the human-readable item listing.
+fn menu_item_columns(
+    items: &[MenuItemOutput],
+    ctx: &OutputCtx,
+) -> (Vec<&'static str>, Vec<Vec<String>>) {
+    // Show APP column only when items come from several apps, or if asked for.
+    let show_app = several_apps(items.iter().map(|i| i.app_pid)) || ctx.requested_field("app");
 
-    let mut headers: Vec<Cell> = Vec::new();
+    let mut headers = Vec::new();
     if show_app && ctx.include_field("app") {
-        headers.push(Cell::new("APP"));
+        headers.push("APP");
     }
     if ctx.include_field("path") {
-        headers.push(Cell::new("PATH"));
+        headers.push("PATH");
     }
     if ctx.include_field("enabled") {
-        headers.push(Cell::new("ENABLED"));
+        headers.push("ENABLED");
     }
     if ctx.include_field("checked") {
-        headers.push(Cell::new("CHECKED"));
+        headers.push("CHECKED");
     }
     if ctx.include_field("shortcut") {
-        headers.push(Cell::new("SHORTCUT"));
+        headers.push("SHORTCUT");
     }
     if ctx.include_field("role") {
-        headers.push(Cell::new("ROLE"));
+        headers.push("ROLE");
     }
-
-    if !ctx.no_header {
-        table.set_header(headers);
+    // Tooltips are long, so only shown when asked for.
+    let show_help = ctx.requested_field("help");
+    if show_help {
+        headers.push("HELP");
     }
 
+    let mut rows = Vec::new();
     for item in items {
-        let mut row: Vec<Cell> = Vec::new();
+        let mut row: Vec<String> = Vec::new();
         if show_app && ctx.include_field("app") {
-            row.push(Cell::new(item.app_name.as_deref().unwrap_or("")));
+            row.push(item.app_name.clone().unwrap_or_default());
         }
         if ctx.include_field("path") {
             let path_str = if item.is_alternate {
@@ -137,24 +251,26 @@ fn write_menu_items_table(items: &[MenuItemOutput], ctx: &OutputCtx) {
             } else {
                 item.path.clone()
             };
-            row.push(Cell::new(path_str));
+            row.push(path_str);
         }
         if ctx.include_field("enabled") {
-            row.push(Cell::new(if item.enabled { "yes" } else { "no" }));
+            row.push(if item.enabled { "yes" } else { "no" }.to_owned());
         }
         if ctx.include_field("checked") {
-            row.push(Cell::new(if item.checked { "✓" } else { "" }));
+            row.push(check_mark(item.check_state).to_owned());
         }
         if ctx.include_field("shortcut") {
-            row.push(Cell::new(item.shortcut.as_deref().unwrap_or("")));
+            row.push(item.shortcut.clone().unwrap_or_default());
         }
         if ctx.include_field("role") {
-            row.push(Cell::new(&item.role));
+            row.push(item.role.clone());
         }
-        table.add_row(row);
+        if show_help {
+            row.push(item.help.clone().unwrap_or_default());
+        }
+        rows.push(row);
     }
-
-    println!("{table}");
+    (headers, rows)
 }
 
 // --- Tree output ---
@@ -162,13 +278,15 @@ fn write_menu_items_table(items: &[MenuItemOutput], ctx: &OutputCtx) {
 /// Write a tree of `MenuTreeOutput` to stdout.
 pub fn write_menu_tree(nodes: &[MenuTreeOutput], ctx: &OutputCtx) {
     match ctx.format {
-        OutputFormat::Json => print_json(nodes),
-        OutputFormat::Compact => print_compact_json(nodes),
-        OutputFormat::Ndjson => print_ndjson(nodes),
-        OutputFormat::Path => {
+        OutputFormat::Json => print_json(&ctx.versioned(nodes)),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(nodes)),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(nodes)),
+        OutputFormat::Path | OutputFormat::Path0 => {
+            let mut paths = Vec::new();
             for node in nodes {
-                print_tree_paths(node);
+                tree_paths(node, &mut paths);
             }
+            print_paths(paths, ctx.format);
         }
         OutputFormat::Id => {
             for node in nodes {
@@ -181,15 +299,35 @@ pub fn write_menu_tree(nodes: &[MenuTreeOutput], ctx: &OutputCtx) {
                 print_tree_visual(node, "", i + 1 == count, ctx);
             }
         }
+        OutputFormat::Html => print_html("menucli", &html::tree(nodes)),
     }
 }
 
-fn print_tree_paths(node: &MenuTreeOutput) {
+/// Write the trees of several apps, each under a heading naming the app.
+pub fn write_app_trees(trees: &[(&ExtrasResult, Vec<MenuTreeOutput>)], ctx: &OutputCtx) {
+    if ctx.format == OutputFormat::Html {
+        let mut body = String::new();
+        for (app, nodes) in trees {
+            let heading = format!("{} (pid {})", app.app_name, app.app_pid);
+            body.push_str(&html::heading(&heading));
+            body.push_str(&html::tree(nodes));
+        }
+        print_html("menucli", &body);
+        return;
+    }
+    for (app, nodes) in trees {
+        println!("--- {} (pid {}) ---", app.app_name, app.app_pid);
+        write_menu_tree(nodes, ctx);
+    }
+}
+
+/// Collect the paths of the leaves under `node`.
+fn tree_paths<'a>(node: &'a MenuTreeOutput, out: &mut Vec<&'a str>) {
     if node.children.is_empty() {
-        println!("{}", node.path);
+        out.push(&node.path);
     }
     for child in &node.children {
-        print_tree_paths(child);
+        tree_paths(child, out);
     }
 }
 
@@ -208,7 +346,10 @@ fn print_tree_visual(node: &MenuTreeOutput, prefix: &str, is_last: bool, ctx: &O
         .map(|s| format!("  [{s}]"))
         .unwrap_or_default();
     let enabled_str = if !node.enabled { " (disabled)" } else { "" };
-    let checked_str = if node.checked { " ✓" } else { "" };
+    let checked_str = match node.check_state {
+        CheckState::Off => String::new(),
+        state => format!(" {}", check_mark(state)),
+    };
     let alt_str = if node.is_alternate { " [alt]" } else { "" };
     println!(
         "{prefix}{connector}{}{shortcut_str}{enabled_str}{checked_str}{alt_str}",
@@ -227,13 +368,11 @@ fn print_tree_visual(node: &MenuTreeOutput, prefix: &str, is_last: bool, ctx: &O
 /// Write search results to stdout.
 pub fn write_search_results(results: &[SearchResultOutput], ctx: &OutputCtx) {
     match ctx.format {
-        OutputFormat::Json => print_json(results),
-        OutputFormat::Compact => print_compact_json(results),
-        OutputFormat::Ndjson => print_ndjson(results),
-        OutputFormat::Path => {
-            for r in results {
-                println!("{}", r.path);
-            }
+        OutputFormat::Json => print_json(&ctx.versioned(results)),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(results)),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(results)),
+        OutputFormat::Path | OutputFormat::Path0 => {
+            print_paths(results.iter().map(|r| r.path.as_str()), ctx.format);
         }
         OutputFormat::Id => {
             for r in results {
@@ -241,24 +380,183 @@ pub fn write_search_results(results: &[SearchResultOutput], ctx: &OutputCtx) {
             }
         }
         OutputFormat::Table | OutputFormat::Auto => write_search_table(results, ctx),
+        OutputFormat::Html => write_search_html(results),
     }
 }
 
 fn write_search_table(results: &[SearchResultOutput], ctx: &OutputCtx) {
     let mut table = Table::new();
     table.load_preset(UTF8_BORDERS_ONLY);
+    // Show APP column only when results come from several apps.
+    let show_app = several_apps(results.iter().map(|r| r.app_pid));
     if !ctx.no_header {
-        table.set_header(["PATH", "ENABLED", "SHORTCUT", "SCORE"]);
+        let mut headers = vec!["PATH", "ENABLED", "SHORTCUT", "SCORE"];
+        if show_app {
+            headers.insert(0, "APP");
+        }
+        table.set_header(headers);
     }
-    for r in results {
-        table.add_row([
-            r.path.as_str(),
+    // Collapsed alternates follow their primary, marked with the Option key.
+    let rows: Vec<(&SearchResultOutput, bool)> = results
+        .iter()
+        .flat_map(|r| std::iter::once((r, false)).chain(r.alternates.iter().map(|a| (a, true))))
+        .collect();
+    for &(r, nested) in &rows {
+        let score = r.score.to_string();
+        let path = if nested {
+            format!("  ⌥ {}", r.path)
+        } else {
+            r.path.clone()
+        };
+        let mut row = vec![
+            path.as_str(),
             if r.enabled { "yes" } else { "no" },
             r.shortcut.as_deref().unwrap_or(""),
-            &r.score.to_string(),
-        ]);
+            &score,
+        ];
+        if show_app {
+            row.insert(0, r.app_name.as_deref().unwrap_or(""));
+        }
+        table.add_row(row);
+    }
+
+    // comfy-table can't measure ANSI-styled cells, so style the matched spans
+    // after layout: each row holds its path once, in result order.
+    let mut rendered = table.to_string();
+    if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+        let mut from = 0;
+        for &(r, _) in &rows {
+            if let Some(at) = rendered[from..].find(r.path.as_str()) {
+                let start = from + at;
+                let styled = highlight(&r.path, &r.match_ranges);
+                rendered.replace_range(start..start + r.path.len(), &styled);
+                from = start + styled.len();
+            }
+        }
+    }
+    println!("{rendered}");
+}
+
+fn write_search_html(results: &[SearchResultOutput]) {
+    let show_app = several_apps(results.iter().map(|r| r.app_pid));
+    let mut headers = vec!["PATH", "ENABLED", "SHORTCUT", "SCORE"];
+    if show_app {
+        headers.insert(0, "APP");
+    }
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|r| {
+            // Collapsed alternates stay in their primary's cell, so sorting
+            // keeps them together.
+            let mut path = html::mark(&r.path, &r.match_ranges);
+            path.extend(r.alternates.iter().map(|a| {
+                format!(
+                    "<div class=\"alt\">⌥ {}</div>",
+                    html::mark(&a.path, &a.match_ranges)
+                )
+            }));
+            let mut row = vec![
+                path,
+                if r.enabled { "yes" } else { "no" }.to_owned(),
+                html::escape(r.shortcut.as_deref().unwrap_or("")),
+                r.score.to_string(),
+            ];
+            if show_app {
+                row.insert(0, html::escape(r.app_name.as_deref().unwrap_or("")));
+            }
+            row
+        })
+        .collect();
+    print_html("menucli search", &html::table(&headers, &rows));
+}
+
+/// Wrap the `[start, end)` char spans of `text` in bold+underline.
+fn highlight(text: &str, ranges: &[[usize; 2]]) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        if ranges.iter().any(|r| r[0] == i) {
+            out.push_str("\x1b[1;4m");
+        }
+        out.push(c);
+        if ranges.iter().any(|r| r[1] == i + 1) {
+            out.push_str("\x1b[0m");
+        }
+    }
+    out
+}
+
+// --- Counts ---
+
+/// Write `list --count` results: the total alone for path/id output, a
+/// per-menu table for humans.
+pub fn write_count(counts: &MenuCountOutput, ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json => print_json(&ctx.versioned(&[counts])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[counts])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[counts])),
+        OutputFormat::Path | OutputFormat::Path0 | OutputFormat::Id => {
+            println!("{}", counts.total);
+        }
+        OutputFormat::Table | OutputFormat::Auto => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_BORDERS_ONLY);
+            if !ctx.no_header {
+                table.set_header(["MENU", "ITEMS"]);
+            }
+            for m in &counts.menus {
+                table.add_row([m.menu.as_str(), &m.count.to_string()]);
+            }
+            table.add_row(["total", &counts.total.to_string()]);
+            println!("{table}");
+        }
+        OutputFormat::Html => {
+            let mut rows: Vec<Vec<String>> = counts
+                .menus
+                .iter()
+                .map(|m| vec![html::escape(&m.menu), m.count.to_string()])
+                .collect();
+            rows.push(vec!["total".to_owned(), counts.total.to_string()]);
+            print_html("menucli", &html::table(&["MENU", "ITEMS"], &rows));
+        }
+    }
+}
+
+// --- Bench ---
+
+/// Write `bench` results: one row per measurement for humans.
+pub fn write_bench(result: &BenchOutput, ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json => print_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[result])),
+        _ => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_BORDERS_ONLY);
+            if !ctx.no_header {
+                table.set_header(["MEASUREMENT", "ITEMS", "MEDIAN", "MIN", "MAX"]);
+            }
+            let mut row = |name: &str, items: usize, t: &BenchTiming| {
+                table.add_row([
+                    name,
+                    &items.to_string(),
+                    &format!("{:.1}ms", t.median_ms),
+                    &format!("{:.1}ms", t.min_ms),
+                    &format!("{:.1}ms", t.max_ms),
+                ]);
+            };
+            row("build tree", result.items, &result.build);
+            for m in &result.menus {
+                row(&format!("  {}", m.menu), m.items, &m.build);
+            }
+            row("fetch (batch)", result.fetched, &result.fetch_batch);
+            row("fetch (single)", result.fetched, &result.fetch_single);
+            println!("{table}");
+            println!(
+                "{:.0} items/sec, batch fetch {:.1}x faster ({} iterations, pid {})",
+                result.items_per_sec, result.batch_speedup, result.iterations, result.pid
+            );
+        }
     }
-    println!("{table}");
 }
 
 // --- Apps ---
@@ -266,43 +564,392 @@ fn write_search_table(results: &[SearchResultOutput], ctx: &OutputCtx) {
 /// Write app list to stdout.
 pub fn write_apps(apps: &[AppInfoOutput], ctx: &OutputCtx) {
     match ctx.format {
-        OutputFormat::Json => print_json(apps),
-        OutputFormat::Compact => print_compact_json(apps),
-        OutputFormat::Ndjson => print_ndjson(apps),
-        OutputFormat::Id | OutputFormat::Path => {
-            for app in apps {
-                println!("{}", app.name);
-            }
+        OutputFormat::Json => print_json(&ctx.versioned(&app_objects(apps, ctx))),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&app_objects(apps, ctx))),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&app_objects(apps, ctx))),
+        OutputFormat::Id | OutputFormat::Path | OutputFormat::Path0 => {
+            let lines: Vec<String> = apps
+                .iter()
+                .map(|app| match &ctx.fields {
+                    Some(fields) => app_field_values(app, fields).join("\t"),
+                    None => app.name.clone(),
+                })
+                .collect();
+            print_paths(lines.iter().map(String::as_str), ctx.format);
         }
         OutputFormat::Table | OutputFormat::Auto => write_apps_table(apps, ctx),
+        OutputFormat::Html => {
+            let (headers, rows) = app_columns(apps, ctx);
+            print_html("menucli apps", &html::table(&headers, &escaped(&rows)));
+        }
     }
 }
 
+/// `apps` as JSON objects holding only the `--fields` named, if any.
+fn app_objects(apps: &[AppInfoOutput], ctx: &OutputCtx) -> Vec<Value> {
+    apps.iter()
+        .map(|app| {
+            let mut value = serde_json::to_value(app).unwrap_or_default();
+            if let Value::Object(map) = &mut value {
+                map.retain(|name, _| ctx.include_field(name));
+            }
+            value
+        })
+        .collect()
+}
+
+/// `app`'s value of each named field as plain text: strings unquoted, empty
+/// for fields it does not have.
+fn app_field_values(app: &AppInfoOutput, fields: &[String]) -> Vec<String> {
+    let value = serde_json::to_value(app).unwrap_or_default();
+    fields
+        .iter()
+        .map(|field| match &value[field.as_str()] {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
 fn write_apps_table(apps: &[AppInfoOutput], ctx: &OutputCtx) {
+    let (headers, rows) = app_columns(apps, ctx);
     let mut table = Table::new();
     table.load_preset(UTF8_BORDERS_ONLY);
     if !ctx.no_header {
-        table.set_header(["NAME", "PID", "BUNDLE ID", "FRONTMOST"]);
+        table.set_header(headers);
     }
-    for app in apps {
-        table.add_row([
-            app.name.as_str(),
-            &app.pid.to_string(),
-            app.bundle_id.as_deref().unwrap_or(""),
-            if app.frontmost { "yes" } else { "" },
-        ]);
+    for row in rows {
+        table.add_row(row);
     }
     println!("{table}");
 }
 
+/// The headers and rows of the human-readable app listing.
+fn app_columns(apps: &[AppInfoOutput], ctx: &OutputCtx) -> (Vec<&'static str>, Vec<Vec<String>>) {
+    // Shown by default, unless --fields leaves them out.
+    let name = ctx.include_field("name");
+    let pid = ctx.include_field("pid");
+    let bundle_id = ctx.include_field("bundle_id");
+    let frontmost = ctx.include_field("frontmost");
+    // Only shown when asked for.
+    let policy = ctx.requested_field("activation_policy");
+    let launched = ctx.requested_field("launch_date");
+
+    let headers = [
+        (name, "NAME"),
+        (pid, "PID"),
+        (bundle_id, "BUNDLE ID"),
+        (frontmost, "FRONTMOST"),
+        (policy, "POLICY"),
+        (launched, "LAUNCHED"),
+    ];
+    let headers = headers
+        .iter()
+        .filter(|(shown, _)| *shown)
+        .map(|(_, h)| *h)
+        .collect();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut rows = Vec::new();
+    for app in apps {
+        let mut row: Vec<String> = Vec::new();
+        if name {
+            row.push(app.name.clone());
+        }
+        if pid {
+            row.push(app.pid.to_string());
+        }
+        if bundle_id {
+            row.push(app.bundle_id.clone().unwrap_or_default());
+        }
+        if frontmost {
+            row.push(if app.frontmost { "yes" } else { "" }.to_owned());
+        }
+        if policy {
+            row.push(
+                match app.activation_policy {
+                    ActivationPolicy::Regular => "regular",
+                    ActivationPolicy::Accessory => "accessory",
+                    ActivationPolicy::Prohibited => "prohibited",
+                }
+                .to_owned(),
+            );
+        }
+        if launched {
+            let age = app.launch_date.map(|t| age(now.saturating_sub(t)));
+            row.push(age.unwrap_or_default());
+        }
+        rows.push(row);
+    }
+    (headers, rows)
+}
+
+/// `secs` as a rough age in its largest unit (`5m ago`).
+fn age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86_400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// The current time as an RFC 3339 UTC timestamp with milliseconds
+/// (`2024-05-01T09:30:00.250Z`), for `generated_at` / `performed_at`.
+#[must_use]
+pub fn timestamp_now() -> String {
+    format_rfc3339(SystemTime::now())
+}
+
+// --- Resolutions ---
+
+/// Write the items `resolve` found, in the requested format.
+pub fn write_resolutions(resolutions: &[ResolutionOutput], ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json => print_json(&ctx.versioned(resolutions)),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(resolutions)),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(resolutions)),
+        OutputFormat::Path | OutputFormat::Path0 => {
+            print_paths(resolutions.iter().map(|r| r.path.as_str()), ctx.format);
+        }
+        OutputFormat::Id => {
+            for r in resolutions {
+                println!("{}", r.title);
+            }
+        }
+        OutputFormat::Table | OutputFormat::Auto => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_BORDERS_ONLY);
+            if !ctx.no_header {
+                table.set_header(["PATH", "STRATEGY", "SCORE", "ENABLED"]);
+            }
+            for r in resolutions {
+                table.add_row([
+                    r.path.as_str(),
+                    r.strategy.name(),
+                    &r.score.map(|s| s.to_string()).unwrap_or_default(),
+                    if r.enabled { "yes" } else { "no" },
+                ]);
+            }
+            println!("{table}");
+        }
+        OutputFormat::Html => {
+            let rows: Vec<Vec<String>> = resolutions
+                .iter()
+                .map(|r| {
+                    vec![
+                        html::escape(&r.path),
+                        r.strategy.name().to_owned(),
+                        r.score.map(|s| s.to_string()).unwrap_or_default(),
+                        if r.enabled { "yes" } else { "no" }.to_owned(),
+                    ]
+                })
+                .collect();
+            let headers = ["PATH", "STRATEGY", "SCORE", "ENABLED"];
+            print_html("menucli resolve", &html::table(&headers, &rows));
+        }
+    }
+}
+
+// --- Explain ---
+
+/// Write how a query resolves: a summary, then the candidates.
+pub fn write_explanation(e: &ExplainOutput, ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json => print_json(&ctx.versioned(&[e])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[e])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[e])),
+        OutputFormat::Path | OutputFormat::Path0 => {
+            print_paths(e.candidates.iter().map(|c| c.path.as_str()), ctx.format);
+        }
+        OutputFormat::Id => {
+            for c in &e.candidates {
+                println!("{}", c.title);
+            }
+        }
+        OutputFormat::Table | OutputFormat::Auto => {
+            for (label, value) in explanation_summary(e) {
+                println!("{label:<12}{value}");
+            }
+            let mut table = Table::new();
+            table.load_preset(UTF8_BORDERS_ONLY);
+            if !ctx.no_header {
+                table.set_header(["#", "PATH", "SCORE", "ENABLED"]);
+            }
+            for (i, c) in e.candidates.iter().enumerate() {
+                table.add_row(vec![
+                    (i + 1).to_string(),
+                    c.path.clone(),
+                    c.score.map(|s| s.to_string()).unwrap_or_default(),
+                    if c.enabled { "yes" } else { "no" }.to_owned(),
+                ]);
+            }
+            println!("{table}");
+        }
+        OutputFormat::Html => {
+            let mut body = String::from("<dl>\n");
+            for (label, value) in explanation_summary(e) {
+                body.push_str(&html::term(label, &value));
+            }
+            body.push_str("</dl>\n");
+            let rows: Vec<Vec<String>> = e
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    vec![
+                        (i + 1).to_string(),
+                        html::escape(&c.path),
+                        c.score.map(|s| s.to_string()).unwrap_or_default(),
+                        if c.enabled { "yes" } else { "no" }.to_owned(),
+                    ]
+                })
+                .collect();
+            body.push_str(&html::table(&["#", "PATH", "SCORE", "ENABLED"], &rows));
+            print_html("menucli explain", &body);
+        }
+    }
+}
+
+/// The labelled lines summarizing an explanation for humans.
+fn explanation_summary(e: &ExplainOutput) -> Vec<(&'static str, String)> {
+    let mut lines = vec![
+        ("Query:", e.query.clone()),
+        ("Strategy:", e.strategy.name().to_owned()),
+    ];
+    if let Some(ratio) = e.confidence_ratio {
+        lines.push((
+            "Confidence:",
+            format!("{ratio:.1}x (auto-resolve needs {:.1}x)", e.required_ratio),
+        ));
+    }
+    let outcome = if e.resolved { "yes" } else { "no" };
+    lines.push(("Resolved:", format!("{outcome}: {}", e.reason)));
+    if e.candidates.len() < e.candidates_count {
+        lines.push((
+            "Candidates:",
+            format!("{} of {}", e.candidates.len(), e.candidates_count),
+        ));
+    }
+    lines
+}
+
+// --- Action ---
+
+/// Write the action performed on an item, or the actions it supports.
+pub fn write_action(result: &ActionOutput, ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Auto => print_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[result])),
+        _ => match (&result.action, &result.screenshot) {
+            (Some(action), Some(file)) => println!("{}: {action}, saved {file}", result.path),
+            (Some(action), None) => println!("{}: {action}", result.path),
+            (None, _) => println!("{}: {}", result.path, result.actions.join(", ")),
+        },
+    }
+}
+
+// --- Preview ---
+
+/// Write the `preview` panel for `item`: its details, then its siblings with
+/// `item` marked. Always text, whatever the output format, since fzf shows
+/// the preview command's output as is.
+pub fn write_preview(item: &MenuItemOutput, siblings: &[MenuItemOutput]) {
+    match &item.shortcut {
+        Some(shortcut) => println!("{}  {shortcut}", item.title),
+        None => println!("{}", item.title),
+    }
+    println!("{}", item.path);
+    println!();
+    println!("enabled   {}", if item.enabled { "yes" } else { "no" });
+    match item.check_state {
+        CheckState::On => println!("state     ✓ on"),
+        CheckState::Mixed => println!("state     – mixed"),
+        CheckState::Off if item.is_radio => println!("state     off"),
+        CheckState::Off => {}
+    }
+    if let Some(help) = &item.help {
+        println!("help      {help}");
+    }
+    if item.children_count > 0 {
+        println!("submenu   {} items", item.children_count);
+    }
+    if let Some(primary) = &item.alternate_of {
+        println!("alternate of {primary}");
+    }
+    println!("id        {}", item.id);
+
+    if siblings.len() < 2 {
+        return;
+    }
+    println!();
+    println!("{}", item.parent_path.as_deref().unwrap_or("Menu bar"));
+    let width = siblings
+        .iter()
+        .map(|s| s.title.chars().count())
+        .max()
+        .unwrap_or(0);
+    for sibling in siblings {
+        let marker = if sibling.path == item.path {
+            "▸"
+        } else {
+            " "
+        };
+        let shortcut = sibling.shortcut.as_deref().unwrap_or("");
+        let disabled = if sibling.enabled { "" } else { "  (disabled)" };
+        let line = format!("{marker} {:<width$}  {shortcut}{disabled}", sibling.title);
+        println!("{}", line.trim_end());
+    }
+}
+
+// --- Focus ---
+
+/// Write what has keyboard focus.
+pub fn write_focused(result: &FocusedOutput, ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json => print_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[result])),
+        _ => {
+            println!("{} ({})", result.app_name, result.app_pid);
+            if let Some(element) = &result.element {
+                let role = element.role.as_deref().unwrap_or("?");
+                match &element.title {
+                    Some(title) => println!("focused: {role} \"{title}\""),
+                    None => println!("focused: {role}"),
+                }
+            }
+            if let Some(item) = &result.highlighted {
+                println!("highlighted: {}", item.path);
+            }
+        }
+    }
+}
+
+// --- Attribute ---
+
+/// Write an attribute read: the whole record as JSON, otherwise just the
+/// value on one line, so it pipes into `jq` as is.
+pub fn write_attribute(result: &AttributeOutput, ctx: &OutputCtx) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Auto => print_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[result])),
+        _ => println!("{}", result.value),
+    }
+}
+
 // --- Toggle ---
 
 /// Write toggle result to stdout.
 pub fn write_toggle(result: &ToggleOutput, ctx: &OutputCtx) {
     match ctx.format {
-        OutputFormat::Json | OutputFormat::Auto => print_json(result),
-        OutputFormat::Compact => print_compact_json(result),
-        OutputFormat::Ndjson => print_ndjson(&[result]),
+        OutputFormat::Json | OutputFormat::Auto => print_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Compact => print_compact_json(&ctx.versioned(&[result])[0]),
+        OutputFormat::Ndjson => print_ndjson(&ctx.versioned(&[result])),
         _ => {
             let state = if result.checked_after {
                 "on (✓)"
@@ -318,13 +965,19 @@ pub fn write_toggle(result: &ToggleOutput, ctx: &OutputCtx) {
 // --- Error output ---
 
 /// Write a structured error to stderr.
-pub fn write_error(err: &crate::types::ErrorOutput, format: OutputFormat, json_flag: bool) {
+pub fn write_error(
+    err: &crate::types::ErrorOutput,
+    format: OutputFormat,
+    json_flag: bool,
+    output_version: u32,
+) {
     let fmt = resolve_format(format, json_flag);
     let stderr = std::io::stderr();
     let mut out = stderr.lock();
     match fmt {
         OutputFormat::Json | OutputFormat::Compact | OutputFormat::Ndjson => {
-            let s = serde_json::to_string_pretty(err).unwrap_or_default();
+            let s = serde_json::to_string_pretty(&Versioned::new(err, output_version))
+                .unwrap_or_default();
             let _ = writeln!(out, "{s}");
         }
         _ => {
@@ -335,41 +988,108 @@ pub fn write_error(err: &crate::types::ErrorOutput, format: OutputFormat, json_f
                     let _ = writeln!(out, "    {c}");
                 }
             }
+            if let Some(hint) = &err.error.hint {
+                let _ = writeln!(out, "  Hint: {hint}");
+            }
         }
     }
 }
 
-// --- Debug timer ---
+// --- Schema versioning ---
 
-/// A RAII timer that prints elapsed milliseconds to stderr on drop.
+/// An output object rendered in a pinned schema version.
 ///
-/// Created via [`OutputCtx::timer`]. Does nothing when `debug` is false.
-pub struct DebugTimer {
-    label: &'static str,
-    start: std::time::Instant,
-    active: bool,
+/// Older versions are compatibility shims over the current output types: when
+/// a field is renamed or removed, add a variant that restores the old shape.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Versioned<'a, T> {
+    /// v1: the original output, without `schema_version`.
+    V1(&'a T),
+    /// Current schema.
+    Current(Stamped<'a, T>),
 }
 
-impl DebugTimer {
-    #[must_use]
-    fn new(label: &'static str, active: bool) -> Self {
-        Self {
-            label,
-            start: std::time::Instant::now(),
-            active,
+/// The object's own fields preceded by `schema_version`, and by
+/// `truncated: true` when `--partial` output comes from an interrupted walk.
+#[derive(Serialize)]
+struct Stamped<'a, T> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    #[serde(flatten)]
+    item: &'a T,
+}
+
+impl<'a, T> Versioned<'a, T> {
+    fn new(item: &'a T, version: u32) -> Self {
+        if version == 1 {
+            Self::V1(item)
+        } else {
+            Self::Current(Stamped {
+                schema_version: SCHEMA_VERSION,
+                truncated: false,
+                item,
+            })
+        }
+    }
+
+    fn truncated(mut self, truncated: bool) -> Self {
+        if let Self::Current(stamped) = &mut self {
+            stamped.truncated = truncated;
         }
+        self
     }
 }
 
-impl Drop for DebugTimer {
-    fn drop(&mut self) {
-        if self.active {
-            let ms = self.start.elapsed().as_secs_f64() * 1000.0;
-            eprintln!("[debug] {}: {ms:.2}ms", self.label);
+/// Apply the same shims to an already-serialized result: a single object or
+/// an array of objects (used by `rpc`, which builds `Value`s directly).
+pub fn version_value(value: &mut Value, version: u32) {
+    if version == 1 {
+        return;
+    }
+    let objects: Vec<&mut Value> = match value {
+        Value::Array(items) => items.iter_mut().collect(),
+        other => vec![other],
+    };
+    for obj in objects {
+        if let Value::Object(map) = obj {
+            map.insert("schema_version".to_owned(), SCHEMA_VERSION.into());
         }
     }
 }
 
+// --- Path output ---
+
+/// Print one record per path: newline-terminated for `--output path`, and
+/// NUL-terminated for `path0` (`-0`), which survives titles with newlines.
+fn print_paths<'a>(paths: impl IntoIterator<Item = &'a str>, format: OutputFormat) {
+    let end = if format == OutputFormat::Path0 {
+        '\0'
+    } else {
+        '\n'
+    };
+    let mut out = std::io::stdout().lock();
+    for path in paths {
+        let _ = write!(out, "{path}{end}");
+    }
+    let _ = out.flush();
+}
+
+// --- HTML output ---
+
+/// Print a standalone page around `body`, stamped with when it was made.
+fn print_html(title: &str, body: &str) {
+    println!("{}", html::page(title, body, &timestamp_now()));
+}
+
+/// Plain-text table cells, escaped for `html::table`.
+fn escaped(rows: &[Vec<String>]) -> Vec<Vec<String>> {
+    rows.iter()
+        .map(|row| row.iter().map(|cell| html::escape(cell)).collect())
+        .collect()
+}
+
 // --- Generic JSON helpers ---
 
 fn print_json<T: Serialize + ?Sized>(value: &T) {
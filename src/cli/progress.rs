@@ -0,0 +1,73 @@
+/// Live progress on stderr while a slow tree walk runs.
+use std::io::Write;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::menu::tree::WalkProgress;
+
+/// Walks finishing sooner than this never show a spinner.
+const SHOW_AFTER: Duration = Duration::from_millis(300);
+
+/// How often the spinner line is redrawn.
+const REDRAW_EVERY: Duration = Duration::from_millis(100);
+
+const FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// A spinner line (`⠹ Edit: 412 items…`) drawn on stderr from a background
+/// thread while a walk is in progress.
+///
+/// Stops and clears its line on drop, so drop it before writing output.
+pub struct Spinner {
+    /// Dropping the sender tells the drawing thread to stop.
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Start drawing `progress`, if any; `None` gives an inert spinner.
+    #[must_use]
+    pub fn start(progress: Option<&Arc<WalkProgress>>) -> Self {
+        let Some(progress) = progress.map(Arc::clone) else {
+            return Self {
+                stop: None,
+                handle: None,
+            };
+        };
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = std::thread::spawn(move || {
+            if stopped.recv_timeout(SHOW_AFTER) != Err(RecvTimeoutError::Timeout) {
+                return;
+            }
+            let mut stderr = std::io::stderr();
+            for frame in FRAMES.iter().cycle() {
+                let _ = write!(
+                    stderr,
+                    "\r\x1b[2K{frame} {}: {} items…",
+                    progress.menu(),
+                    progress.items()
+                );
+                let _ = stderr.flush();
+                if stopped.recv_timeout(REDRAW_EVERY) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+            }
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+        });
+        Self {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
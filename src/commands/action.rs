@@ -0,0 +1,132 @@
+/// `action` command: perform a named AX action on a menu item.
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use tracing::debug_span;
+
+use super::resolve_item;
+use crate::backend::AxBackend;
+use crate::cli::args::ActionArgs;
+use crate::cli::output::{timestamp_now, write_action};
+use crate::cli::OutputCtx;
+use crate::menu::{MenuError, MenuNode};
+use crate::types::ActionOutput;
+
+/// The action `--screenshot` captures the result of.
+const SHOW_MENU: &str = "AXShowMenu";
+
+/// How long `--screenshot` waits for the menu to appear on screen.
+const MENU_SHOW_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often `--screenshot` looks for the opening menu.
+const MENU_SHOW_POLL: Duration = Duration::from_millis(50);
+
+/// Run `menucli action`.
+///
+/// Resolves the item as `click` does, then performs `--name` on it once its
+/// element lists that action — an escape hatch for items that need
+/// `AXShowMenu` or similar rather than a press. Without `--name`, only
+/// reports the actions the item supports. `--screenshot` performs
+/// `AXShowMenu` (the only action it accepts), captures the menu that opened,
+/// and closes it again.
+///
+/// # Errors
+///
+/// Returns `MenuError::InvalidInput` if `--screenshot` comes with another
+/// action, before anything is done; `MenuError` on AX failure, missing
+/// permissions, unknown app, or unresolvable path;
+/// `MenuError::ActionUnsupported` if the item does not support the action;
+/// and `MenuError::ScreenshotFailed` if the menu could not be captured.
+pub fn run(args: &ActionArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let action = match (args.name.as_deref(), &args.screenshot) {
+        (Some(name), Some(_)) if name != SHOW_MENU => {
+            return Err(MenuError::InvalidInput {
+                reason: format!("--screenshot captures the menu {SHOW_MENU} opens, not {name}"),
+            });
+        }
+        (None, Some(_)) => Some(SHOW_MENU),
+        (name, _) => name,
+    };
+    let (lookup, node) = resolve_item(backend, &args.item, ctx)?;
+    let pid = lookup.app.app_pid;
+
+    let mut output = ActionOutput {
+        id: node.id.clone(),
+        title: node.title.clone(),
+        path: node.path.clone(),
+        action: None,
+        actions: backend.actions(pid, &node)?,
+        app_name: Some(lookup.app.app_name.clone()),
+        app_pid: Some(pid),
+        performed_at: None,
+        screenshot: None,
+    };
+    if let Some(action) = action {
+        ctx.cancel.check()?;
+        let _t_action = debug_span!("perform_action").entered();
+        backend.perform_action(pid, &node, action)?;
+        output.action = Some(action.to_owned());
+        output.performed_at = Some(timestamp_now());
+        drop(_t_action);
+
+        if let Some(path) = &args.screenshot {
+            let _t_capture = debug_span!("capture_menu").entered();
+            let captured = capture_menu(backend, pid, &node, path, ctx);
+            // Close the menu whether or not the capture worked.
+            if let Err(err) = backend.perform_action(pid, &node, "AXCancel") {
+                eprintln!(
+                    "Warning: could not close the menu of '{}': {err}",
+                    node.path
+                );
+            }
+            captured?;
+            output.screenshot = Some(path.display().to_string());
+        }
+    }
+
+    if !ctx.quiet {
+        write_action(&output, ctx);
+    }
+    Ok(())
+}
+
+/// Capture the menu `node` has just opened to `path`, with `screencapture`,
+/// once the menu is on screen.
+fn capture_menu(
+    backend: &dyn AxBackend,
+    pid: i32,
+    node: &MenuNode,
+    path: &Path,
+    ctx: &OutputCtx,
+) -> Result<(), MenuError> {
+    let failed = |reason: String| MenuError::ScreenshotFailed {
+        path: node.path.clone(),
+        reason,
+    };
+    let deadline = Instant::now() + MENU_SHOW_TIMEOUT;
+    let frame = loop {
+        if let Some(frame) = backend.menu_frame(pid, node)? {
+            break frame;
+        }
+        if Instant::now() >= deadline {
+            return Err(failed("its menu is not on screen".to_owned()));
+        }
+        ctx.cancel.check()?;
+        std::thread::sleep(MENU_SHOW_POLL);
+    };
+    let region = format!(
+        "{:.0},{:.0},{:.0},{:.0}",
+        frame.x, frame.y, frame.width, frame.height
+    );
+    let status = Command::new("screencapture")
+        .args(["-x", "-R", &region])
+        .arg(path)
+        .status()
+        .map_err(|e| failed(format!("cannot run screencapture: {e}")))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(failed(format!("screencapture exited with {status}")))
+    }
+}
@@ -1,33 +1,133 @@
 /// `apps` command: list running applications with PIDs.
-use crate::ax::list_running_apps;
-use crate::cli::args::AppsArgs;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::ax::app::RunningApp;
+use crate::backend::AxBackend;
+use crate::cli::args::{AppSort, AppsArgs};
 use crate::cli::output::write_apps;
 use crate::cli::OutputCtx;
-use crate::menu::MenuError;
+use crate::menu::tree::{sweep_extras, TreeOptions};
+use crate::menu::{MenuError, MenuNode};
+use crate::pattern;
 use crate::types::AppInfoOutput;
 
 /// Run `menucli apps`.
 ///
 /// # Errors
 ///
-/// Cannot currently fail; the list may simply be empty.
-pub fn run(args: &AppsArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
-    let apps = list_running_apps();
+/// Returns `MenuError::InvalidInput` if `--filter` is not a valid pattern.
+pub fn run(args: &AppsArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let opts = TreeOptions {
+        include_alternates: false,
+        locale_aliases: false,
+        menus: Vec::new(),
+        exclude_menus: Vec::new(),
+        ax_timeout: ctx.ax_timeout,
+        progress: None,
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: false,
+    };
+    let output = app_infos(backend, args, &opts)?;
+    write_apps(&output, ctx);
+    Ok(())
+}
 
-    let mut output: Vec<AppInfoOutput> = apps
-        .iter()
+/// The running apps `args` selects. Probed capabilities (`--with-menu`,
+/// `--with-extras`) are also reported in each entry.
+///
+/// # Errors
+///
+/// Returns `MenuError::InvalidInput` if `--filter` is not a valid pattern.
+pub fn app_infos(
+    backend: &dyn AxBackend,
+    args: &AppsArgs,
+    opts: &TreeOptions,
+) -> Result<Vec<AppInfoOutput>, MenuError> {
+    let mut apps = if args.all {
+        backend.list_all_apps()
+    } else {
+        backend.list_apps()
+    };
+    if args.frontmost {
+        apps.retain(|a| a.frontmost);
+    }
+    // Before probing, which costs an AX round trip per app.
+    if let Some(pattern) = &args.filter {
+        let filter = name_filter(pattern, args.regex)?;
+        apps.retain(|a| {
+            filter.is_match(&a.name) || a.bundle_id.as_deref().is_some_and(|b| filter.is_match(b))
+        });
+    }
+    sort_apps(&mut apps, args.sort);
+    let has_menu = args.with_menu.then(|| {
+        probe(&apps, opts, |pid, opts| {
+            backend.build_tree(pid, Some(1), opts)
+        })
+    });
+    let has_extras = args.with_extras.then(|| {
+        probe(&apps, opts, |pid, opts| {
+            backend.build_extras_tree(pid, Some(1), opts)
+        })
+    });
+
+    Ok(apps
+        .into_iter()
         .map(|a| AppInfoOutput {
-            name: a.name.clone(),
+            has_menu: has_menu.as_ref().map(|pids| pids.contains(&a.pid)),
+            has_extras: has_extras.as_ref().map(|pids| pids.contains(&a.pid)),
+            name: a.name,
             pid: a.pid,
-            bundle_id: a.bundle_id.clone(),
+            bundle_id: a.bundle_id,
             frontmost: a.frontmost,
+            activation_policy: a.activation_policy,
+            launch_date: a.launch_date,
         })
-        .collect();
+        .filter(|a| a.has_menu != Some(false) && a.has_extras != Some(false))
+        .collect())
+}
 
-    if args.frontmost {
-        output.retain(|a| a.frontmost);
+/// Reorder `apps`, which backends list by name, for `--sort`. Ties keep
+/// their name order.
+fn sort_apps(apps: &mut [RunningApp], sort: AppSort) {
+    match sort {
+        AppSort::Name => {}
+        AppSort::Pid => apps.sort_by_key(|a| a.pid),
+        AppSort::Frontmost => apps.sort_by_key(|a| !a.frontmost),
+        AppSort::LaunchDate => {
+            apps.sort_by_key(|a| (a.launch_date.is_none(), Reverse(a.launch_date)));
+        }
     }
+}
 
-    write_apps(&output, ctx);
-    Ok(())
+/// Compile `--filter`, ignoring case: a regular expression with `--regex`, a
+/// glob matching the whole name if it has a `*` or `?` (as `--app glob:`
+/// does), else a substring.
+fn name_filter(filter: &str, regex: bool) -> Result<Regex, MenuError> {
+    let compiled = if regex {
+        pattern::regex(filter, false)
+    } else if filter.contains(['*', '?']) {
+        pattern::glob(filter, false)
+    } else {
+        pattern::regex(&regex::escape(filter), false)
+    };
+    compiled.map_err(|e| MenuError::InvalidInput {
+        reason: format!("--filter: {e}"),
+    })
+}
+
+/// PIDs of the `apps` whose top level `build` reads as non-empty. The sweep
+/// behind `list --extras` does the work: several apps at once, each with a
+/// short AX timeout so unresponsive apps just count as lacking it.
+fn probe<F>(apps: &[RunningApp], opts: &TreeOptions, build: F) -> HashSet<i32>
+where
+    F: Fn(i32, &TreeOptions) -> Result<Vec<MenuNode>, MenuError> + Sync,
+{
+    sweep_extras(apps, opts, build)
+        .into_iter()
+        .map(|found| found.app_pid)
+        .collect()
 }
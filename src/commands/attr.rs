@@ -0,0 +1,42 @@
+/// `attr` command: read one AX attribute of a menu item.
+use tracing::debug_span;
+
+use super::resolve_item;
+use crate::backend::AxBackend;
+use crate::cli::args::AttrArgs;
+use crate::cli::output::write_attribute;
+use crate::cli::OutputCtx;
+use crate::menu::MenuError;
+use crate::types::AttributeOutput;
+
+/// Run `menucli attr`.
+///
+/// Resolves the item as `click` does, then reads the attribute from its live
+/// element, so anything the app exposes can be read before it becomes a
+/// field of `list`.
+///
+/// # Errors
+///
+/// Returns `MenuError` on AX failure, missing permissions, unknown app, or
+/// unresolvable path.
+pub fn run(args: &AttrArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let (lookup, node) = resolve_item(backend, &args.item, ctx)?;
+    let pid = lookup.app.app_pid;
+
+    let _t_attribute = debug_span!("read_attribute").entered();
+    let output = AttributeOutput {
+        id: node.id.clone(),
+        title: node.title.clone(),
+        path: node.path.clone(),
+        attribute: args.name().to_owned(),
+        value: backend.attribute(pid, &node, args.name())?,
+        app_name: Some(lookup.app.app_name.clone()),
+        app_pid: Some(pid),
+    };
+    drop(_t_attribute);
+
+    if !ctx.quiet {
+        write_attribute(&output, ctx);
+    }
+    Ok(())
+}
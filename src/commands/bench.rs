@@ -0,0 +1,115 @@
+/// `bench` command: time how fast an application's menus can be read.
+use std::time::{Duration, Instant};
+
+use super::record_target;
+use crate::backend::AxBackend;
+use crate::cli::args::BenchArgs;
+use crate::cli::output::write_bench;
+use crate::cli::OutputCtx;
+use crate::menu::tree::TreeOptions;
+use crate::menu::{flatten, CancelToken, MenuError};
+use crate::types::{BenchOutput, BenchTiming, MenuBenchOutput};
+
+/// Run `menucli bench`.
+///
+/// # Errors
+///
+/// Returns `MenuError` on AX failure, missing permissions, or unknown app.
+pub fn run(args: &BenchArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let opts = TreeOptions {
+        include_alternates: ctx.alternates,
+        locale_aliases: false,
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: None,
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: false,
+    };
+
+    let pid = backend
+        .resolve_target(args.app.as_deref())
+        .map_err(MenuError::from)?;
+    record_target(backend, pid, ctx);
+
+    let (tree, build) = measure(args.iterations, &opts.cancel, || {
+        backend.build_tree(pid, None, &opts)
+    })?;
+    let items = flatten(&tree).len();
+
+    let mut menus = Vec::new();
+    for menu in &tree {
+        let menu_opts = TreeOptions {
+            menus: vec![menu.title.clone()],
+            ..opts.clone()
+        };
+        let (nodes, build) = measure(args.iterations, &opts.cancel, || {
+            backend.build_tree(pid, None, &menu_opts)
+        })?;
+        menus.push(MenuBenchOutput {
+            menu: menu.title.clone(),
+            items: flatten(&nodes).len(),
+            build,
+        });
+    }
+
+    let (fetched, fetch_batch) = measure(args.iterations, &opts.cancel, || {
+        backend.fetch_attributes(pid, true, &opts)
+    })?;
+    let (_, fetch_single) = measure(args.iterations, &opts.cancel, || {
+        backend.fetch_attributes(pid, false, &opts)
+    })?;
+
+    #[allow(clippy::cast_precision_loss)]
+    let items_per_sec = per_sec(items as f64, build.median_ms);
+    write_bench(
+        &BenchOutput {
+            pid,
+            iterations: args.iterations,
+            items,
+            build,
+            items_per_sec,
+            menus,
+            fetched,
+            fetch_batch,
+            fetch_single,
+            batch_speedup: ratio(fetch_single.median_ms, fetch_batch.median_ms),
+        },
+        ctx,
+    );
+    Ok(())
+}
+
+/// Run `f` `iterations` (at least one) times, returning its last result and
+/// the timings. Ctrl-C stops the benchmark rather than skewing it with a
+/// partial walk.
+fn measure<T>(
+    iterations: u32,
+    cancel: &CancelToken,
+    mut f: impl FnMut() -> Result<T, MenuError>,
+) -> Result<(T, BenchTiming), MenuError> {
+    let mut samples: Vec<Duration> = Vec::new();
+    loop {
+        let start = Instant::now();
+        let result = f()?;
+        samples.push(start.elapsed());
+        cancel.check()?;
+        if samples.len() >= iterations as usize {
+            return Ok((result, BenchTiming::from_samples(&samples)));
+        }
+    }
+}
+
+fn per_sec(count: f64, ms: f64) -> f64 {
+    ratio(count * 1000.0, ms)
+}
+
+/// `a / b`, or 0 when `b` is too small to divide by meaningfully.
+fn ratio(a: f64, b: f64) -> f64 {
+    if b > f64::EPSILON {
+        a / b
+    } else {
+        0.0
+    }
+}
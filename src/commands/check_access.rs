@@ -1,5 +1,9 @@
 /// `check-access` command: verify Accessibility permission is granted.
-use crate::ax::{ensure_trusted, permission_instructions};
+use std::process::Command;
+
+use crate::ax::{host_app_path, permission_instructions, ACCESSIBILITY_SETTINGS_URL};
+use crate::backend::AxBackend;
+use crate::cli::args::CheckAccessArgs;
 use crate::cli::OutputCtx;
 use crate::menu::MenuError;
 
@@ -10,8 +14,17 @@ use crate::menu::MenuError;
 /// # Errors
 ///
 /// Returns `MenuError::AccessDenied` if permission is not granted.
-pub fn run(ctx: &OutputCtx) -> Result<(), MenuError> {
-    ensure_trusted().map_err(|_| MenuError::AccessDenied)?;
+pub fn run(
+    args: &CheckAccessArgs,
+    ctx: &OutputCtx,
+    backend: &dyn AxBackend,
+) -> Result<(), MenuError> {
+    if backend.ensure_trusted().is_err() {
+        if args.open_settings {
+            open_settings();
+        }
+        return Err(MenuError::AccessDenied);
+    }
 
     match ctx.format {
         crate::cli::OutputFormat::Json
@@ -27,3 +40,27 @@ pub fn run(ctx: &OutputCtx) -> Result<(), MenuError> {
 
     Ok(())
 }
+
+/// Open the Accessibility pane and reveal the terminal's app bundle in Finder
+/// so it can be dragged into the list. Best-effort: the error that follows
+/// still explains the manual steps.
+fn open_settings() {
+    let open = |args: &[&str]| {
+        Command::new("open")
+            .args(args)
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+    if open(&[ACCESSIBILITY_SETTINGS_URL]) {
+        eprintln!("Opened System Settings → Privacy & Security → Accessibility.");
+    }
+    let Some(app) = host_app_path() else {
+        return;
+    };
+    if open(&["-R", &app.to_string_lossy()]) {
+        eprintln!(
+            "Revealed {} in Finder: drag it into the list, then restart it.",
+            app.display()
+        );
+    }
+}
@@ -1,72 +1,225 @@
-/// `click` command: activate (press) a menu item.
-use crate::ax::resolve_target;
-use crate::cli::args::ClickArgs;
-use crate::cli::output::write_menu_items;
+/// `click` command: activate (press) one or more menu items.
+use std::time::{Duration, Instant};
+
+use tracing::{debug, debug_span};
+
+use super::toggle::reresolve;
+use super::{activate, attribute, build_for_query, resolve_app, resolve_paths, scope};
+use crate::ax::AXError;
+use crate::backend::AxBackend;
+use crate::cli::args::{read_lines, ClickArgs, ItemCondition, WaitCondition};
+use crate::cli::hooks::Hook;
+use crate::cli::output::{timestamp_now, write_menu_items};
 use crate::cli::OutputCtx;
-use crate::menu::tree::{build_extras_tree, TreeOptions};
-use crate::menu::{build_tree_with_opts, press_node, resolve, MenuError};
+use crate::menu::tree::{names_dynamic_submenu, press_modifiers, TreeOptions};
+use crate::menu::{CancelToken, CheckState, MenuError, MenuNode};
 use crate::types::MenuItemOutput;
 
-/// Helper to convert a `MenuNode` to `MenuItemOutput`.
-fn node_to_output(node: &crate::menu::MenuNode) -> MenuItemOutput {
-    MenuItemOutput {
-        title: node.title.clone(),
-        path: node.path.clone(),
-        enabled: node.enabled,
-        checked: node.checked,
-        shortcut: node.shortcut.clone(),
-        role: node.role.clone(),
-        children_count: node.children.len(),
-        depth: node.depth,
-        is_alternate: node.is_alternate,
-        alternate_of: node.alternate_of.clone(),
-        app_name: None,
-        app_pid: None,
-    }
-}
-
 /// Run `menucli click`.
 ///
+/// Every path is resolved against one tree build before anything is pressed,
+/// so a typo clicks nothing. The items are then pressed in order, `--delay`
+/// apart, stopping at the first failure; the items clicked until then are
+/// still reported on stdout. With `--wait-for`, the command then blocks
+/// until the condition holds.
+///
 /// # Errors
 ///
 /// Returns `MenuError` on AX failure, missing permissions, unknown app, unresolvable path,
-/// or if the item is disabled.
-pub fn run(args: &ClickArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
+/// or if an item is disabled.
+/// Returns `MenuError::ConditionNotMet` if `--wait-for` timed out.
+pub fn run(args: &ClickArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let paths = paths(args)?;
     let tree_opts = TreeOptions {
         include_alternates: ctx.alternates,
+        locale_aliases: args.resolve.lang.is_some(),
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: ctx.walk_progress(),
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: args.walk.open_menus || paths.iter().any(|p| names_dynamic_submenu(p)),
     };
 
-    let _t_resolve = ctx.timer("resolve_target");
-    let pid = resolve_target(args.app.as_deref()).map_err(MenuError::from)?;
-    drop(_t_resolve);
-
-    let tree = if args.extras {
-        let _t_tree = ctx.timer("build_extras_tree");
-        let t = build_extras_tree(pid, None, &tree_opts)?;
-        drop(_t_tree);
-        t
-    } else {
-        let _t_tree = ctx.timer("build_tree");
-        let t = build_tree_with_opts(pid, None, &tree_opts)?;
-        drop(_t_tree);
-        t
-    };
+    let pid = resolve_app(backend, args.app.as_deref(), args.first, &args.focus, ctx)?;
+    let _focus = activate(backend, pid, &args.focus)?;
 
-    let _t_resolve_path = ctx.timer("resolve_path");
-    let node = resolve(&tree, &args.path)?;
+    let resolve_opts = args.resolve.options();
+    let tree = build_for_query(
+        backend,
+        pid,
+        args.extras,
+        // Only a single path can be answered from one branch.
+        match paths.as_slice() {
+            [path] if args.within.is_none() => Some(path.as_str()),
+            _ => None,
+        },
+        &resolve_opts,
+        &tree_opts,
+        ctx,
+    )?;
+
+    let _t_resolve_path = debug_span!("resolve_path").entered();
+    let app = attribute(&backend.list_all_apps(), pid, tree);
+    let scoped = scope(&app.nodes, args.within.as_deref(), &resolve_opts)?;
+    let nodes = resolve_paths(
+        scoped,
+        &paths,
+        args.id.as_deref(),
+        &resolve_opts,
+        args.first,
+        args.all,
+    )?;
     drop(_t_resolve_path);
 
-    let output = node_to_output(node);
+    let mut output: Vec<_> = nodes
+        .iter()
+        .map(|n| MenuItemOutput {
+            app_name: Some(app.app_name.clone()),
+            app_pid: Some(pid),
+            bundle_id: app.bundle_id.clone(),
+            modifiers: press_modifiers(n, &args.modifiers),
+            ..MenuItemOutput::from_node(n)
+        })
+        .collect();
 
     if args.dry_run {
-        write_menu_items(&[output], ctx);
+        if !ctx.quiet {
+            write_menu_items(&output, ctx);
+        }
         return Ok(());
     }
 
-    let _t_press = ctx.timer("press_node");
-    press_node(node)?;
+    // Owned, since each press stamps its item in `output`.
+    let item_paths: Vec<String> = output.iter().map(|item| item.path.clone()).collect();
+    let item_paths: Vec<&str> = item_paths.iter().map(String::as_str).collect();
+    ctx.hooks.run(Hook::PreClick, pid, &item_paths, &output)?;
+
+    let _t_press = debug_span!("press_node").entered();
+    let mut clicked = 0;
+    let mut result = Ok(());
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            if let Some(delay) = args.delay {
+                std::thread::sleep(delay);
+            }
+        }
+        result = ctx
+            .cancel
+            .check()
+            .and_then(|()| press(backend, pid, args, node, &tree_opts));
+        if result.is_err() {
+            break;
+        }
+        output[i].performed_at = Some(timestamp_now());
+        clicked += 1;
+    }
     drop(_t_press);
 
-    write_menu_items(&[output], ctx);
-    Ok(())
+    if clicked > 0 && !ctx.quiet {
+        write_menu_items(&output[..clicked], ctx);
+    }
+    result?;
+    ctx.hooks
+        .run_after(Hook::PostClick, pid, &item_paths, &output);
+
+    match &args.wait_for {
+        Some(condition) => wait_for(backend, pid, args, condition, &tree_opts, &ctx.cancel),
+        None => Ok(()),
+    }
+}
+
+/// How often `--wait-for` re-checks its condition.
+const WAIT_POLL: Duration = Duration::from_millis(100);
+
+/// Block until `condition` holds or `--wait-timeout` passes.
+fn wait_for(
+    backend: &dyn AxBackend,
+    pid: i32,
+    args: &ClickArgs,
+    condition: &WaitCondition,
+    tree_opts: &TreeOptions,
+    cancel: &CancelToken,
+) -> Result<(), MenuError> {
+    let _t_wait = debug_span!("wait_for").entered();
+    let deadline = Instant::now() + args.wait_timeout;
+    loop {
+        if condition_holds(backend, pid, args.extras, condition, tree_opts)? {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(MenuError::ConditionNotMet {
+                condition: condition.to_string(),
+            });
+        }
+        cancel.check()?;
+        std::thread::sleep(WAIT_POLL);
+    }
+}
+
+/// Check `condition` once against the app's current menus or windows.
+fn condition_holds(
+    backend: &dyn AxBackend,
+    pid: i32,
+    extras: bool,
+    condition: &WaitCondition,
+    tree_opts: &TreeOptions,
+) -> Result<bool, MenuError> {
+    let (path, state) = match condition {
+        WaitCondition::WindowTitle(text) => {
+            let text = text.to_lowercase();
+            let titles = backend.window_titles(pid)?;
+            return Ok(titles.iter().any(|t| t.to_lowercase().contains(&text)));
+        }
+        WaitCondition::State { path, state } => (path, *state),
+    };
+    let node = match reresolve(backend, pid, extras, path, tree_opts) {
+        Ok(node) => Some(node),
+        Err(MenuError::ItemNotFound { .. }) => None,
+        Err(e) => return Err(e),
+    };
+    Ok(match (state, node) {
+        (ItemCondition::Gone, node) => node.is_none(),
+        (_, None) => false,
+        (ItemCondition::Exists, Some(_)) => true,
+        (ItemCondition::Checked, Some(n)) => n.check_state == CheckState::On,
+        (ItemCondition::Unchecked, Some(n)) => n.check_state == CheckState::Off,
+        (ItemCondition::Enabled, Some(n)) => n.enabled,
+        (ItemCondition::Disabled, Some(n)) => !n.enabled,
+    })
+}
+
+/// The positional paths followed by those read from stdin with `--stdin`.
+fn paths(args: &ClickArgs) -> Result<Vec<String>, MenuError> {
+    let mut paths = args.paths.clone();
+    if args.stdin {
+        let read = read_lines("-").map_err(|reason| MenuError::InvalidInput { reason })?;
+        if read.is_empty() && paths.is_empty() {
+            return Err(MenuError::InvalidInput {
+                reason: "no paths on stdin".to_owned(),
+            });
+        }
+        paths.extend(read);
+    }
+    Ok(paths)
+}
+
+/// Press `node`, finding it again by path if an earlier click made the app
+/// rebuild its menu and the element is gone.
+fn press(
+    backend: &dyn AxBackend,
+    pid: i32,
+    args: &ClickArgs,
+    node: &MenuNode,
+    tree_opts: &TreeOptions,
+) -> Result<(), MenuError> {
+    match backend.press_with(pid, node, &args.modifiers) {
+        Err(MenuError::AX(AXError::InvalidElement)) => {
+            debug!(path = %node.path, "menu item element gone; resolving it again");
+            let fresh = reresolve(backend, pid, args.extras, &node.path, tree_opts)?;
+            backend.press_with(pid, &fresh, &args.modifiers)
+        }
+        result => result,
+    }
 }
@@ -0,0 +1,40 @@
+/// `completions` command: print a shell completion script.
+use std::io;
+
+use clap::error::ErrorKind;
+use clap::CommandFactory;
+use clap_complete::env::{Bash, EnvCompleter, Fish, Zsh};
+use clap_complete::generate;
+use clap_complete_nushell::Nushell;
+
+use crate::cli::args::{CompletionShell, CompletionsArgs};
+use crate::cli::Cli;
+
+/// Run `menucli completions`, exiting with an I/O error if the script
+/// cannot be written.
+///
+/// Bash, zsh, and fish get a script that asks `COMPLETE=<shell> menucli` for
+/// candidates on every TAB (see `main`), so subcommands and flags never go
+/// stale and `--app` and menu paths complete from the running system
+/// (see [`crate::cli::complete`]). Nushell gets a static script generated
+/// from the argument definitions.
+pub fn run(args: &CompletionsArgs) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_owned();
+    let shell: &dyn EnvCompleter = match args.shell {
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Zsh => &Zsh,
+        CompletionShell::Fish => &Fish,
+        CompletionShell::Nushell => {
+            generate(Nushell, &mut command, name, &mut io::stdout());
+            return;
+        }
+    };
+    match shell.write_registration("COMPLETE", &name, &name, &name, &mut io::stdout().lock()) {
+        // A reader that stops early (`| head`) is not a failure.
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => {
+            Cli::command().error(ErrorKind::Io, err).exit();
+        }
+        _ => {}
+    }
+}
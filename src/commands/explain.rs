@@ -0,0 +1,37 @@
+/// `explain` command: trace how a query resolves.
+use tracing::debug_span;
+
+use super::lookup;
+use crate::backend::AxBackend;
+use crate::cli::args::ExplainArgs;
+use crate::cli::output::write_explanation;
+use crate::cli::OutputCtx;
+use crate::menu::{explain, MenuError};
+use crate::types::ExplainOutput;
+
+/// Run `menucli explain`.
+///
+/// Resolves the path as `resolve` does, and reports every candidate the
+/// matching strategy found, the fuzzy confidence ratio, and whether it was
+/// enough to auto-resolve. An ambiguous query is reported, not an error.
+///
+/// # Errors
+///
+/// Returns `MenuError` on AX failure, missing permissions, unknown app, or a
+/// path nothing matches.
+pub fn run(args: &ExplainArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let lookup = lookup(backend, &args.lookup, Some(&args.path), false, ctx)?;
+
+    let _t_explain = debug_span!("explain").entered();
+    let explanation = explain(lookup.nodes()?, &args.path, &lookup.resolve)?;
+    drop(_t_explain);
+
+    let output = ExplainOutput::from_explanation(
+        &args.path,
+        &explanation,
+        lookup.resolve.fuzzy_ratio,
+        args.limit,
+    );
+    write_explanation(&output, ctx);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+/// `focused` command: report what has keyboard focus system-wide.
+use tracing::debug_span;
+
+use super::attribute;
+use crate::backend::AxBackend;
+use crate::cli::args::FocusedArgs;
+use crate::cli::output::{timestamp_now, write_focused};
+use crate::cli::OutputCtx;
+use crate::menu::tree::TreeOptions;
+use crate::menu::{flatten, MenuError};
+use crate::types::{FocusedOutput, MenuItemOutput};
+
+/// Run `menucli focused`.
+///
+/// Asks the system-wide AX element for the focused app and element. With
+/// `--highlighted`, also reads that app's menus and reports the deepest item
+/// AX marks as selected — the one under the pointer or keyboard in an open
+/// menu.
+///
+/// # Errors
+///
+/// Returns `MenuError` on AX failure, missing permissions, or when no app
+/// has focus.
+pub fn run(args: &FocusedArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let _t_focus = debug_span!("focus").entered();
+    let focus = backend.focus().map_err(MenuError::from)?;
+    drop(_t_focus);
+
+    let tree = if args.highlighted {
+        let opts = TreeOptions {
+            include_alternates: ctx.alternates,
+            locale_aliases: false,
+            menus: Vec::new(),
+            exclude_menus: ctx.exclude_menus.clone(),
+            ax_timeout: ctx.ax_timeout,
+            progress: ctx.walk_progress(),
+            cancel: ctx.cancel.clone(),
+            extras_jobs: None,
+            open_menus: false,
+        };
+        let _t_tree = debug_span!("build_tree").entered();
+        if args.extras {
+            backend.build_extras_tree(focus.pid, None, &opts)?
+        } else {
+            backend.build_tree(focus.pid, None, &opts)?
+        }
+    } else {
+        Vec::new()
+    };
+
+    let app = attribute(&backend.list_all_apps(), focus.pid, tree);
+    let highlighted = flatten(&app.nodes)
+        .into_iter()
+        .filter(|f| f.selected == Some(true))
+        .max_by_key(|f| f.depth)
+        .map(|f| MenuItemOutput::from_flat(f, Some(&app)));
+
+    let output = FocusedOutput {
+        app_name: app.app_name.clone(),
+        app_pid: app.app_pid,
+        bundle_id: app.bundle_id.clone(),
+        element: focus.element,
+        highlighted,
+        generated_at: Some(timestamp_now()),
+    };
+    write_focused(&output, ctx);
+    Ok(())
+}
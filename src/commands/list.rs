@@ -1,152 +1,217 @@
 /// `list` command: list all menu items for an application.
-use crate::ax::resolve_target;
+use tracing::debug_span;
+
+use super::{
+    activate, attribute, attribution, build_app_trees, check_interrupted, ensure_nonempty,
+    resolve_app, single_app_focus,
+};
+use crate::backend::AxBackend;
 use crate::cli::args::ListArgs;
-use crate::cli::output::{write_menu_items, write_menu_tree};
+use crate::cli::output::{
+    timestamp_now, write_app_trees, write_count, write_menu_items, write_menu_tree,
+};
+use crate::cli::progress::Spinner;
 use crate::cli::OutputCtx;
-use crate::menu::tree::{build_all_extras, build_extras_tree, TreeOptions};
-use crate::menu::{build_tree_with_opts, flatten, MenuError, MenuNode};
-use crate::types::{MenuItemOutput, MenuTreeOutput};
+use crate::menu::tree::{ExtrasResult, TreeOptions};
+use crate::menu::{flatten, MenuError, MenuNode};
+use crate::types::{MenuCountOutput, MenuItemOutput, MenuTreeOutput};
 
 /// Run `menucli list`.
 ///
 /// # Errors
 ///
 /// Returns `MenuError` on AX failure, missing permissions, or unknown app.
-pub fn run(args: &ListArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
+pub fn run(args: &ListArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
     let opts = TreeOptions {
         include_alternates: ctx.alternates,
+        locale_aliases: false,
+        menus: args.menu.clone(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: ctx.walk_progress(),
+        cancel: ctx.cancel.clone(),
+        extras_jobs: args.jobs,
+        open_menus: args.walk.open_menus,
     };
 
     if args.extras {
-        return run_extras(args, ctx, &opts);
+        return run_extras(args, ctx, backend, &opts);
+    }
+
+    if let [_, _, ..] = args.app.as_slice() {
+        let spinner = Spinner::start(opts.progress.as_ref());
+        let results = build_app_trees(backend, &args.app, false, &args.focus, |pid| {
+            backend.build_tree(pid, args.depth, &opts)
+        })?;
+        drop(spinner);
+        check_interrupted(ctx, args.partial)?;
+        return output_attributed(&results, args, ctx);
     }
 
-    let _t_resolve = ctx.timer("resolve_target");
-    let pid = resolve_target(args.app.as_deref()).map_err(MenuError::from)?;
-    drop(_t_resolve);
+    let app = args.app.first().map(String::as_str);
+    let pid = resolve_app(backend, app, false, &args.focus, ctx)?;
+    let _focus = activate(backend, pid, &args.focus)?;
 
-    let _t_tree = ctx.timer("build_tree");
-    let tree = build_tree_with_opts(pid, args.depth, &opts)?;
+    let _t_tree = debug_span!("build_tree").entered();
+    let spinner = Spinner::start(opts.progress.as_ref());
+    let tree = backend.build_tree(pid, args.depth, &opts)?;
+    drop(spinner);
     drop(_t_tree);
+    check_interrupted(ctx, args.partial)?;
 
-    output_tree(&tree, args, ctx, None)
+    output_tree(&attribute(&backend.list_all_apps(), pid, tree), args, ctx)
 }
 
-fn run_extras(args: &ListArgs, ctx: &OutputCtx, opts: &TreeOptions) -> Result<(), MenuError> {
-    if let Some(app) = &args.app {
-        // Single app extras
-        let _t_resolve = ctx.timer("resolve_target");
-        let pid = resolve_target(Some(app.as_str())).map_err(MenuError::from)?;
-        drop(_t_resolve);
+fn run_extras(
+    args: &ListArgs,
+    ctx: &OutputCtx,
+    backend: &dyn AxBackend,
+    opts: &TreeOptions,
+) -> Result<(), MenuError> {
+    match args.app.as_slice() {
+        [] => {
+            // All apps extras
+            single_app_focus(&args.focus)?;
+            let _t_tree = debug_span!("build_all_extras").entered();
+            let spinner = Spinner::start(opts.progress.as_ref());
+            let results = backend.build_all_extras(args.depth, opts);
+            drop(spinner);
+            drop(_t_tree);
+            check_interrupted(ctx, args.partial)?;
+
+            output_attributed(&results, args, ctx)
+        }
+        [app] => {
+            // Single app extras
+            let pid = resolve_app(backend, Some(app.as_str()), false, &args.focus, ctx)?;
+            let _focus = activate(backend, pid, &args.focus)?;
+
+            let _t_tree = debug_span!("build_extras_tree").entered();
+            let spinner = Spinner::start(opts.progress.as_ref());
+            let tree = backend.build_extras_tree(pid, args.depth, opts)?;
+            drop(spinner);
+            drop(_t_tree);
+            check_interrupted(ctx, args.partial)?;
+
+            output_tree(&attribute(&backend.list_all_apps(), pid, tree), args, ctx)
+        }
+        apps => {
+            let spinner = Spinner::start(opts.progress.as_ref());
+            let results = build_app_trees(backend, apps, false, &args.focus, |pid| {
+                backend.build_extras_tree(pid, args.depth, opts)
+            })?;
+            drop(spinner);
+            check_interrupted(ctx, args.partial)?;
+
+            output_attributed(&results, args, ctx)
+        }
+    }
+}
 
-        let _t_tree = ctx.timer("build_extras_tree");
-        let tree = build_extras_tree(pid, args.depth, opts)?;
-        drop(_t_tree);
+/// Output the trees of several apps: a section per app with `--tree`,
+/// otherwise one list of items attributed to their app.
+fn output_attributed(
+    results: &[ExtrasResult],
+    args: &ListArgs,
+    ctx: &OutputCtx,
+) -> Result<(), MenuError> {
+    let use_tree = args.tree && !args.flat;
 
-        output_tree(&tree, args, ctx, None)
+    if use_tree {
+        ensure_nonempty(
+            args.fail_if_empty,
+            results.iter().all(|r| r.nodes.is_empty()),
+        )?;
+        // For tree output, show each app's menus separately.
+        let trees: Vec<(&ExtrasResult, Vec<MenuTreeOutput>)> = results
+            .iter()
+            .filter(|r| !r.nodes.is_empty())
+            .map(|r| (r, tree_output(&r.nodes)))
+            .collect();
+        write_app_trees(&trees, ctx);
     } else {
-        // All apps extras
-        let _t_tree = ctx.timer("build_all_extras");
-        let results = build_all_extras(args.depth, opts);
-        drop(_t_tree);
-
-        // Flatten all results into a single list with app attribution.
-        let use_tree = args.tree && !args.flat;
-
-        if use_tree {
-            // For tree output, show each app's extras separately.
-            for result in &results {
-                let nodes: Vec<MenuTreeOutput> =
-                    result.nodes.iter().map(node_to_tree_output).collect();
-                if !nodes.is_empty() {
-                    println!("--- {} (pid {}) ---", result.app_name, result.app_pid);
-                    write_menu_tree(&nodes, ctx);
-                }
-            }
-            Ok(())
-        } else {
-            let mut items: Vec<MenuItemOutput> = Vec::new();
-            for result in &results {
-                let flat = flatten(&result.nodes);
-                for f in flat {
-                    items.push(flat_to_output(
-                        f,
-                        Some(&result.app_name),
-                        Some(result.app_pid),
-                    ));
-                }
+        let mut items: Vec<MenuItemOutput> = Vec::new();
+        for result in results {
+            let flat = flatten(&result.nodes);
+            for f in flat {
+                items.push(MenuItemOutput::from_flat(
+                    f,
+                    attribution(result, args.from_file.as_deref()),
+                ));
             }
+        }
+        stamp(&mut items);
 
-            if args.enabled_only {
-                items.retain(|i| i.enabled);
-            }
+        apply_filters(&mut items, args);
+        ensure_nonempty(args.fail_if_empty, items.is_empty())?;
 
-            write_menu_items(&items, ctx);
-            Ok(())
-        }
+        write_items(&items, args, ctx);
     }
+
+    Ok(())
 }
 
-fn output_tree(
-    tree: &[MenuNode],
-    args: &ListArgs,
-    ctx: &OutputCtx,
-    app_info: Option<(&str, i32)>,
-) -> Result<(), MenuError> {
+fn output_tree(app: &ExtrasResult, args: &ListArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
+    let tree = &app.nodes;
     let use_tree = args.tree && !args.flat;
 
     if use_tree {
-        let nodes: Vec<MenuTreeOutput> = tree.iter().map(node_to_tree_output).collect();
-        write_menu_tree(&nodes, ctx);
+        ensure_nonempty(args.fail_if_empty, tree.is_empty())?;
+        write_menu_tree(&tree_output(tree), ctx);
     } else {
-        let _t_flatten = ctx.timer("flatten");
+        let _t_flatten = debug_span!("flatten").entered();
         let mut items: Vec<MenuItemOutput> = flatten(tree)
             .into_iter()
-            .map(|f| flat_to_output(f, app_info.map(|(n, _)| n), app_info.map(|(_, p)| p)))
+            .map(|f| MenuItemOutput::from_flat(f, attribution(app, args.from_file.as_deref())))
             .collect();
         drop(_t_flatten);
+        stamp(&mut items);
 
-        if args.enabled_only {
-            items.retain(|i| i.enabled);
-        }
+        apply_filters(&mut items, args);
+        ensure_nonempty(args.fail_if_empty, items.is_empty())?;
 
-        write_menu_items(&items, ctx);
+        write_items(&items, args, ctx);
     }
 
     Ok(())
 }
 
-fn flat_to_output(
-    f: crate::menu::FlatItem,
-    app_name: Option<&str>,
-    app_pid: Option<i32>,
-) -> MenuItemOutput {
-    MenuItemOutput {
-        title: f.title,
-        path: f.path,
-        enabled: f.enabled,
-        checked: f.checked,
-        shortcut: f.shortcut,
-        role: f.role,
-        children_count: f.children_count,
-        depth: f.depth,
-        is_alternate: f.is_alternate,
-        alternate_of: f.alternate_of,
-        app_name: app_name.map(str::to_owned),
-        app_pid,
+/// Write the filtered items, or just their counts with `--count`.
+fn write_items(items: &[MenuItemOutput], args: &ListArgs, ctx: &OutputCtx) {
+    if args.count {
+        write_count(&MenuCountOutput::from_items(items), ctx);
+    } else {
+        write_menu_items(items, ctx);
     }
 }
 
-fn node_to_tree_output(node: &MenuNode) -> MenuTreeOutput {
-    MenuTreeOutput {
-        title: node.title.clone(),
-        path: node.path.clone(),
-        enabled: node.enabled,
-        checked: node.checked,
-        shortcut: node.shortcut.clone(),
-        role: node.role.clone(),
-        children: node.children.iter().map(node_to_tree_output).collect(),
-        is_alternate: node.is_alternate,
-        alternate_of: node.alternate_of.clone(),
+/// The tree output of `nodes`, its top-level menus marked as read now.
+fn tree_output(nodes: &[MenuNode]) -> Vec<MenuTreeOutput> {
+    let now = timestamp_now();
+    nodes
+        .iter()
+        .map(|node| MenuTreeOutput {
+            generated_at: Some(now.clone()),
+            ..MenuTreeOutput::from_node(node)
+        })
+        .collect()
+}
+
+/// Mark `items` as read now.
+fn stamp(items: &mut [MenuItemOutput]) {
+    let now = timestamp_now();
+    for item in items {
+        item.generated_at = Some(now.clone());
     }
 }
+
+/// Drop flat items excluded by the `list` filter flags.
+fn apply_filters(items: &mut Vec<MenuItemOutput>, args: &ListArgs) {
+    items.retain(|i| {
+        (!args.enabled_only || i.enabled)
+            && (!args.leaf_only || i.children_count == 0)
+            && (!args.checked_only || i.checked)
+            && (!args.with_shortcut || i.shortcut.is_some())
+            && args.role.as_ref().is_none_or(|r| i.role == *r)
+    });
+}
@@ -0,0 +1,100 @@
+/// `man` command: generate man pages from the argument definitions.
+use std::io::Write;
+use std::path::Path;
+
+use clap::error::ErrorKind;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use roff::{bold, roman, Roff};
+
+use crate::cli::args::ManArgs;
+use crate::cli::Cli;
+use crate::menu::MenuError;
+
+/// Run `menucli man`, exiting with an I/O error if a page cannot be written.
+pub fn run(args: &ManArgs) {
+    let mut command = Cli::command().disable_help_subcommand(true);
+    command.build();
+    let result = match &args.out_dir {
+        Some(dir) => write_pages(&command, dir),
+        None => render(&command, &mut std::io::stdout().lock()),
+    };
+    match result {
+        // A reader that stops early (`| head`) is not a failure.
+        Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => {
+            Cli::command().error(ErrorKind::Io, err).exit();
+        }
+        _ => {}
+    }
+}
+
+/// Write the page of `command` and of each of its subcommands into `dir`,
+/// printing each file written.
+fn write_pages(command: &clap::Command, dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for command in std::iter::once(command).chain(command.get_subcommands()) {
+        let path = dir.join(Man::new(command.clone()).get_filename());
+        render(command, &mut std::fs::File::create(&path)?)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Render the page of `command`: clap's sections, then the exit codes every
+/// subcommand shares.
+fn render(command: &clap::Command, out: &mut dyn Write) -> std::io::Result<()> {
+    Man::new(command.clone()).render(out)?;
+    let mut roff = Roff::new();
+    roff.control("SH", ["EXIT STATUS"]);
+    for (code, meaning) in exit_codes() {
+        roff.control("TP", []);
+        roff.text([bold(code.to_string())]);
+        roff.text([roman(meaning)]);
+    }
+    roff.to_writer(out)
+}
+
+/// What each exit code means. The codes come from an error that produces
+/// them, so the page cannot drift from `MenuError::exit_code`.
+fn exit_codes() -> Vec<(i32, &'static str)> {
+    let errors = [
+        (
+            MenuError::InvalidInput {
+                reason: String::new(),
+            },
+            "Runtime error: the item is disabled, a toggle or --wait-for did not reach its state, \
+             a hook failed, or the app did not answer.",
+        ),
+        (
+            MenuError::AccessDenied,
+            "Accessibility permission is not granted.",
+        ),
+        (
+            MenuError::AppNotFound {
+                identifier: String::new(),
+            },
+            "No app or menu item matches, or the query matches several apps or items.",
+        ),
+        (
+            MenuError::NoResults,
+            "Nothing to print (list or search with --fail-if-empty).",
+        ),
+        (
+            MenuError::AssertionFailed {
+                path: String::new(),
+                condition: String::new(),
+            },
+            "A state --assert condition does not hold.",
+        ),
+        (MenuError::TimedOut, "--timeout passed."),
+        (MenuError::Cancelled, "Interrupted by Ctrl-C."),
+    ];
+    let mut codes = vec![(0, "Success."), (2, "Invalid command-line arguments.")];
+    codes.extend(
+        errors
+            .iter()
+            .map(|(err, meaning)| (err.exit_code(), *meaning)),
+    );
+    codes.sort_by_key(|&(code, _)| code);
+    codes
+}
@@ -1,29 +1,486 @@
 /// Command dispatch: routes `Command` enum variants to their implementations.
+pub mod action;
 pub mod apps;
+pub mod attr;
+pub mod bench;
 pub mod check_access;
 pub mod click;
+pub mod completions;
+pub mod explain;
+pub mod focused;
 pub mod list;
+pub mod man;
+pub mod preview;
+pub mod resolve;
+pub mod rpc;
 pub mod search;
 pub mod state;
 pub mod toggle;
 
-use crate::cli::args::Command;
+use std::path::Path;
+use std::time::Duration;
+
+use tracing::debug_span;
+
+use crate::ax::app::RunningApp;
+use crate::ax::AXError;
+use crate::backend::cache::default_dir;
+use crate::backend::{AxBackend, CacheBackend, FakeBackend};
+use crate::cli::args::{CacheArgs, Command, FocusArgs, ItemArgs, LookupArgs};
+use crate::cli::progress::Spinner;
 use crate::cli::OutputCtx;
-use crate::menu::MenuError;
+use crate::menu::tree::{names_dynamic_submenu, ExtrasResult, TreeOptions};
+use crate::menu::{
+    is_exact_path, resolve_all, resolve_id, resolve_with, subtree, MenuError, MenuNode,
+    ResolveOptions,
+};
 
 /// Dispatch a parsed `Command` to its handler.
 ///
 /// # Errors
 ///
-/// Returns `MenuError` on any command failure.
-pub fn dispatch(command: &Command, ctx: &OutputCtx) -> Result<(), MenuError> {
+/// Returns `MenuError` on any command failure, and `MenuError::Cancelled` if
+/// Ctrl-C interrupted it (even after printing `--partial` results).
+pub fn dispatch(
+    command: &Command,
+    ctx: &OutputCtx,
+    backend: &dyn AxBackend,
+) -> Result<(), MenuError> {
+    let t_command = debug_span!("command").entered();
+    run(command, ctx, backend)?;
+    drop(t_command);
+    ctx.cancel.check()
+}
+
+fn run(command: &Command, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
     match command {
-        Command::CheckAccess => check_access::run(ctx),
-        Command::Apps(args) => apps::run(args, ctx),
-        Command::List(args) => list::run(args, ctx),
-        Command::Search(args) => search::run(args, ctx),
-        Command::State(args) => state::run(args, ctx),
-        Command::Click(args) => click::run(args, ctx),
-        Command::Toggle(args) => toggle::run(args, ctx),
+        Command::CheckAccess(args) => check_access::run(args, ctx, backend),
+        Command::Apps(args) => apps::run(args, ctx, backend),
+        Command::Focused(args) => focused::run(args, ctx, backend),
+        Command::Bench(args) => bench::run(args, ctx, backend),
+        Command::List(args) => {
+            with_source(args.from_file.as_deref(), Some(&args.cache), backend, |b| {
+                list::run(args, ctx, b)
+            })
+        }
+        Command::Search(args) => {
+            with_source(args.from_file.as_deref(), Some(&args.cache), backend, |b| {
+                search::run(args, ctx, b)
+            })
+        }
+        Command::State(args) => with_source(args.from_file.as_deref(), None, backend, |b| {
+            state::run(args, ctx, b)
+        }),
+        Command::Click(args) => click::run(args, ctx, backend),
+        Command::Toggle(args) => toggle::run(args, ctx, backend),
+        Command::Action(args) => action::run(args, ctx, backend),
+        Command::Attr(args) => attr::run(args, ctx, backend),
+        Command::Resolve(args) => resolve::run(args, ctx, backend),
+        Command::Explain(args) => explain::run(args, ctx, backend),
+        Command::Preview(args) => preview::run(args, ctx, backend),
+        Command::Rpc => rpc::run(ctx, backend),
+        Command::Completions(args) => {
+            completions::run(args);
+            Ok(())
+        }
+        Command::Man(args) => {
+            man::run(args);
+            Ok(())
+        }
+        Command::Do(_) => unreachable!("aliases are expanded while parsing"),
+    }
+}
+
+/// Run `f` against a snapshot loaded from `--from-file` if given, else
+/// `backend` — through the disk cache when `cache` enables it.
+fn with_source(
+    from_file: Option<&Path>,
+    cache: Option<&CacheArgs>,
+    backend: &dyn AxBackend,
+    f: impl FnOnce(&dyn AxBackend) -> Result<(), MenuError>,
+) -> Result<(), MenuError> {
+    if let Some(path) = from_file {
+        return f(&FakeBackend::from_snapshot(path)?);
+    }
+    let cache_dir = cache.filter(|c| c.cache && !c.no_cache).zip(default_dir());
+    match cache_dir {
+        Some((cache, dir)) => f(&CacheBackend::new(
+            backend,
+            dir,
+            Duration::from_secs(cache.cache_ttl),
+        )),
+        None => f(backend),
+    }
+}
+
+/// How long `--activate` waits for the app's menu bar to appear.
+const ACTIVATE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `--launch` waits for a launched app's menu bar to appear.
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Hands focus back to the previously frontmost app when dropped.
+struct RestoreFocus<'a> {
+    backend: &'a dyn AxBackend,
+    previous: i32,
+}
+
+impl Drop for RestoreFocus<'_> {
+    fn drop(&mut self) {
+        let _t_restore = debug_span!("restore_focus").entered();
+        // Best-effort: the previous app may have quit meanwhile.
+        let _ = self.backend.activate(self.previous, Duration::ZERO);
+    }
+}
+
+/// Resolve `--app` to a PID, and record it as the command's target for the
+/// error envelope. When a pattern or name matches several apps, `first` takes
+/// the first of them (by name) instead of failing; with `--launch`, an app
+/// that is not running is started.
+fn resolve_app(
+    backend: &dyn AxBackend,
+    app: Option<&str>,
+    first: bool,
+    focus: &FocusArgs,
+    ctx: &OutputCtx,
+) -> Result<i32, MenuError> {
+    let pid = find_app(backend, app, first, focus)?;
+    record_target(backend, pid, ctx);
+    Ok(pid)
+}
+
+/// Record the app running as `pid` as the one the command works on, so that
+/// a later failure names it without looking it up again.
+fn record_target(backend: &dyn AxBackend, pid: i32, ctx: &OutputCtx) {
+    let name = backend
+        .list_apps()
+        .into_iter()
+        .find(|app| app.pid == pid)
+        .map(|app| app.name);
+    ctx.target.set(name, pid);
+}
+
+/// [`resolve_app`] without recording the target, for one of several apps.
+fn find_app(
+    backend: &dyn AxBackend,
+    app: Option<&str>,
+    first: bool,
+    focus: &FocusArgs,
+) -> Result<i32, MenuError> {
+    let _t_resolve = debug_span!("resolve_target").entered();
+    match (backend.resolve_target(app), app) {
+        (Err(AXError::AmbiguousApp { pids, .. }), _) if first => Ok(pids[0]),
+        (Err(AXError::AppNotFound { .. }), Some(app)) if focus.launch => {
+            let _t_launch = debug_span!("launch").entered();
+            Ok(backend.launch(app, LAUNCH_TIMEOUT)?)
+        }
+        (result, _) => result.map_err(MenuError::from),
+    }
+}
+
+/// Resolve each of several `--app`s and build their trees with `build`, all
+/// apps at once. Results keep the order the apps were given in, naming each
+/// app once; the first app that fails fails the command.
+fn build_app_trees<F>(
+    backend: &dyn AxBackend,
+    apps: &[String],
+    first: bool,
+    focus: &FocusArgs,
+    build: F,
+) -> Result<Vec<ExtrasResult>, MenuError>
+where
+    F: Fn(i32) -> Result<Vec<MenuNode>, MenuError> + Sync,
+{
+    single_app_focus(focus)?;
+    let mut pids: Vec<i32> = Vec::with_capacity(apps.len());
+    for app in apps {
+        let pid = find_app(backend, Some(app.as_str()), first, focus)?;
+        if !pids.contains(&pid) {
+            pids.push(pid);
+        }
+    }
+    let running = backend.list_all_apps();
+
+    let _t_tree = debug_span!("build_app_trees", apps = pids.len()).entered();
+    let build = &build;
+    let trees: Vec<Result<Vec<MenuNode>, MenuError>> = std::thread::scope(|s| {
+        let workers: Vec<_> = pids
+            .iter()
+            .map(|&pid| s.spawn(move || build(pid)))
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| {
+                w.join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    pids.into_iter()
+        .zip(trees)
+        .map(|(pid, tree)| Ok(attribute(&running, pid, tree?)))
+        .collect()
+}
+
+/// Reject `--activate` and `--restore-focus` for a command reading several
+/// apps (or every app's extras), which has no one app to bring to the front.
+fn single_app_focus(focus: &FocusArgs) -> Result<(), MenuError> {
+    if focus.activate || focus.restore_focus {
+        return Err(MenuError::InvalidInput {
+            reason: "--activate and --restore-focus take a single --app".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// `nodes`, attributed to the app running as `pid`, looked up once in
+/// `running` for its name and bundle ID. An app that is not among them (say,
+/// it quit meanwhile) is named by its PID.
+fn attribute(running: &[RunningApp], pid: i32, nodes: Vec<MenuNode>) -> ExtrasResult {
+    let app = running.iter().find(|a| a.pid == pid);
+    ExtrasResult {
+        app_name: app.map_or_else(|| pid.to_string(), |a| a.name.clone()),
+        app_pid: pid,
+        bundle_id: app.and_then(|a| a.bundle_id.clone()),
+        nodes,
+    }
+}
+
+/// The app `result` is attributed to in output: `None` for a `--from-file`
+/// snapshot, whose app is only a stand-in for the one it was taken from.
+fn attribution<'a>(result: &'a ExtrasResult, from_file: Option<&Path>) -> Option<&'a ExtrasResult> {
+    from_file.is_none().then_some(result)
+}
+
+/// Bring `pid` frontmost if `--activate` asks for it. With `--restore-focus`,
+/// the returned guard re-activates the app that was frontmost before once
+/// the command is done with `pid` — whether `--activate` or the command
+/// itself (say, a click opening a window) moved focus away from it.
+fn activate<'a>(
+    backend: &'a dyn AxBackend,
+    pid: i32,
+    focus: &FocusArgs,
+) -> Result<Option<RestoreFocus<'a>>, MenuError> {
+    let previous = focus
+        .restore_focus
+        .then(|| backend.resolve_target(None).ok())
+        .flatten()
+        .filter(|&p| p != pid);
+    if focus.activate {
+        let _t_activate = debug_span!("activate").entered();
+        backend.activate(pid, ACTIVATE_TIMEOUT)?;
+    }
+    Ok(previous.map(|previous| RestoreFocus { backend, previous }))
+}
+
+/// The app a single-item command works on, with the tree its item resolves
+/// against (see [`lookup`]).
+struct Lookup<'a> {
+    /// The app and its menus.
+    app: ExtrasResult,
+    /// How the item's path resolves.
+    resolve: ResolveOptions,
+    /// `--within`, applied by [`Lookup::nodes`].
+    within: Option<String>,
+    /// Hands focus back once the command is done, with `--restore-focus`.
+    _focus: Option<RestoreFocus<'a>>,
+}
+
+impl Lookup<'_> {
+    /// The menus to resolve the item in: the tree, narrowed to `--within`.
+    fn nodes(&self) -> Result<&[MenuNode], MenuError> {
+        scope(&self.app.nodes, self.within.as_deref(), &self.resolve)
+    }
+}
+
+/// Resolve `--app` (the first of several matches with `first`), activate it
+/// if asked, and build the tree `path` will be resolved against, as
+/// [`build_for_query`] does.
+fn lookup<'a>(
+    backend: &'a dyn AxBackend,
+    args: &LookupArgs,
+    path: Option<&str>,
+    first: bool,
+    ctx: &OutputCtx,
+) -> Result<Lookup<'a>, MenuError> {
+    let tree_opts = TreeOptions {
+        include_alternates: ctx.alternates,
+        locale_aliases: args.resolve.lang.is_some(),
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: ctx.walk_progress(),
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: args.walk.open_menus || path.is_some_and(names_dynamic_submenu),
+    };
+
+    let pid = resolve_app(backend, args.app.as_deref(), first, &args.focus, ctx)?;
+    let focus = activate(backend, pid, &args.focus)?;
+
+    let resolve = args.resolve.options();
+    let tree = build_for_query(
+        backend,
+        pid,
+        args.extras,
+        path.filter(|_| args.within.is_none()),
+        &resolve,
+        &tree_opts,
+        ctx,
+    )?;
+    Ok(Lookup {
+        app: attribute(&backend.list_all_apps(), pid, tree),
+        resolve,
+        within: args.within.clone(),
+        _focus: focus,
+    })
+}
+
+/// Look up the app of `--app` and resolve the one item `args` names in its
+/// menus, by `--id` or path; `--first` settles a wildcard path matching
+/// several.
+fn resolve_item<'a>(
+    backend: &'a dyn AxBackend,
+    args: &ItemArgs,
+    ctx: &OutputCtx,
+) -> Result<(Lookup<'a>, MenuNode), MenuError> {
+    let lookup = lookup(backend, &args.lookup, args.path.as_deref(), args.first, ctx)?;
+    let _t_resolve_path = debug_span!("resolve_path").entered();
+    let nodes = resolve_matches(
+        lookup.nodes()?,
+        args.path.as_deref(),
+        args.id.as_deref(),
+        &lookup.resolve,
+        args.first,
+        false,
+    )?;
+    let node = nodes[0].clone();
+    drop(_t_resolve_path);
+    Ok((lookup, node))
+}
+
+/// Build the tree `click`, `state`, and `toggle` resolve `query` against.
+///
+/// An exact `::` path only needs the branch it names, so the rest of the menu
+/// bar is never walked; anything else (fuzzy queries, `--id`, `--within`,
+/// extras) passes `None` and gets the full tree.
+fn build_for_query(
+    backend: &dyn AxBackend,
+    pid: i32,
+    extras: bool,
+    query: Option<&str>,
+    resolve: &ResolveOptions,
+    tree_opts: &TreeOptions,
+    ctx: &OutputCtx,
+) -> Result<Vec<MenuNode>, MenuError> {
+    let _spinner = Spinner::start(tree_opts.progress.as_ref());
+    let tree = query_tree(backend, pid, extras, query, resolve, tree_opts)?;
+    // Never act on an item from a walk cut short by Ctrl-C.
+    ctx.cancel.check()?;
+    Ok(tree)
+}
+
+/// [`build_for_query`] without the spinner and Ctrl-C check, for building
+/// several apps' trees at once.
+fn query_tree(
+    backend: &dyn AxBackend,
+    pid: i32,
+    extras: bool,
+    query: Option<&str>,
+    resolve: &ResolveOptions,
+    tree_opts: &TreeOptions,
+) -> Result<Vec<MenuNode>, MenuError> {
+    if extras {
+        let _t_tree = debug_span!("build_extras_tree").entered();
+        backend.build_extras_tree(pid, None, tree_opts)
+    } else if let Some(path) = query.filter(|q| is_exact_path(q)) {
+        let _t_tree = debug_span!("build_branch").entered();
+        backend.build_branch(pid, path, resolve, tree_opts)
+    } else {
+        let _t_tree = debug_span!("build_tree").entered();
+        backend.build_tree(pid, None, tree_opts)
+    }
+}
+
+/// After a walk, fail with `MenuError::Cancelled` if Ctrl-C cut it short,
+/// unless `partial` asks for what was read so far.
+fn check_interrupted(ctx: &OutputCtx, partial: bool) -> Result<(), MenuError> {
+    if partial {
+        Ok(())
+    } else {
+        ctx.cancel.check()
+    }
+}
+
+/// With `--fail-if-empty`, turn an empty result into `MenuError::NoResults`.
+fn ensure_nonempty(fail_if_empty: bool, empty: bool) -> Result<(), MenuError> {
+    if fail_if_empty && empty {
+        Err(MenuError::NoResults)
+    } else {
+        Ok(())
+    }
+}
+
+/// Narrow `tree` to the `--within` subtree, if given, resolved with `opts`.
+fn scope<'a>(
+    tree: &'a [MenuNode],
+    within: Option<&str>,
+    opts: &ResolveOptions,
+) -> Result<&'a [MenuNode], MenuError> {
+    within.map_or(Ok(tree), |w| subtree(tree, w, opts))
+}
+
+/// [`resolve_matches`] for each of `paths` in turn, or for `--id` alone when
+/// there are none, concatenating the matches in order.
+fn resolve_paths<'a>(
+    tree: &'a [MenuNode],
+    paths: &[impl AsRef<str>],
+    id: Option<&str>,
+    opts: &ResolveOptions,
+    first: bool,
+    all: bool,
+) -> Result<Vec<&'a MenuNode>, MenuError> {
+    if paths.is_empty() {
+        return resolve_matches(tree, None, id, opts, first, all);
+    }
+    let mut nodes = Vec::with_capacity(paths.len());
+    for path in paths {
+        nodes.extend(resolve_matches(
+            tree,
+            Some(path.as_ref()),
+            id,
+            opts,
+            first,
+            all,
+        )?);
+    }
+    Ok(nodes)
+}
+
+/// Resolve `--id` or `path` to the items a command acts on. Wildcard paths
+/// matching several items are an ambiguity error unless `--first` or `--all`
+/// is given; `opts` tunes resolution otherwise.
+fn resolve_matches<'a>(
+    tree: &'a [MenuNode],
+    path: Option<&str>,
+    id: Option<&str>,
+    opts: &ResolveOptions,
+    first: bool,
+    all: bool,
+) -> Result<Vec<&'a MenuNode>, MenuError> {
+    if let Some(id) = id {
+        return resolve_id(tree, id).map(|node| vec![node]);
+    }
+    // clap requires one of `path` / `--id`.
+    let path = path.unwrap_or_default();
+    if all {
+        resolve_all(tree, path, opts)
+    } else if first {
+        resolve_all(tree, path, opts).map(|mut nodes| {
+            nodes.truncate(1);
+            nodes
+        })
+    } else {
+        resolve_with(tree, path, opts).map(|node| vec![node])
     }
 }
@@ -0,0 +1,76 @@
+/// `preview` command: an item's details as a compact panel, for fzf.
+use std::time::Duration;
+
+use tracing::debug_span;
+
+use super::record_target;
+use crate::backend::cache::default_dir;
+use crate::backend::{AxBackend, CacheBackend};
+use crate::cli::args::PreviewArgs;
+use crate::cli::output::write_preview;
+use crate::cli::OutputCtx;
+use crate::menu::tree::TreeOptions;
+use crate::menu::{resolve, MenuError, MenuNode};
+use crate::types::MenuItemOutput;
+
+/// Run `menucli preview`.
+///
+/// fzf runs the preview command once per highlighted line, so the tree comes
+/// from the disk cache whenever a walk within `--cache-ttl` left one there;
+/// only the first preview of a session pays for walking the menus.
+///
+/// # Errors
+///
+/// Returns `MenuError` on AX failure, missing permissions, unknown app, or
+/// unresolvable path.
+pub fn run(args: &PreviewArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let opts = TreeOptions {
+        include_alternates: ctx.alternates,
+        locale_aliases: false,
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: None,
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: false,
+    };
+
+    let pid = backend
+        .resolve_target(args.app.as_deref())
+        .map_err(MenuError::from)?;
+    record_target(backend, pid, ctx);
+
+    let _t_tree = debug_span!("build_tree").entered();
+    let cached = default_dir()
+        .filter(|_| !args.no_cache)
+        .map(|dir| CacheBackend::new(backend, dir, Duration::from_secs(args.cache_ttl)));
+    let source: &dyn AxBackend = match &cached {
+        Some(cache) => cache,
+        None => backend,
+    };
+    let tree = if args.extras {
+        source.build_extras_tree(pid, None, &opts)?
+    } else {
+        source.build_tree(pid, None, &opts)?
+    };
+    drop(_t_tree);
+
+    let _t_resolve_path = debug_span!("resolve_path").entered();
+    let node = resolve(&tree, &args.path)?;
+    let siblings = level_of(&tree, node).unwrap_or(std::slice::from_ref(node));
+    drop(_t_resolve_path);
+
+    let siblings: Vec<MenuItemOutput> = siblings.iter().map(MenuItemOutput::from_node).collect();
+    write_preview(&MenuItemOutput::from_node(node), &siblings);
+    Ok(())
+}
+
+/// The level of `nodes` that holds `node` itself (not just an item with the
+/// same path): its parent's children, or the top level.
+fn level_of<'a>(nodes: &'a [MenuNode], node: &MenuNode) -> Option<&'a [MenuNode]> {
+    if nodes.iter().any(|n| std::ptr::eq(n, node)) {
+        return Some(nodes);
+    }
+    nodes.iter().find_map(|n| level_of(&n.children, node))
+}
@@ -0,0 +1,47 @@
+/// `resolve` command: show which menu item a query would target.
+use tracing::debug_span;
+
+use super::lookup;
+use crate::backend::AxBackend;
+use crate::cli::args::ResolveQueryArgs;
+use crate::cli::output::write_resolutions;
+use crate::cli::OutputCtx;
+use crate::menu::{resolve_candidates, resolve_explained, MenuError};
+use crate::types::ResolutionOutput;
+
+/// Run `menucli resolve`.
+///
+/// Resolves the path exactly as `click`, `toggle`, and `state` would, and
+/// reports the item with the strategy that found it and its fuzzy score.
+/// With `--all`, every candidate is reported instead of an ambiguity error.
+///
+/// # Errors
+///
+/// Returns `MenuError` on AX failure, missing permissions, unknown app, or
+/// an unresolvable (or, without `--all`, ambiguous) path.
+pub fn run(
+    args: &ResolveQueryArgs,
+    ctx: &OutputCtx,
+    backend: &dyn AxBackend,
+) -> Result<(), MenuError> {
+    let lookup = lookup(backend, &args.lookup, Some(&args.path), false, ctx)?;
+
+    let _t_resolve_path = debug_span!("resolve_path").entered();
+    let scoped = lookup.nodes()?;
+    let resolutions = if args.all {
+        resolve_candidates(scoped, &args.path, &lookup.resolve)?
+    } else {
+        vec![resolve_explained(scoped, &args.path, &lookup.resolve)?]
+    };
+    drop(_t_resolve_path);
+    if ctx.quiet {
+        return Ok(());
+    }
+
+    let output: Vec<_> = resolutions
+        .iter()
+        .map(|r| ResolutionOutput::from_resolution(&args.path, r))
+        .collect();
+    write_resolutions(&output, ctx);
+    Ok(())
+}
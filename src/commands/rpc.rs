@@ -0,0 +1,510 @@
+/// `rpc` command: JSON-RPC 2.0 over stdin/stdout.
+///
+/// Reads one request (or batch array) per line from stdin and writes one response
+/// per line to stdout. Method names mirror the subcommands (`list`, `search`,
+/// `click`, `toggle`, `state`, `apps`, `check-access`) and params mirror their
+/// flags in `snake_case` (e.g. `{"path": "File::Save", "app": "TextEdit"}`).
+///
+/// Menu trees are cached per `(pid, extras, depth)` for the lifetime of the session.
+/// Where the backend can watch an app's menus, its cached trees are dropped as
+/// soon as the app reports a menu change or quits. `click` and `toggle` drop the
+/// cached trees of the app they acted on; the `refresh` method drops cached trees
+/// explicitly (all, or one app's via `app`).
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, debug_span};
+
+use super::apps;
+use super::toggle::{needs_press, press_toward};
+use crate::backend::{AxBackend, MenuWatch};
+use crate::cli::args::{AppSort, AppsArgs, ExpectState};
+use crate::cli::output::timestamp_now;
+use crate::cli::{version_value, OutputCtx};
+use crate::menu::tree::TreeOptions;
+use crate::menu::{flatten, resolve, search, MenuError, MenuNode, SearchFilters, SearchOptions};
+use crate::types::{ErrorOutput, MenuItemOutput, MenuTreeOutput, SearchResultOutput, ToggleOutput};
+
+/// Invalid JSON was received.
+const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object.
+const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters.
+const INVALID_PARAMS: i64 = -32602;
+/// A `MenuError` raised by the method; details are in `error.data`.
+const SERVER_ERROR: i64 = -32000;
+
+/// Cache key for a built tree: `(pid, extras, max_depth)`.
+type TreeKey = (i32, bool, Option<usize>);
+
+/// An incoming JSON-RPC request. A missing `id` makes it a notification.
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// An outgoing JSON-RPC response.
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<MenuError> for RpcError {
+    fn from(err: MenuError) -> Self {
+        let detail = ErrorOutput::from_menu_error(&err).error;
+        Self {
+            code: SERVER_ERROR,
+            message: err.to_string(),
+            data: serde_json::to_value(detail).ok(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ListParams {
+    app: Option<String>,
+    tree: bool,
+    enabled_only: bool,
+    depth: Option<usize>,
+    extras: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    query: String,
+    #[serde(default)]
+    app: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    exact: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    min_score: u32,
+    #[serde(default)]
+    extras: bool,
+}
+
+fn default_limit() -> usize {
+    SearchOptions::default().limit
+}
+
+/// Params shared by `click`, `toggle`, and `state`.
+#[derive(Debug, Deserialize)]
+struct ItemParams {
+    path: String,
+    #[serde(default)]
+    app: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    extras: bool,
+    /// `toggle` only.
+    #[serde(default)]
+    expect: Option<ExpectState>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+// Each bool mirrors an `apps` flag.
+#[allow(clippy::struct_excessive_bools)]
+struct AppsParams {
+    all: bool,
+    frontmost: bool,
+    filter: Option<String>,
+    regex: bool,
+    with_menu: bool,
+    with_extras: bool,
+    sort: AppSort,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RefreshParams {
+    app: Option<String>,
+}
+
+/// Per-session state: output context, backend, and the tree cache.
+struct Session<'a> {
+    ctx: &'a OutputCtx,
+    backend: &'a dyn AxBackend,
+    tree_opts: TreeOptions,
+    trees: HashMap<TreeKey, Vec<MenuNode>>,
+    /// Menu watches of apps with cached trees (`None` where unsupported).
+    watches: HashMap<i32, Option<Box<dyn MenuWatch>>>,
+}
+
+impl<'a> Session<'a> {
+    fn new(ctx: &'a OutputCtx, backend: &'a dyn AxBackend) -> Self {
+        Self {
+            ctx,
+            backend,
+            tree_opts: TreeOptions {
+                include_alternates: ctx.alternates,
+                locale_aliases: false,
+                menus: Vec::new(),
+                exclude_menus: ctx.exclude_menus.clone(),
+                ax_timeout: ctx.ax_timeout,
+                progress: None,
+                cancel: ctx.cancel.clone(),
+                extras_jobs: None,
+                open_menus: false,
+            },
+            trees: HashMap::new(),
+            watches: HashMap::new(),
+        }
+    }
+
+    /// Return the cached tree for `key`, building it on first use.
+    fn tree(&mut self, key: TreeKey) -> Result<&[MenuNode], MenuError> {
+        let (pid, extras, depth) = key;
+        // Watch before walking, so changes made during the walk are not missed.
+        let backend = self.backend;
+        self.watches
+            .entry(pid)
+            .or_insert_with(|| backend.watch_menus(pid));
+        let tree = match self.trees.entry(key) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let _t_tree = debug_span!("build_tree").entered();
+                let built = if extras {
+                    self.backend
+                        .build_extras_tree(pid, depth, &self.tree_opts)?
+                } else {
+                    self.backend.build_tree(pid, depth, &self.tree_opts)?
+                };
+                e.insert(built)
+            }
+        };
+        Ok(tree)
+    }
+
+    /// Drop all cached trees belonging to `pid`.
+    fn invalidate(&mut self, pid: i32) {
+        self.trees.retain(|&(p, _, _), _| p != pid);
+        self.watches.remove(&pid);
+    }
+
+    /// Drop the cached trees of every app whose watch reports a change.
+    fn drop_stale(&mut self) {
+        let stale: Vec<i32> = self
+            .watches
+            .iter_mut()
+            .filter_map(|(&pid, watch)| watch.as_mut()?.changed().then_some(pid))
+            .collect();
+        for pid in stale {
+            debug!(pid, "menus changed; dropping cached trees");
+            self.invalidate(pid);
+        }
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        self.drop_stale();
+        match method {
+            "list" => self.list(&parse_params(params)?),
+            "search" => self.search(&parse_params(params)?),
+            "click" => self.click(&parse_params(params)?),
+            "toggle" => self.toggle(&parse_params(params)?),
+            "state" => self.state(&parse_params(params)?),
+            "apps" => self.apps(&parse_params(params)?),
+            "check-access" => self.check_access(),
+            "refresh" => self.refresh(&parse_params(params)?),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {method}"),
+            )),
+        }
+    }
+
+    fn list(&mut self, p: &ListParams) -> Result<Value, RpcError> {
+        if p.extras && p.app.is_none() {
+            let results = self.backend.build_all_extras(p.depth, &self.tree_opts);
+            let mut items = Vec::new();
+            for result in &results {
+                for f in flatten(&result.nodes) {
+                    items.push(MenuItemOutput::from_flat(f, Some(result)));
+                }
+            }
+            if p.enabled_only {
+                items.retain(|i| i.enabled);
+            }
+            return Ok(to_value(&items));
+        }
+
+        let pid = self
+            .backend
+            .resolve_target(p.app.as_deref())
+            .map_err(MenuError::from)?;
+        let tree = self.tree((pid, p.extras, p.depth))?;
+
+        if p.tree {
+            let nodes: Vec<_> = tree.iter().map(MenuTreeOutput::from_node).collect();
+            return Ok(to_value(&nodes));
+        }
+
+        let mut items: Vec<_> = flatten(tree)
+            .into_iter()
+            .map(|f| MenuItemOutput::from_flat(f, None))
+            .collect();
+        if p.enabled_only {
+            items.retain(|i| i.enabled);
+        }
+        Ok(to_value(&items))
+    }
+
+    fn search(&mut self, p: &SearchParams) -> Result<Value, RpcError> {
+        let flat = if p.extras && p.app.is_none() {
+            self.backend
+                .build_all_extras(None, &self.tree_opts)
+                .iter()
+                .flat_map(|r| flatten(&r.nodes))
+                .collect()
+        } else {
+            let pid = self
+                .backend
+                .resolve_target(p.app.as_deref())
+                .map_err(MenuError::from)?;
+            flatten(self.tree((pid, p.extras, None))?)
+        };
+
+        let opts = SearchOptions {
+            limit: p.limit,
+            exact: p.exact,
+            case_sensitive: p.case_sensitive,
+            include_help: false,
+            min_score: p.min_score,
+            filters: SearchFilters::default(),
+            collapse_alternates: false,
+        };
+
+        let output: Vec<SearchResultOutput> = search(&flat, &p.query, &opts)
+            .iter()
+            .map(SearchResultOutput::from_result)
+            .collect();
+        Ok(to_value(&output))
+    }
+
+    fn click(&mut self, p: &ItemParams) -> Result<Value, RpcError> {
+        let backend = self.backend;
+        let pid = backend
+            .resolve_target(p.app.as_deref())
+            .map_err(MenuError::from)?;
+        let node = resolve(self.tree((pid, p.extras, None))?, &p.path)?;
+        let output = MenuItemOutput::from_node(node);
+
+        if !p.dry_run {
+            backend.press(pid, node)?;
+            self.invalidate(pid);
+        }
+        Ok(to_value(&output))
+    }
+
+    fn toggle(&mut self, p: &ItemParams) -> Result<Value, RpcError> {
+        let backend = self.backend;
+        let pid = backend
+            .resolve_target(p.app.as_deref())
+            .map_err(MenuError::from)?;
+        // Owned, since pressing invalidates the cached tree it came from.
+        let node = resolve(self.tree((pid, p.extras, None))?, &p.path)?.clone();
+        let expect = p.expect.map(ExpectState::checked);
+        let press = needs_press(&node, expect)?;
+        let checked_before = node.checked;
+        let path = node.path.clone();
+
+        let checked_after = if p.dry_run || !press {
+            checked_before
+        } else {
+            self.invalidate(pid);
+            press_toward(backend, pid, p.extras, &node, &self.tree_opts, expect)?
+        };
+
+        Ok(to_value(&ToggleOutput {
+            path,
+            checked_before,
+            checked_after,
+            dry_run: p.dry_run,
+            performed_at: timestamp_now(),
+        }))
+    }
+
+    fn state(&mut self, p: &ItemParams) -> Result<Value, RpcError> {
+        let pid = self
+            .backend
+            .resolve_target(p.app.as_deref())
+            .map_err(MenuError::from)?;
+        let node = resolve(self.tree((pid, p.extras, None))?, &p.path)?;
+        Ok(to_value(&MenuItemOutput::from_node(node)))
+    }
+
+    fn refresh(&mut self, p: &RefreshParams) -> Result<Value, RpcError> {
+        if let Some(app) = p.app.as_deref() {
+            let pid = self
+                .backend
+                .resolve_target(Some(app))
+                .map_err(MenuError::from)?;
+            self.invalidate(pid);
+        } else {
+            self.trees.clear();
+            self.watches.clear();
+        }
+        Ok(json!({ "ok": true }))
+    }
+
+    fn apps(&self, p: &AppsParams) -> Result<Value, RpcError> {
+        let args = AppsArgs {
+            all: p.all,
+            frontmost: p.frontmost,
+            filter: p.filter.clone(),
+            regex: p.regex,
+            with_menu: p.with_menu,
+            with_extras: p.with_extras,
+            sort: p.sort,
+        };
+        let output = apps::app_infos(self.backend, &args, &self.tree_opts)?;
+        Ok(to_value(&output))
+    }
+
+    fn check_access(&self) -> Result<Value, RpcError> {
+        self.backend
+            .ensure_trusted()
+            .map_err(|_| MenuError::AccessDenied)?;
+        Ok(json!({ "ok": true, "message": "Accessibility permission granted" }))
+    }
+}
+
+/// Deserialize method params, treating absent params as an empty object.
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value<T: Serialize + ?Sized>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Handle a single request value. Returns `None` for notifications.
+fn handle(session: &mut Session<'_>, raw: Value) -> Option<RpcResponse> {
+    let req: RpcRequest = match serde_json::from_value(raw) {
+        Ok(req) => req,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, e.to_string()),
+            ))
+        }
+    };
+
+    let id = req.id?;
+    if req.jsonrpc != "2.0" {
+        return Some(error_response(
+            id,
+            RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+        ));
+    }
+
+    Some(match session.call(&req.method, req.params) {
+        Ok(mut result) => {
+            version_value(&mut result, session.ctx.output_version);
+            RpcResponse {
+                jsonrpc: "2.0",
+                id,
+                result: Some(result),
+                error: None,
+            }
+        }
+        Err(err) => error_response(id, err),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> RpcResponse {
+    RpcResponse {
+        jsonrpc: "2.0",
+        id,
+        result: None,
+        error: Some(error),
+    }
+}
+
+/// Run `menucli rpc`.
+///
+/// Serves requests until stdin reaches EOF.
+///
+/// # Errors
+///
+/// Never fails at the session level: per-request failures are reported as
+/// JSON-RPC error responses.
+pub fn run(ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let mut session = Session::new(ctx, backend);
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Err(e) => Some(to_value(&error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, e.to_string()),
+            ))),
+            Ok(Value::Array(batch)) if batch.is_empty() => Some(to_value(&error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "empty batch"),
+            ))),
+            Ok(Value::Array(batch)) => {
+                let responses: Vec<RpcResponse> = batch
+                    .into_iter()
+                    .filter_map(|raw| handle(&mut session, raw))
+                    .collect();
+                (!responses.is_empty()).then(|| to_value(&responses))
+            }
+            Ok(raw) => handle(&mut session, raw).map(|r| to_value(&r)),
+        };
+
+        if let Some(reply) = reply {
+            let mut out = stdout.lock();
+            let _ = writeln!(out, "{reply}");
+            let _ = out.flush();
+        }
+    }
+
+    Ok(())
+}
@@ -1,10 +1,19 @@
 /// `search` command: fuzzy-search menu items.
-use crate::ax::resolve_target;
+use std::cmp::Reverse;
+
+use tracing::debug_span;
+
+use super::{
+    activate, attribute, attribution, build_app_trees, check_interrupted, ensure_nonempty,
+    resolve_app, scope, single_app_focus,
+};
+use crate::backend::AxBackend;
 use crate::cli::args::SearchArgs;
-use crate::cli::output::write_search_results;
+use crate::cli::output::{timestamp_now, write_search_results};
+use crate::cli::progress::Spinner;
 use crate::cli::OutputCtx;
-use crate::menu::tree::{build_all_extras, build_extras_tree, TreeOptions};
-use crate::menu::{build_tree_with_opts, flatten, search, MenuError, SearchOptions};
+use crate::menu::tree::TreeOptions;
+use crate::menu::{flatten, search, MenuError, ResolveOptions, SearchOptions};
 use crate::types::SearchResultOutput;
 
 /// Run `menucli search`.
@@ -12,71 +21,121 @@ use crate::types::SearchResultOutput;
 /// # Errors
 ///
 /// Returns `MenuError` on AX failure, missing permissions, or unknown app.
-pub fn run(args: &SearchArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
+pub fn run(args: &SearchArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
     let tree_opts = TreeOptions {
-        include_alternates: ctx.alternates,
+        include_alternates: ctx.alternates || args.collapse_alternates,
+        locale_aliases: false,
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: ctx.walk_progress(),
+        cancel: ctx.cancel.clone(),
+        extras_jobs: args.jobs,
+        open_menus: args.walk.open_menus,
     };
 
-    let flat = if args.extras {
-        if let Some(app) = &args.app {
-            let _t_resolve = ctx.timer("resolve_target");
-            let pid = resolve_target(Some(app.as_str())).map_err(MenuError::from)?;
-            drop(_t_resolve);
+    let opts = SearchOptions {
+        limit: if args.no_limit { 0 } else { args.limit },
+        exact: args.exact,
+        case_sensitive: args.case_sensitive,
+        include_help: args.include_help,
+        min_score: args.min_score,
+        filters: args.filters.filters(),
+        collapse_alternates: args.collapse_alternates,
+    };
+    // `--within` names a menu the way a path names an item.
+    let within_opts = ResolveOptions {
+        exact: args.exact,
+        case_sensitive: args.case_sensitive,
+        ..ResolveOptions::default()
+    };
 
-            let _t_tree = ctx.timer("build_extras_tree");
-            let tree = build_extras_tree(pid, None, &tree_opts)?;
+    // Each app's tree, and whether it was the one app asked for.
+    let (results, one_app, _focus) = if let [_, _, ..] = args.app.as_slice() {
+        let spinner = Spinner::start(tree_opts.progress.as_ref());
+        let results = build_app_trees(backend, &args.app, false, &args.focus, |pid| {
+            if args.extras {
+                backend.build_extras_tree(pid, None, &tree_opts)
+            } else {
+                backend.build_tree(pid, None, &tree_opts)
+            }
+        })?;
+        drop(spinner);
+        check_interrupted(ctx, args.partial)?;
+        (results, false, None)
+    } else if args.extras {
+        if let Some(app) = args.app.first() {
+            let pid = resolve_app(backend, Some(app.as_str()), false, &args.focus, ctx)?;
+            let focus = activate(backend, pid, &args.focus)?;
+
+            let _t_tree = debug_span!("build_extras_tree").entered();
+            let spinner = Spinner::start(tree_opts.progress.as_ref());
+            let tree = backend.build_extras_tree(pid, None, &tree_opts)?;
+            drop(spinner);
             drop(_t_tree);
+            check_interrupted(ctx, args.partial)?;
 
-            flatten(&tree)
+            (
+                vec![attribute(&backend.list_all_apps(), pid, tree)],
+                true,
+                focus,
+            )
         } else {
-            let _t_tree = ctx.timer("build_all_extras");
-            let results = build_all_extras(None, &tree_opts);
+            single_app_focus(&args.focus)?;
+            let _t_tree = debug_span!("build_all_extras").entered();
+            let spinner = Spinner::start(tree_opts.progress.as_ref());
+            let results = backend.build_all_extras(None, &tree_opts);
+            drop(spinner);
             drop(_t_tree);
+            check_interrupted(ctx, args.partial)?;
 
-            let mut all = Vec::new();
-            for result in &results {
-                all.extend(flatten(&result.nodes));
-            }
-            all
+            (results, false, None)
         }
     } else {
-        let _t_resolve = ctx.timer("resolve_target");
-        let pid = resolve_target(args.app.as_deref()).map_err(MenuError::from)?;
-        drop(_t_resolve);
+        let app = args.app.first().map(String::as_str);
+        let pid = resolve_app(backend, app, false, &args.focus, ctx)?;
+        let focus = activate(backend, pid, &args.focus)?;
 
-        let _t_tree = ctx.timer("build_tree");
-        let tree = build_tree_with_opts(pid, None, &tree_opts)?;
+        let _t_tree = debug_span!("build_tree").entered();
+        let spinner = Spinner::start(tree_opts.progress.as_ref());
+        let tree = backend.build_tree(pid, None, &tree_opts)?;
+        drop(spinner);
         drop(_t_tree);
+        check_interrupted(ctx, args.partial)?;
 
-        let _t_flatten = ctx.timer("flatten");
-        let f = flatten(&tree);
-        drop(_t_flatten);
-        f
+        (
+            vec![attribute(&backend.list_all_apps(), pid, tree)],
+            true,
+            focus,
+        )
     };
 
-    let opts = SearchOptions {
-        limit: args.limit,
-        exact: args.exact,
-        case_sensitive: args.case_sensitive,
-    };
-
-    let _t_search = ctx.timer("search");
-    let results = search(&flat, &args.query, &opts);
+    let _t_search = debug_span!("search").entered();
+    let now = timestamp_now();
+    let mut output: Vec<SearchResultOutput> = Vec::new();
+    for result in &results {
+        let nodes = match scope(&result.nodes, args.within.as_deref(), &within_opts) {
+            Ok(nodes) => nodes,
+            Err(e) if one_app => return Err(e),
+            // Apps without the `--within` menu simply contribute nothing.
+            Err(_) => continue,
+        };
+        let app = attribution(result, args.from_file.as_deref());
+        output.extend(search(&flatten(nodes), &args.query, &opts).iter().map(|r| {
+            SearchResultOutput {
+                app_name: app.map(|a| a.app_name.clone()),
+                app_pid: app.map(|a| a.app_pid),
+                bundle_id: app.and_then(|a| a.bundle_id.clone()),
+                generated_at: Some(now.clone()),
+                ..SearchResultOutput::from_result(r)
+            }
+        }));
+    }
+    // Stable, so equal scores keep the order the apps were given in.
+    output.sort_by_key(|r| Reverse(r.score));
+    output.truncate(opts.max_results());
     drop(_t_search);
-
-    let output: Vec<SearchResultOutput> = results
-        .iter()
-        .map(|r| SearchResultOutput {
-            title: r.item.title.clone(),
-            path: r.item.path.clone(),
-            enabled: r.item.enabled,
-            checked: r.item.checked,
-            shortcut: r.item.shortcut.clone(),
-            score: r.score,
-            is_alternate: r.item.is_alternate,
-            alternate_of: r.item.alternate_of.clone(),
-        })
-        .collect();
+    ensure_nonempty(args.fail_if_empty, output.is_empty())?;
 
     write_search_results(&output, ctx);
     Ok(())
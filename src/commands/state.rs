@@ -1,57 +1,143 @@
-/// `state` command: get the current state of a specific menu item.
-use crate::ax::resolve_target;
-use crate::cli::args::StateArgs;
-use crate::cli::output::write_menu_items;
+/// `state` command: get the current state of one or more menu items.
+use tracing::debug_span;
+
+use super::{
+    activate, attribute, attribution, build_app_trees, build_for_query, query_tree, resolve_app,
+    resolve_paths, scope,
+};
+use crate::backend::AxBackend;
+use crate::cli::args::{StateArgs, StateAssertion};
+use crate::cli::output::{timestamp_now, write_menu_items};
+use crate::cli::progress::Spinner;
 use crate::cli::OutputCtx;
-use crate::menu::tree::{build_extras_tree, TreeOptions};
-use crate::menu::{build_tree_with_opts, resolve, MenuError};
+use crate::menu::tree::{names_dynamic_submenu, TreeOptions};
+use crate::menu::{CheckState, MenuError, MenuNode};
 use crate::types::MenuItemOutput;
 
 /// Run `menucli state`.
 ///
+/// All paths are resolved against one tree build and their matches reported,
+/// in order, as one array. With several `--app`s, their trees are built at
+/// once and every path is resolved in each, matches naming their app; like
+/// `search`, an app lacking one of the paths contributes nothing, and only if
+/// every app does is it an error.
+///
 /// # Errors
 ///
 /// Returns `MenuError` on AX failure, missing permissions, unknown app, or unresolvable path.
-pub fn run(args: &StateArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
+/// Returns `MenuError::AssertionFailed` if a matched item fails an `--assert`.
+pub fn run(args: &StateArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
+    let paths = args.all_paths();
     let tree_opts = TreeOptions {
         include_alternates: ctx.alternates,
+        locale_aliases: args.resolve.lang.is_some(),
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: ctx.walk_progress(),
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: args.walk.open_menus || paths.iter().any(|p| names_dynamic_submenu(p)),
     };
 
-    let _t_resolve = ctx.timer("resolve_target");
-    let pid = resolve_target(args.app.as_deref()).map_err(MenuError::from)?;
-    drop(_t_resolve);
+    let resolve_opts = args.resolve.options();
+    // Only a single path can be answered from one branch.
+    let query = match paths.as_slice() {
+        [path] if args.within.is_none() => Some(*path),
+        _ => None,
+    };
 
-    let tree = if args.extras {
-        let _t_tree = ctx.timer("build_extras_tree");
-        let t = build_extras_tree(pid, None, &tree_opts)?;
-        drop(_t_tree);
-        t
+    // Each tree with the app it is attributed to.
+    let (trees, _focus) = if let [_, _, ..] = args.app.as_slice() {
+        let spinner = Spinner::start(tree_opts.progress.as_ref());
+        let results = build_app_trees(backend, &args.app, args.first, &args.focus, |pid| {
+            query_tree(backend, pid, args.extras, query, &resolve_opts, &tree_opts)
+        })?;
+        drop(spinner);
+        ctx.cancel.check()?;
+        (results, None)
     } else {
-        let _t_tree = ctx.timer("build_tree");
-        let t = build_tree_with_opts(pid, None, &tree_opts)?;
-        drop(_t_tree);
-        t
+        let app = args.app.first().map(String::as_str);
+        let pid = resolve_app(backend, app, args.first, &args.focus, ctx)?;
+        let focus = activate(backend, pid, &args.focus)?;
+        let tree = build_for_query(
+            backend,
+            pid,
+            args.extras,
+            query,
+            &resolve_opts,
+            &tree_opts,
+            ctx,
+        )?;
+        (vec![attribute(&backend.list_all_apps(), pid, tree)], focus)
     };
 
-    let _t_resolve_path = ctx.timer("resolve_path");
-    let node = resolve(&tree, &args.path)?;
+    let _t_resolve_path = debug_span!("resolve_path").entered();
+    let mut nodes = Vec::new();
+    let mut missing = None;
+    for app in &trees {
+        let matches = scope(&app.nodes, args.within.as_deref(), &resolve_opts).and_then(|scoped| {
+            resolve_paths(
+                scoped,
+                &paths,
+                args.id.as_deref(),
+                &resolve_opts,
+                args.first,
+                args.all,
+            )
+        });
+        match matches {
+            Ok(matches) => nodes.extend(matches.into_iter().map(|node| (app, node))),
+            Err(err @ MenuError::ItemNotFound { .. }) if trees.len() > 1 => {
+                missing.get_or_insert(err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    if let (true, Some(err)) = (nodes.is_empty(), missing) {
+        return Err(err);
+    }
     drop(_t_resolve_path);
 
-    let output = MenuItemOutput {
-        title: node.title.clone(),
-        path: node.path.clone(),
-        enabled: node.enabled,
-        checked: node.checked,
-        shortcut: node.shortcut.clone(),
-        role: node.role.clone(),
-        children_count: node.children.len(),
-        depth: node.depth,
-        is_alternate: node.is_alternate,
-        alternate_of: node.alternate_of.clone(),
-        app_name: None,
-        app_pid: None,
-    };
+    for (_, node) in &nodes {
+        check_assertions(node, &args.assert)?;
+    }
+    if ctx.quiet {
+        return Ok(());
+    }
 
-    write_menu_items(&[output], ctx);
+    let now = timestamp_now();
+    let output: Vec<_> = nodes
+        .iter()
+        .map(|(app, n)| {
+            let app = attribution(app, args.from_file.as_deref());
+            MenuItemOutput {
+                app_name: app.map(|a| a.app_name.clone()),
+                app_pid: app.map(|a| a.app_pid),
+                bundle_id: app.and_then(|a| a.bundle_id.clone()),
+                performed_at: Some(now.clone()),
+                ..MenuItemOutput::from_node(n)
+            }
+        })
+        .collect();
+
+    write_menu_items(&output, ctx);
     Ok(())
 }
+
+/// Check `node` against each `--assert` condition. `exists` always holds
+/// here, since a missing item already failed to resolve.
+fn check_assertions(node: &MenuNode, assertions: &[StateAssertion]) -> Result<(), MenuError> {
+    let failed = assertions.iter().find(|a| match a {
+        StateAssertion::Checked => node.check_state != CheckState::On,
+        StateAssertion::Enabled => !node.enabled,
+        StateAssertion::Exists => false,
+    });
+    match failed {
+        Some(assertion) => Err(MenuError::AssertionFailed {
+            path: node.path.clone(),
+            condition: assertion.name().to_owned(),
+        }),
+        None => Ok(()),
+    }
+}
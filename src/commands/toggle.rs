@@ -1,109 +1,237 @@
 /// `toggle` command: toggle a checkmark menu item and report the new state.
-use crate::ax::resolve_target;
-use crate::cli::args::ToggleArgs;
-use crate::cli::output::write_toggle;
+use std::time::Duration;
+
+use tracing::{debug, debug_span};
+
+use super::{activate, build_for_query, resolve_app, scope};
+use crate::backend::AxBackend;
+use crate::cli::args::{ExpectState, ToggleArgs};
+use crate::cli::hooks::Hook;
+use crate::cli::output::{timestamp_now, write_toggle};
 use crate::cli::OutputCtx;
-use crate::menu::tree::{build_extras_tree, TreeOptions};
-use crate::menu::{build_tree_with_opts, press_node, resolve, MenuError};
+use crate::menu::tree::{names_dynamic_submenu, TreeOptions};
+use crate::menu::{resolve_with, CheckState, MenuError, MenuNode, ResolveOptions};
 use crate::types::ToggleOutput;
 
-/// Maximum number of attempts to confirm the toggle took effect.
-const MAX_RETRIES: u32 = 5;
-
-/// Initial delay (ms) between `AXPress` and the first re-read.
-const INITIAL_DELAY_MS: u64 = 50;
+/// How long to wait for the app to update the checkmark after `AXPress`.
+const CONFIRM_TIMEOUT: Duration = Duration::from_millis(1500);
 
 /// Run `menucli toggle`.
 ///
-/// After pressing the item, re-reads the menu tree up to [`MAX_RETRIES`] times
-/// with exponential back-off (`50 -> 100 -> 200 -> 400 -> 800 ms`) waiting for the
-/// app to update its AX checkmark state. If the state flips within that window
-/// we report the observed value; otherwise we infer `!checked_before`.
+/// After pressing the item, waits for its checkmark to change (on the app's AX
+/// notification, or at the next poll) and reports the state read then. If nothing changes within
+/// [`CONFIRM_TIMEOUT`], the state read at the deadline is reported as is, so
+/// `checked_after` may equal `checked_before`. With `--expect`, see
+/// [`press_toward`].
 ///
 /// # Errors
 ///
 /// Returns `MenuError::NotToggleable` if the item has no checkmark state.
+/// Returns `MenuError::MixedState` if the item shows a dash.
+/// Returns `MenuError::RadioSelected` if the item is the chosen radio item.
+/// Returns `MenuError::StateNotReached` if `--expect` was not met.
 /// Returns `MenuError::ItemDisabled` if the item is not clickable.
 /// Returns `MenuError` on AX failure, missing permissions, or unknown app.
-pub fn run(args: &ToggleArgs, ctx: &OutputCtx) -> Result<(), MenuError> {
+pub fn run(args: &ToggleArgs, ctx: &OutputCtx, backend: &dyn AxBackend) -> Result<(), MenuError> {
     let tree_opts = TreeOptions {
         include_alternates: ctx.alternates,
+        locale_aliases: args.resolve.lang.is_some(),
+        menus: Vec::new(),
+        exclude_menus: ctx.exclude_menus.clone(),
+        ax_timeout: ctx.ax_timeout,
+        progress: ctx.walk_progress(),
+        cancel: ctx.cancel.clone(),
+        extras_jobs: None,
+        open_menus: args.walk.open_menus || names_dynamic_submenu(&args.path),
     };
 
-    let _t_resolve = ctx.timer("resolve_target");
-    let pid = resolve_target(args.app.as_deref()).map_err(MenuError::from)?;
-    drop(_t_resolve);
+    let pid = resolve_app(backend, args.app.as_deref(), false, &args.focus, ctx)?;
+    let _focus = activate(backend, pid, &args.focus)?;
 
-    let tree = if args.extras {
-        let _t_tree = ctx.timer("build_extras_tree[1]");
-        let t = build_extras_tree(pid, None, &tree_opts)?;
-        drop(_t_tree);
-        t
-    } else {
-        let _t_tree = ctx.timer("build_tree[1]");
-        let t = build_tree_with_opts(pid, None, &tree_opts)?;
-        drop(_t_tree);
-        t
-    };
+    let resolve_opts = args.resolve.options();
+    let tree = build_for_query(
+        backend,
+        pid,
+        args.extras,
+        Some(args.path.as_str()).filter(|_| args.within.is_none()),
+        &resolve_opts,
+        &tree_opts,
+        ctx,
+    )?;
 
-    let _t_resolve_path = ctx.timer("resolve_path");
-    let node = resolve(&tree, &args.path)?;
+    let _t_resolve_path = debug_span!("resolve_path").entered();
+    let node = resolve_with(
+        scope(&tree, args.within.as_deref(), &resolve_opts)?,
+        &args.path,
+        &resolve_opts,
+    )?;
     drop(_t_resolve_path);
+    let expect = args.expect.map(ExpectState::checked);
+    let press = needs_press(node, expect)?;
 
     let checked_before = node.checked;
     let path = node.path.clone();
 
-    if args.dry_run {
+    if args.dry_run || !press {
         let output = ToggleOutput {
             path,
             checked_before,
             checked_after: checked_before,
-            dry_run: true,
+            dry_run: args.dry_run,
+            performed_at: timestamp_now(),
         };
-        write_toggle(&output, ctx);
+        if !ctx.quiet {
+            write_toggle(&output, ctx);
+        }
         return Ok(());
     }
 
-    let _t_press = ctx.timer("press_node");
-    press_node(node)?;
+    let _t_press = debug_span!("press_and_confirm").entered();
+    let checked_after = press_toward(backend, pid, args.extras, node, &tree_opts, expect)?;
     drop(_t_press);
 
-    // Poll for the AX state to flip, with exponential back-off.
-    let _t_poll = ctx.timer("poll_state");
-    let mut delay_ms = INITIAL_DELAY_MS;
-    let mut checked_after = !checked_before; // optimistic default
-    for attempt in 0..MAX_RETRIES {
-        std::thread::sleep(std::time::Duration::from_millis(delay_ms));
-
-        let tree2_result = if args.extras {
-            build_extras_tree(pid, None, &tree_opts)
-        } else {
-            build_tree_with_opts(pid, None, &tree_opts)
-        };
-
-        if let Ok(tree2) = tree2_result {
-            if let Ok(node2) = resolve(&tree2, &args.path) {
-                if node2.checked != checked_before {
-                    // Confirmed: the state flipped.
-                    checked_after = node2.checked;
-                    break;
-                }
-            }
-        }
-
-        if attempt + 1 < MAX_RETRIES {
-            delay_ms *= 2;
-        }
-    }
-    drop(_t_poll);
-
     let output = ToggleOutput {
         path,
         checked_before,
         checked_after,
         dry_run: false,
+        performed_at: timestamp_now(),
     };
 
-    write_toggle(&output, ctx);
+    if !ctx.quiet {
+        write_toggle(&output, ctx);
+    }
+    ctx.hooks
+        .run_after(Hook::PostToggle, pid, &[output.path.as_str()], &output);
     Ok(())
 }
+
+/// Whether toggling `node` takes a press: not when it already shows `expect`
+/// (a mixed item shows neither state).
+///
+/// # Errors
+///
+/// Returns the errors of [`ensure_two_state`] when a press is needed.
+pub fn needs_press(node: &MenuNode, expect: Option<bool>) -> Result<bool, MenuError> {
+    if expect == Some(node.checked) && node.check_state != CheckState::Mixed {
+        return Ok(false);
+    }
+    ensure_two_state(node)?;
+    Ok(true)
+}
+
+/// Refuse to toggle a mixed (dash) item: pressing one turns it on rather than
+/// flipping it, so `checked_after` would not be the opposite state. The same
+/// goes for the chosen item of a bulleted radio group, which stays chosen; an
+/// unchosen radio item can still be "toggled" on, which selects it. A group
+/// only inferred from a single checkmark may just be one checkbox in its
+/// submenu, so its chosen item is toggled after a warning.
+///
+/// # Errors
+///
+/// Returns `MenuError::MixedState` if `node` is mixed, or
+/// `MenuError::RadioSelected` if it is the chosen item of a bulleted group.
+fn ensure_two_state(node: &MenuNode) -> Result<(), MenuError> {
+    if node.check_state == CheckState::Mixed {
+        return Err(MenuError::MixedState {
+            path: node.path.clone(),
+        });
+    }
+    if let Some(group) = node.radio_group.as_ref().filter(|_| node.checked) {
+        if node.radio_bulleted {
+            return Err(MenuError::RadioSelected {
+                path: node.path.clone(),
+                group: group.clone(),
+            });
+        }
+        eprintln!(
+            "Warning: '{}' looks like the chosen item of '{group}'; if it is, pressing it leaves it chosen",
+            node.path
+        );
+    }
+    Ok(())
+}
+
+/// Press `node` and report its checkmark once the app has updated it.
+///
+/// Waits up to [`CONFIRM_TIMEOUT`] for the change (see
+/// [`AxBackend::press_and_wait`]). If the app rebuilt its menu and the pressed
+/// element is gone, only the branch leading to the item's path is rebuilt to
+/// read the new state.
+///
+/// # Errors
+///
+/// Returns `MenuError` if the press fails, or if the item cannot be found again
+/// after its element was destroyed.
+pub fn press_and_confirm(
+    backend: &dyn AxBackend,
+    pid: i32,
+    extras: bool,
+    node: &MenuNode,
+    tree_opts: &TreeOptions,
+) -> Result<bool, MenuError> {
+    match backend.press_and_wait(pid, node, CONFIRM_TIMEOUT)? {
+        Some(checked) => Ok(checked),
+        None => reresolve(backend, pid, extras, &node.path, tree_opts).map(|n| n.checked),
+    }
+}
+
+/// Press `node` via [`press_and_confirm`]; with `expect`, make sure it ends
+/// there.
+///
+/// The optimistic reading of a press is that it flipped the checkmark, but an
+/// app that is busy or ignores AX presses leaves it unchanged. So a press that
+/// changed nothing is retried once before the state is checked.
+///
+/// # Errors
+///
+/// Returns `MenuError::StateNotReached` if the checkmark still is not
+/// `expect`, or the errors of [`press_and_confirm`].
+pub fn press_toward(
+    backend: &dyn AxBackend,
+    pid: i32,
+    extras: bool,
+    node: &MenuNode,
+    tree_opts: &TreeOptions,
+    expect: Option<bool>,
+) -> Result<bool, MenuError> {
+    let mut checked = press_and_confirm(backend, pid, extras, node, tree_opts)?;
+    let Some(expected) = expect else {
+        return Ok(checked);
+    };
+    if checked == node.checked {
+        debug!(path = %node.path, "checkmark unchanged after press; pressing again");
+        checked = press_and_confirm(backend, pid, extras, node, tree_opts)?;
+    }
+    if checked != expected {
+        return Err(MenuError::StateNotReached {
+            path: node.path.clone(),
+            expected: if expected { "on" } else { "off" }.to_owned(),
+        });
+    }
+    Ok(checked)
+}
+
+/// Find the item at the full `path` again in a freshly built branch.
+///
+/// # Errors
+///
+/// Returns `MenuError` if the branch cannot be built or no longer has the item.
+pub fn reresolve(
+    backend: &dyn AxBackend,
+    pid: i32,
+    extras: bool,
+    path: &str,
+    tree_opts: &TreeOptions,
+) -> Result<MenuNode, MenuError> {
+    let opts = ResolveOptions {
+        exact: true,
+        ..ResolveOptions::default()
+    };
+    let tree = if extras {
+        backend.build_extras_tree(pid, None, tree_opts)
+    } else {
+        backend.build_branch(pid, path, &opts, tree_opts)
+    };
+    resolve_with(&tree?, path, &opts).cloned()
+}
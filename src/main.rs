@@ -2,35 +2,135 @@
 #![allow(clippy::module_name_repetitions)]
 //! menucli — query and interact with macOS app menu bars.
 
-mod ax;
 mod cli;
 mod commands;
-mod menu;
-mod types;
 
-use clap::Parser;
+use std::time::Duration;
 
-use cli::{write_error, Cli, OutputCtx};
+use clap::CommandFactory;
+use clap_complete::CompleteEnv;
+use menucli_core::backend::{AxBackend, RetryBackend, RetryPolicy};
+use menucli_core::{ax, backend, menu, pattern, types};
+
+use ax::AXError;
+use cli::args::Command;
+use cli::config::Config;
+use cli::output::Target;
+use cli::{deadline, interrupt, logging, write_error, Cli, OutputCtx, OutputFormat};
+use menu::{CancelToken, MenuError};
 use types::ErrorOutput;
 
 fn main() {
-    let cli = Cli::parse();
-
-    let ctx = OutputCtx::new(
-        cli.output,
-        cli.json,
-        cli.fields.as_deref(),
-        cli.no_header,
-        cli.debug,
-        cli.alternates,
-    );
+    // A shell asking for completions (`COMPLETE=zsh menucli -- …`) gets its
+    // candidates and nothing else.
+    CompleteEnv::with_factory(Cli::command).complete();
+    let config = Config::load();
+    let cli = config.parse();
+    logging::init(&cli);
+
+    let backend = match backend::from_env() {
+        Ok(backend) => with_retries(backend, &cli),
+        Err(err) => fail(&err, cli.output_format(), cli.json, cli.output_version),
+    };
 
-    match commands::dispatch(&cli.command, &ctx) {
+    // `rpc` blocks on stdin between requests, so Ctrl-C must still end it.
+    let cancel = if matches!(cli.command, Command::Rpc) {
+        CancelToken::default()
+    } else {
+        interrupt::install()
+    };
+    let target = Target::default();
+    if let Some(timeout) = cli.timeout {
+        if !matches!(cli.command, Command::Rpc) {
+            let (output, json, version) = (cli.output_format(), cli.json, cli.output_version);
+            let has_target = cli.command.target().is_some();
+            let target = target.clone();
+            deadline::arm(timeout, cancel.clone(), move || {
+                let err = MenuError::TimedOut;
+                let envelope = envelope(&err, has_target.then(|| target.get()).flatten());
+                write_error(&envelope, output, json, version);
+                std::process::exit(err.exit_code());
+            });
+        }
+    }
+
+    // Looking up the app for its profile is the first AX work, so it runs
+    // under the deadline and the retries like the command itself.
+    let cli = config.apply_profile(cli, backend.as_ref());
+    let ctx = OutputCtx {
+        ax_timeout: cli.ax_timeout,
+        quiet: cli.quiet,
+        exclude_menus: cli.exclude_menus.clone(),
+        hooks: config.hooks.clone(),
+        cancel,
+        target,
+        ..OutputCtx::new(
+            cli.output_format(),
+            cli.json,
+            cli.fields.as_deref(),
+            cli.no_header,
+            cli.debug || cli.debug_json,
+            cli.alternates,
+            cli.output_version,
+        )
+    };
+
+    match commands::dispatch(&cli.command, &ctx, backend.as_ref()) {
         Ok(()) => {}
+        Err(err @ MenuError::AssertionFailed { .. }) if cli.quiet => {
+            std::process::exit(err.exit_code())
+        }
         Err(err) => {
-            let error_output = ErrorOutput::from_menu_error(&err);
-            write_error(&error_output, cli.output, cli.json);
+            // Several apps, or a snapshot, make no single target.
+            let target = cli.command.target().and_then(|_| ctx.target.get());
+            write_error(
+                &envelope(&err, target),
+                cli.output_format(),
+                cli.json,
+                cli.output_version,
+            );
             std::process::exit(err.exit_code());
         }
     }
 }
+
+/// The error envelope for `err`, naming the app the command had resolved,
+/// if any. Errors that mean the app was never reached have no target.
+fn envelope(err: &MenuError, target: Option<(Option<String>, i32)>) -> ErrorOutput {
+    let envelope = ErrorOutput::from_menu_error(err);
+    let unreached = matches!(
+        err,
+        MenuError::AccessDenied
+            | MenuError::AppNotFound { .. }
+            | MenuError::AX(
+                AXError::NotTrusted | AXError::AppNotFound { .. } | AXError::AmbiguousApp { .. }
+            )
+    );
+    match target {
+        Some((name, pid)) if !unreached => envelope.with_target(name, pid),
+        _ => envelope,
+    }
+}
+
+/// Report `err` on stderr and exit with its code.
+fn fail(err: &MenuError, output: OutputFormat, json: bool, output_version: u32) -> ! {
+    write_error(
+        &ErrorOutput::from_menu_error(err),
+        output,
+        json,
+        output_version,
+    );
+    std::process::exit(err.exit_code());
+}
+
+/// Wrap `backend` in a [`RetryBackend`] when `--retries` is set.
+fn with_retries(backend: Box<dyn AxBackend>, cli: &Cli) -> Box<dyn AxBackend> {
+    if cli.retries == 0 {
+        return backend;
+    }
+    let policy = RetryPolicy {
+        retries: cli.retries,
+        delay: Duration::from_millis(cli.retry_delay),
+    };
+    Box::new(RetryBackend::new(backend, policy))
+}
@@ -0,0 +1,1934 @@
+//! End-to-end tests driving the `menucli` binary against a JSON fixture
+//! (`MENUCLI_FIXTURE`), so they run without Accessibility access.
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use serde_json::Value;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/textedit.json");
+
+fn menucli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_menucli"))
+        .args(args)
+        .env("MENUCLI_FIXTURE", FIXTURE)
+        .output()
+        .expect("failed to run menucli")
+}
+
+fn menucli_with_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_menucli"))
+        .args(args)
+        .env("MENUCLI_FIXTURE", FIXTURE)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run menucli");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("failed to run menucli")
+}
+
+fn json(out: &Output) -> Value {
+    serde_json::from_slice(&out.stdout).expect("stdout is not JSON")
+}
+
+#[test]
+fn test_list_flat_paths() {
+    let out = menucli(&["list", "--json"]);
+    assert!(out.status.success());
+    let items = json(&out);
+    let paths: Vec<&str> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["path"].as_str().unwrap())
+        .collect();
+    assert!(paths.contains(&"File::Save As…"));
+    assert!(paths.contains(&"Format::Font::Bold"));
+}
+
+#[test]
+fn test_search_ranks_match_first() {
+    let out = menucli(&["search", "save as", "--json", "--limit", "1"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["path"], "File::Save As…");
+}
+
+#[test]
+fn test_click_dry_run_resolves() {
+    let out = menucli(&["click", "save as", "--dry-run", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["shortcut"], "⇧⌘S");
+}
+
+#[test]
+fn test_click_disabled_fails() {
+    let out = menucli(&["click", "File::Print…", "--json"]);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "item_disabled");
+}
+
+#[test]
+fn test_toggle_flips_state() {
+    let out = menucli(&["toggle", "View::Show Path Bar", "--app", "Finder", "--json"]);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert_eq!(result["checked_before"], false);
+    assert_eq!(result["checked_after"], true);
+}
+
+#[test]
+fn test_state_reads_checkmark() {
+    let out = menucli(&["state", "Allow Hyphenation", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["checked"], true);
+}
+
+#[test]
+fn test_item_not_found_exit_code() {
+    let out = menucli(&["state", "File::Nope", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "item_not_found");
+    assert_eq!(err["error"]["strategy_tried"], "exact_path");
+    assert_eq!(err["error"]["app"], "TextEdit");
+    assert_eq!(err["error"]["pid"], 101);
+    assert!(err["error"]["hint"]
+        .as_str()
+        .unwrap()
+        .contains("--open-menus"));
+}
+
+#[test]
+fn test_fail_if_empty() {
+    let out = menucli(&["search", "qqqzzz", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out), serde_json::json!([]));
+
+    let out = menucli(&["search", "qqqzzz", "--fail-if-empty", "--json"]);
+    assert_eq!(out.status.code(), Some(5));
+    assert!(out.stdout.is_empty());
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "no_results");
+
+    let out = menucli(&[
+        "list",
+        "--app",
+        "Finder",
+        "--role",
+        "AXNope",
+        "--fail-if-empty",
+    ]);
+    assert_eq!(out.status.code(), Some(5));
+    let out = menucli(&["list", "--app", "Finder", "--fail-if-empty", "--json"]);
+    assert!(out.status.success());
+}
+
+#[test]
+fn test_error_context_without_target() {
+    let out = menucli(&["state", "Save", "--app", "Nope", "--json"]);
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert!(err["error"].get("pid").is_none());
+    assert!(err["error"].get("strategy_tried").is_none());
+    assert!(err["error"]["hint"]
+        .as_str()
+        .unwrap()
+        .contains("menucli apps"));
+}
+
+#[test]
+fn test_ambiguous_app_lists_candidates() {
+    // TextEdit is frontmost, but Finder is a different app, not a duplicate.
+    let out = menucli(&["state", "Save", "--app", "e", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "ambiguous_app");
+    assert_eq!(
+        err["error"]["candidates"],
+        serde_json::json!(["Finder (pid 202)", "TextEdit (pid 101)"])
+    );
+}
+
+#[test]
+fn test_stale_pid_is_app_not_found() {
+    let out = menucli(&["state", "View::Show Sidebar", "--app", "202", "--json"]);
+    assert!(out.status.success());
+    for pid in ["99999", "0", "-5"] {
+        // `=` keeps `-5` from parsing as a flag.
+        let out = menucli(&["state", "Save", &format!("--app={pid}"), "--json"]);
+        assert_eq!(out.status.code(), Some(4), "{pid}");
+        let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+        let message = err["error"]["message"].as_str().unwrap();
+        assert!(message.contains(&format!("pid {pid}")), "{message}");
+    }
+}
+
+#[test]
+fn test_app_glob_and_regex_patterns() {
+    let state = |app: &str, extra: &[&str]| {
+        menucli(
+            &[
+                &["state", "View::Show Sidebar", "--app", app, "--json"],
+                extra,
+            ]
+            .concat(),
+        )
+    };
+
+    // Name and bundle ID both match, but it is one app.
+    assert!(state("glob:*finder", &[]).status.success());
+    let out = state("re:^(finder|text)", &[]);
+    assert_eq!(out.status.code(), Some(4));
+    // --first takes the first match by name.
+    let out = state("re:^(finder|text)", &["--first"]);
+    assert_eq!(json(&out)[0]["path"], "View::Show Sidebar");
+
+    let out = state("re:(", &[]);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn test_single_app_outputs_attributed() {
+    for args in [
+        &["list", "--app", "Finder", "--json"][..],
+        &["search", "sidebar", "--app", "Finder", "--json"],
+        &["state", "View::Show Sidebar", "--app", "Finder", "--json"],
+        &[
+            "click",
+            "Show Sidebar",
+            "--app",
+            "Finder",
+            "--dry-run",
+            "--json",
+        ],
+    ] {
+        let out = menucli(args);
+        assert!(out.status.success(), "{args:?}");
+        assert_eq!(json(&out)[0]["app_name"], "Finder", "{args:?}");
+        assert_eq!(json(&out)[0]["app_pid"], 202, "{args:?}");
+        assert_eq!(json(&out)[0]["bundle_id"], "com.apple.finder", "{args:?}");
+    }
+
+    // One app needs no APP column.
+    let out = menucli(&["list", "--app", "Finder", "--output", "table"]);
+    assert!(!String::from_utf8(out.stdout).unwrap().contains("APP"));
+}
+
+#[test]
+fn test_parent_path() {
+    let out = menucli(&["list", "--json"]);
+    let items = json(&out);
+    assert_eq!(items[0]["path"], "File");
+    assert!(items[0].get("parent_path").is_none());
+    assert_eq!(items[1]["parent_path"], "File");
+
+    let out = menucli(&["state", "Format::Font::Bold", "--json"]);
+    assert_eq!(json(&out)[0]["parent_path"], "Format::Font");
+}
+
+#[test]
+fn test_descendants_count() {
+    let out = menucli(&["list", "--json"]);
+    let items = json(&out);
+    let field = |path: &str, name: &str| {
+        let item = items.as_array().unwrap().iter().find(|i| i["path"] == path);
+        item.unwrap()[name].clone()
+    };
+    assert_eq!(field("Format", "descendants_count"), 5);
+    assert_eq!(field("Format::Font", "descendants_count"), 2);
+    assert_eq!(field("Format::Font::Bold", "descendants_count"), 0);
+    // File::Print… is the only disabled item under File.
+    assert_eq!(field("File", "enabled_descendants"), 3);
+
+    let out = menucli(&["state", "Format::Font", "--json"]);
+    assert_eq!(json(&out)[0]["descendants_count"], 2);
+    assert_eq!(json(&out)[0]["enabled_descendants"], 2);
+}
+
+#[test]
+fn test_outputs_are_timestamped() {
+    // RFC 3339 UTC with milliseconds, e.g. `2024-05-01T09:30:00.250Z`.
+    let is_timestamp = |v: &Value| {
+        let t = v.as_str().unwrap_or_default();
+        t.len() == 24 && t.ends_with('Z') && t.as_bytes()[10] == b'T'
+    };
+
+    for (args, field) in [
+        (&["list", "--json"][..], "generated_at"),
+        (&["list", "--tree", "--json"], "generated_at"),
+        (&["search", "save", "--json"], "generated_at"),
+        (&["state", "File::Save", "--json"], "performed_at"),
+        (&["click", "File::Save", "--json"], "performed_at"),
+    ] {
+        let out = menucli(args);
+        assert!(out.status.success(), "{args:?}");
+        assert!(is_timestamp(&json(&out)[0][field]), "{args:?}");
+    }
+
+    // A tree is stamped once, on its top-level menus.
+    let out = menucli(&["list", "--tree", "--json"]);
+    assert!(json(&out)[0]["children"][0].get("generated_at").is_none());
+
+    // Nothing is performed on a dry run.
+    let out = menucli(&["click", "File::Save", "--dry-run", "--json"]);
+    assert!(json(&out)[0].get("performed_at").is_none());
+
+    let out = menucli(&["toggle", "Format::Wrap to Page", "--json"]);
+    assert!(is_timestamp(&json(&out)["performed_at"]));
+}
+
+#[test]
+fn test_extras_all_apps_attributed() {
+    let out = menucli(&["list", "--extras", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["app_name"], "TextEdit");
+    assert_eq!(json(&out)[0]["bundle_id"], "com.apple.TextEdit");
+}
+
+#[test]
+fn test_open_menus_lists_lazy_submenus() {
+    let paths = |extra: &[&str]| {
+        let args = [&["list", "--extras", "--app", "TextEdit", "--json"], extra].concat();
+        let out = menucli(&args);
+        assert!(out.status.success());
+        json(&out)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["path"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let recent = "TextEdit Helper::Open Recent::notes.txt".to_owned();
+    assert!(!paths(&[]).contains(&recent));
+    assert!(paths(&["--open-menus"]).contains(&recent));
+}
+
+#[test]
+fn test_from_file_snapshot_round_trip() {
+    let tree = menucli(&["list", "--tree", "--json"]);
+    assert!(tree.status.success());
+    let snapshot =
+        std::env::temp_dir().join(format!("menucli-snapshot-{}.json", std::process::id()));
+    std::fs::write(&snapshot, &tree.stdout).unwrap();
+
+    let from_snapshot = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .args(["--json", "--from-file"])
+            .arg(&snapshot)
+            .output()
+            .expect("failed to run menucli");
+        assert!(out.status.success(), "{args:?}");
+        json(&out)[0].clone()
+    };
+    let found = from_snapshot(&["search", "save as", "--limit", "1"]);
+    assert_eq!(found["path"], "File::Save As…");
+
+    // The snapshot's app is a stand-in, so nothing is attributed to it.
+    for args in [&["list"][..], &["search", "save"], &["state", "File::Save"]] {
+        let item = from_snapshot(args);
+        assert!(item.get("app_name").is_none(), "{args:?}");
+        assert!(item.get("app_pid").is_none(), "{args:?}");
+    }
+    std::fs::remove_file(&snapshot).ok();
+}
+
+#[test]
+fn test_schema_version_stamped_and_pinnable() {
+    let out = menucli(&["state", "File::Save", "--json"]);
+    assert_eq!(json(&out)[0]["schema_version"], 2);
+
+    let out = menucli(&["state", "File::Save", "--json", "--output-version", "1"]);
+    assert!(json(&out)[0].get("schema_version").is_none());
+}
+
+#[test]
+fn test_wildcard_path_all_and_first() {
+    let out = menucli(&["state", "*::Save*", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+
+    let out = menucli(&["state", "*::Save*", "--all", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out).as_array().unwrap().len(), 2);
+
+    let out = menucli(&["click", "*::Save*", "--first", "--dry-run", "--json"]);
+    assert_eq!(json(&out)[0]["path"], "File::Save");
+}
+
+#[test]
+fn test_index_segment_resolves_position() {
+    let out = menucli(&["state", "File::#2", "--json"]);
+    assert!(out.status.success());
+    let item = &json(&out)[0];
+    assert_eq!(item["path"], "File::Save");
+    assert_eq!(item["index"], 2);
+}
+
+#[test]
+fn test_item_id_round_trip() {
+    let out = menucli(&["search", "save as", "--json", "--limit", "1"]);
+    let id = json(&out)[0]["id"].as_str().unwrap().to_owned();
+
+    let out = menucli(&["state", "--id", &id, "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["path"], "File::Save As…");
+    assert_eq!(json(&out)[0]["id"], id.as_str());
+}
+
+#[test]
+fn test_within_scopes_resolution() {
+    let out = menucli(&["search", "bold", "--within", "Format::Font", "--json"]);
+    assert!(out.status.success());
+    let results = json(&out);
+    assert!(results
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["path"].as_str().unwrap().starts_with("Format::Font::")));
+
+    let out = menucli(&["state", "bold", "--within", "Format::Font", "--json"]);
+    assert_eq!(json(&out)[0]["path"], "Format::Font::Bold");
+}
+
+#[test]
+fn test_search_filters_apply_before_limit() {
+    let out = menucli(&["search", "", "--has-shortcut", "--limit", "50", "--json"]);
+    assert!(out.status.success());
+    let results = json(&out);
+    let results = results.as_array().unwrap();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|r| r["shortcut"].is_string()));
+
+    let out = menucli(&["search", "", "--max-depth", "1", "--json"]);
+    let paths: Vec<_> = json(&out)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["path"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(paths, ["File", "Edit", "Format"]);
+}
+
+#[test]
+fn test_search_limit_zero_is_unlimited() {
+    let count = |extra: &[&str]| {
+        let out = menucli(&[&["search", "", "--app", "TextEdit", "--json"], extra].concat());
+        assert!(out.status.success());
+        json(&out).as_array().unwrap().len()
+    };
+    assert_eq!(count(&[]), 10);
+    let all = count(&["--limit", "0"]);
+    assert!(all > 10);
+    assert_eq!(count(&["--no-limit"]), all);
+    assert_eq!(count(&["--no-limit", "--exact"]), all);
+}
+
+#[test]
+fn test_nul_separated_paths() {
+    let out = menucli(&["list", "-0"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.ends_with('\0') && !stdout.contains('\n'));
+    assert!(stdout.split('\0').any(|p| p == "File::Save As…"));
+
+    let out = menucli(&["search", "save as", "--limit", "1", "--output", "path0"]);
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "File::Save As…\0");
+}
+
+#[test]
+fn test_html_output() {
+    let out = menucli(&["list", "--app", "TextEdit", "--tree", "--output", "html"]);
+    assert!(out.status.success());
+    let page = String::from_utf8(out.stdout).unwrap();
+    assert!(page.starts_with("<!DOCTYPE html>"));
+    assert!(page.contains("<details open><summary title=\"File\">"));
+    assert!(page.contains("title=\"File::Save As…\""));
+
+    let out = menucli(&["search", "save", "--app", "TextEdit", "--output", "html"]);
+    let page = String::from_utf8(out.stdout).unwrap();
+    assert!(page.contains("<th>SCORE</th>"));
+    assert!(page.contains("<mark>Save</mark>"));
+}
+
+#[test]
+fn test_explain_resolution() {
+    let out = menucli(&["explain", "save", "--app", "TextEdit", "--json"]);
+    assert!(out.status.success());
+    let e = json(&out);
+    assert_eq!(e["strategy"], "exact_title");
+    assert_eq!(e["verdict"], "unique");
+    assert_eq!(e["resolved"], true);
+    assert_eq!(e["candidates"][0]["path"], "File::Save");
+
+    // Ambiguity is reported, not an error.
+    let out = menucli(&["explain", "sav", "--json", "--limit", "1"]);
+    assert!(out.status.success());
+    let e = json(&out);
+    assert_eq!(e["strategy"], "fuzzy");
+    let ratio = e["confidence_ratio"].as_f64().unwrap();
+    assert_eq!(e["resolved"], ratio >= 2.0);
+    assert_eq!(e["candidates"].as_array().unwrap().len(), 1);
+    assert!(e["candidates_count"].as_u64().unwrap() > 1);
+
+    let out = menucli(&["explain", "save", "--app", "TextEdit", "--output", "table"]);
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(text.contains("Strategy:   exact_title"));
+    assert!(text.contains("ENABLED"));
+    assert!(text
+        .lines()
+        .any(|l| l.contains("File::Save") && l.contains("yes")));
+}
+
+#[test]
+fn test_search_reports_index() {
+    let out = menucli(&["search", "save as", "--json"]);
+    assert_eq!(json(&out)[0]["path"], "File::Save As…");
+    assert_eq!(json(&out)[0]["index"], 3);
+}
+
+#[test]
+fn test_search_collapse_alternates() {
+    let search = |extra: &[&str]| {
+        let args = [&["search", "paste", "--app", "TextEdit", "--json"], extra].concat();
+        let out = menucli(&args);
+        assert!(out.status.success());
+        json(&out)
+    };
+    assert_eq!(search(&[]).as_array().unwrap().len(), 1);
+    assert_eq!(search(&["--alternates"]).as_array().unwrap().len(), 2);
+
+    let results = search(&["--collapse-alternates", "--limit", "1"]);
+    assert_eq!(results.as_array().unwrap().len(), 1);
+    assert_eq!(results[0]["path"], "Edit::Paste");
+    assert_eq!(
+        results[0]["alternates"][0]["path"],
+        "Edit::Paste and Match Style"
+    );
+}
+
+#[test]
+fn test_search_min_score_drops_weak_matches() {
+    let scores = |extra: &[&str]| -> Vec<u64> {
+        let args = [&["search", "save", "--app", "TextEdit", "--json"], extra].concat();
+        let out = menucli(&args);
+        assert!(out.status.success());
+        json(&out)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["score"].as_u64().unwrap())
+            .collect()
+    };
+    let all = scores(&["--no-limit"]);
+    let floor = all[0];
+    let strong = scores(&["--no-limit", "--min-score", &floor.to_string()]);
+    assert!(!strong.is_empty());
+    assert!(strong.iter().all(|&s| s >= floor));
+    // A floor nothing clears leaves nothing, whatever the limit.
+    assert!(scores(&["--limit", "1", "--min-score", "1000000"]).is_empty());
+}
+
+#[test]
+fn test_lang_en_matches_english_titles() {
+    let path = "File::Export as PDF...";
+    let out = menucli(&["state", path, "--app", "Vorschau", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    let out = menucli(&["state", path, "--app", "Vorschau", "--lang", "en", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["path"], "Ablage::Als PDF exportieren …");
+}
+
+#[test]
+fn test_list_leaf_only() {
+    let out = menucli(&["list", "--leaf-only", "--output", "path"]);
+    assert!(out.status.success());
+    let paths = String::from_utf8(out.stdout).unwrap();
+    assert!(paths.lines().any(|p| p == "Format::Font::Bold"));
+    assert!(!paths.lines().any(|p| p == "Format::Font" || p == "File"));
+}
+
+#[test]
+fn test_list_checked_and_shortcut_filters() {
+    let out = menucli(&[
+        "list",
+        "--app",
+        "Finder",
+        "--checked-only",
+        "--output",
+        "path",
+    ]);
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "View::Show Sidebar\n"
+    );
+
+    let out = menucli(&["list", "--with-shortcut", "--role", "AXMenuItem", "--json"]);
+    let items = json(&out);
+    assert!(!items.as_array().unwrap().is_empty());
+    assert!(items
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|i| i["shortcut"].is_string()));
+}
+
+#[test]
+fn test_list_count() {
+    let out = menucli(&[
+        "list",
+        "--count",
+        "--leaf-only",
+        "--menu",
+        "Format",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    let counts = json(&out);
+    assert_eq!(counts["total"], 4);
+    assert_eq!(counts["menus"][0]["menu"], "Format");
+
+    let out = menucli(&["list", "--count", "--output", "path"]);
+    assert!(String::from_utf8(out.stdout)
+        .unwrap()
+        .trim()
+        .parse::<usize>()
+        .is_ok());
+}
+
+#[test]
+fn test_ax_timeout_validated() {
+    assert!(menucli(&["--ax-timeout", "0.25", "list", "--json"])
+        .status
+        .success());
+    let out = menucli(&["--ax-timeout", "0", "list", "--json"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn test_cache_reuses_tree_until_no_cache() {
+    let dir = std::env::temp_dir().join(format!("menucli-cache-{}", std::process::id()));
+    let fixture = dir.join("fixture.json");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(FIXTURE, &fixture).unwrap();
+    let run = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .env("MENUCLI_FIXTURE", &fixture)
+            .env("MENUCLI_CACHE_DIR", &dir)
+            .output()
+            .unwrap();
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    assert!(run(&["list", "--cache", "--output", "path"]).contains("File::Save As…"));
+    let renamed = std::fs::read_to_string(&fixture)
+        .unwrap()
+        .replace("Save As…", "Duplicate");
+    std::fs::write(&fixture, renamed).unwrap();
+
+    assert!(run(&["list", "--cache", "--output", "path"]).contains("File::Save As…"));
+    assert!(run(&["list", "--cache", "--no-cache", "--output", "path"]).contains("File::Duplicate"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_config_file_sets_flag_defaults() {
+    let config = std::env::temp_dir().join(format!("menucli-config-{}.toml", std::process::id()));
+    let run = |contents: &str, args: &[&str]| {
+        std::fs::write(&config, contents).unwrap();
+        Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .env("MENUCLI_FIXTURE", FIXTURE)
+            .env("MENUCLI_CONFIG", &config)
+            .output()
+            .unwrap()
+    };
+
+    let settings = "output = \"path\"\ndefault_app = \"Finder\"\nexclude_menus = [\"Format\"]\n";
+    let out = run(settings, &["list"]);
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().lines().next(),
+        Some("View")
+    );
+
+    // Flags on the command line win over the config.
+    let out = run(settings, &["list", "--app", "TextEdit", "--json"]);
+    let paths = json(&out).to_string();
+    assert!(paths.contains("File::Save As…") && !paths.contains("Format::Font"));
+
+    let out = run("outptu = \"json\"\n", &["apps"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8(out.stderr).unwrap().contains("outptu"));
+    std::fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn test_config_app_profile() {
+    let config = std::env::temp_dir().join(format!("menucli-profile-{}.toml", std::process::id()));
+    std::fs::write(
+        &config,
+        "exclude_menus = [\"Edit\", \"Ablage\"]\n[app.\"com.apple.TextEdit\"]\nexclude_menus = [\"Format\"]\n",
+    )
+    .unwrap();
+    let menus = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .args(["--output", "path", "--depth", "1"])
+            .env("MENUCLI_FIXTURE", FIXTURE)
+            .env("MENUCLI_CONFIG", &config)
+            .output()
+            .unwrap();
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    // TextEdit is frontmost, so its profile replaces the top-level key.
+    assert_eq!(menus(&["list"]), "File\nEdit\n");
+    assert_eq!(
+        menus(&["list", "--exclude-menus", "View"]),
+        "File\nEdit\nFormat\n"
+    );
+    assert_eq!(menus(&["list", "--app", "Vorschau"]), "Darstellung\n");
+    std::fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn test_env_vars_between_config_and_flags() {
+    let config = std::env::temp_dir().join(format!("menucli-env-{}.toml", std::process::id()));
+    std::fs::write(&config, "default_app = \"Vorschau\"\noutput = \"json\"\n").unwrap();
+    let run = |env: &[(&str, &str)], args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .args(["--depth", "1"])
+            .env("MENUCLI_FIXTURE", FIXTURE)
+            .env("MENUCLI_CONFIG", &config)
+            .envs(env.iter().copied())
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        String::from_utf8(out.stdout).unwrap()
+    };
+    let env = [("MENUCLI_APP", "Finder"), ("MENUCLI_OUTPUT", "path")];
+
+    assert_eq!(run(&env, &["list"]), "View\nEdit\n");
+    assert_eq!(
+        run(&env, &["list", "--app", "TextEdit"]),
+        "File\nEdit\nFormat\n"
+    );
+    assert!(run(&env, &["list", "--output", "json"]).starts_with('['));
+    // Switches accept `1`, as well as `true`, `yes` and `on`.
+    let quiet = [("MENUCLI_APP", "Finder"), ("MENUCLI_QUIET", "1")];
+    assert_eq!(run(&quiet, &["state", "View::Show Sidebar"]), "");
+    let bare = [("MENUCLI_OUTPUT", "table"), ("MENUCLI_NO_HEADER", "1")];
+    assert!(!run(&bare, &["list"]).contains("PATH"));
+    // An ambient app does not apply to a snapshot.
+    assert_eq!(
+        run(&env, &["list", "--from-file", FIXTURE]),
+        "File\nEdit\nFormat\n"
+    );
+    std::fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn test_json_conflicts_with_output() {
+    let out = menucli(&["list", "--json", "--output", "path"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn test_hooks_run_around_actions() {
+    let dir = std::env::temp_dir().join(format!("menucli-hooks-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("config.toml");
+    let log = dir.join("hook.log");
+    let run = |hooks: &str, args: &[&str]| {
+        std::fs::write(&config, format!("[hooks]\n{hooks}")).unwrap();
+        Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .env("MENUCLI_FIXTURE", FIXTURE)
+            .env("MENUCLI_CONFIG", &config)
+            .output()
+            .unwrap()
+    };
+
+    let hook = format!(
+        "post_toggle = 'cat > {}; echo \"$MENUCLI_HOOK $MENUCLI_PID\"'\n",
+        log.display()
+    );
+    let out = run(
+        &hook,
+        &["toggle", "View::Show Path Bar", "--app", "Finder", "--json"],
+    );
+    assert!(out.status.success());
+    // The hook's output goes to stderr, keeping stdout parseable.
+    assert_eq!(json(&out)["checked_after"], true);
+    assert_eq!(String::from_utf8(out.stderr).unwrap(), "post_toggle 202\n");
+    let input: Value = serde_json::from_slice(&std::fs::read(&log).unwrap()).unwrap();
+    assert_eq!(input["path"], "View::Show Path Bar");
+
+    let out = run("pre_click = 'exit 1'\n", &["click", "File::Save", "--json"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty());
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "hook_failed");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_do_runs_config_alias() {
+    let config = std::env::temp_dir().join(format!("menucli-aliases-{}.toml", std::process::id()));
+    std::fs::write(
+        &config,
+        r#"
+[aliases]
+path-bar = { app = "Finder", path = "View::Show Path Bar", command = "toggle", flags = ["--expect", "on"] }
+save = { path = "File::Save" }
+"#,
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(args)
+            .env("MENUCLI_FIXTURE", FIXTURE)
+            .env("MENUCLI_CONFIG", &config)
+            .output()
+            .unwrap()
+    };
+
+    let out = run(&["do", "path-bar", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["checked_after"], true);
+
+    // Global flags before `do` and extra flags after the name both apply.
+    let out = run(&["--json", "do", "save", "--dry-run"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["path"], "File::Save");
+
+    let out = run(&["do", "nope"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("path-bar, save"));
+    std::fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn test_bench_reports_per_menu_timings() {
+    let out = menucli(&["bench", "--iterations", "2", "--json"]);
+    assert!(out.status.success());
+    let bench = json(&out);
+    let items = bench["items"].as_u64().unwrap();
+    let menu_items: u64 = bench["menus"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["items"].as_u64().unwrap())
+        .sum();
+    assert_eq!(menu_items, items);
+    assert_eq!(bench["iterations"], 2);
+    assert!(bench["fetch_batch"]["median_ms"].is_number());
+
+    assert!(!menucli(&["bench", "--iterations", "0"]).status.success());
+}
+
+#[test]
+fn test_debug_json_spans_nest_under_command() {
+    let out = menucli(&["--debug-json", "list", "--json"]);
+    assert!(out.status.success());
+    let spans: Vec<Value> = String::from_utf8(out.stderr)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("span is not JSON"))
+        .collect();
+    let root = spans.iter().find(|s| s["name"] == "command").unwrap();
+    assert!(root["parent"].is_null());
+    let tree = spans.iter().find(|s| s["name"] == "build_tree").unwrap();
+    assert_eq!(tree["parent"], root["id"]);
+    assert!(tree["duration_ms"].is_number());
+}
+
+#[test]
+fn test_log_level_emits_json_records() {
+    let dir = std::env::temp_dir().join(format!("menucli-log-{}", std::process::id()));
+    let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+        .args(["--log-level", "debug", "--log-format", "json"])
+        .args(["list", "--cache", "--json"])
+        .env("MENUCLI_FIXTURE", FIXTURE)
+        .env("MENUCLI_CACHE_DIR", &dir)
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let records: Vec<Value> = String::from_utf8(out.stderr)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("log record is not JSON"))
+        .collect();
+    assert!(records
+        .iter()
+        .any(|r| r["fields"]["message"] == "menu tree cache miss"));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // Without --log-level or RUST_LOG, stderr stays quiet.
+    assert!(menucli(&["list", "--json"]).stderr.is_empty());
+}
+
+#[test]
+fn test_timeout_validated_and_untruncated_when_met() {
+    let out = menucli(&["--timeout", "30s", "list", "--json"]);
+    assert!(out.status.success());
+    assert!(json(&out)[0].get("truncated").is_none());
+
+    assert!(!menucli(&["--timeout", "0s", "list"]).status.success());
+    assert!(!menucli(&["--timeout", "soon", "list"]).status.success());
+    // Too long for a `Duration`: a usage error, not a panic.
+    let out = menucli(&["--timeout", "1e30", "list"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8(out.stderr).unwrap().contains("1e30"));
+}
+
+#[test]
+fn test_activate_and_restore_focus() {
+    // Each command line, with whether it activates and hands focus back.
+    let cases: &[(&[&str], bool, bool)] = &[
+        (
+            &[
+                "state",
+                "View::Show Sidebar",
+                "--app",
+                "Finder",
+                "--activate",
+                "--restore-focus",
+            ],
+            true,
+            true,
+        ),
+        // Without --activate, focus is still handed back after a click that took it.
+        (
+            &[
+                "click",
+                "View::Show Sidebar",
+                "--app",
+                "Finder",
+                "--restore-focus",
+            ],
+            false,
+            true,
+        ),
+        (
+            &["list", "--extras", "--app", "TextEdit", "--activate"],
+            true,
+            false,
+        ),
+        (
+            &[
+                "search",
+                "Save",
+                "--extras",
+                "--app",
+                "Finder",
+                "--activate",
+                "--restore-focus",
+            ],
+            true,
+            true,
+        ),
+    ];
+    for &(args, activates, restores) in cases {
+        let out = menucli(&[&["--debug-json"], args].concat());
+        assert!(out.status.success(), "{args:?}");
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        assert_eq!(
+            stderr.contains(r#""name":"activate""#),
+            activates,
+            "{args:?}"
+        );
+        assert_eq!(
+            stderr.contains(r#""name":"restore_focus""#),
+            restores,
+            "{args:?}"
+        );
+    }
+
+    // Every app's extras have no one app to bring to the front.
+    let out = menucli(&["list", "--extras", "--activate"]);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn test_click_sequence() {
+    let out = menucli(&[
+        "click",
+        "File::Save",
+        "Edit::Copy",
+        "--delay",
+        "10ms",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    let items = json(&out);
+    assert_eq!(items[0]["path"], "File::Save");
+    assert_eq!(items[1]["path"], "Edit::Copy");
+
+    // Stops at the disabled item, reporting what was clicked before it.
+    let out = menucli(&[
+        "click",
+        "File::Save",
+        "File::Print…",
+        "Edit::Copy",
+        "--json",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+    let items = json(&out);
+    assert_eq!(items.as_array().unwrap().len(), 1);
+    assert_eq!(items[0]["path"], "File::Save");
+
+    // A path that does not resolve clicks nothing.
+    let out = menucli(&["click", "File::Save", "Nope::Nothing", "--exact", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    assert!(out.stdout.is_empty());
+}
+
+#[test]
+fn test_click_stdin() {
+    let out = menucli_with_stdin(
+        &["click", "--stdin", "--json"],
+        "File::Save\n\nEdit::Copy\n",
+    );
+    assert!(out.status.success());
+    let items = json(&out);
+    assert_eq!(items[0]["path"], "File::Save");
+    assert_eq!(items[1]["path"], "Edit::Copy");
+
+    let out = menucli_with_stdin(&["click", "--stdin"], "");
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn test_click_wait_for() {
+    let out = menucli(&[
+        "click",
+        "Format::Wrap to Page",
+        "--wait-for",
+        "state:Format::Wrap to Page=checked",
+        "--json",
+    ]);
+    assert!(out.status.success());
+
+    let out = menucli(&["click", "File::Save", "--wait-for", "window-title:untitled"]);
+    assert!(out.status.success());
+
+    let out = menucli(&[
+        "click",
+        "File::Save",
+        "--wait-for",
+        "window-title:Export",
+        "--wait-timeout",
+        "200ms",
+        "--json",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(json(&out)[0]["path"], "File::Save");
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "condition_not_met");
+
+    let out = menucli(&["click", "File::Save", "--wait-for", "state:File::Save=open"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn test_click_modifiers_reported() {
+    let out = menucli(&[
+        "click",
+        "View::Show Sidebar",
+        "--app",
+        "Finder",
+        "--modifiers",
+        "option,shift",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    assert_eq!(
+        json(&out)[0]["modifiers"],
+        serde_json::json!(["option", "shift"])
+    );
+
+    let out = menucli(&["click", "View::Show Sidebar", "--app", "Finder", "--json"]);
+    assert!(json(&out)[0].get("modifiers").is_none());
+
+    assert!(!menucli(&["click", "Save", "--modifiers", "hyper"])
+        .status
+        .success());
+}
+
+#[test]
+fn test_virtual_key_in_output() {
+    let out = menucli(&["search", "save as", "--json", "--limit", "1"]);
+    assert_eq!(json(&out)[0]["virtual_key"], 1);
+
+    let out = menucli(&["list", "--tree", "--json"]);
+    let file = &json(&out)[0]["children"];
+    assert!(file[0].get("virtual_key").is_none());
+    assert_eq!(file[2]["virtual_key"], 1);
+}
+
+#[test]
+fn test_raw_shortcut_fields() {
+    let out = menucli(&["search", "save as", "--json", "--limit", "1"]);
+    let item = &json(&out)[0];
+    assert_eq!(item["shortcut"], "⇧⌘S");
+    assert_eq!(item["cmd_char"], "S");
+    assert_eq!(item["cmd_modifiers_mask"], 1);
+    assert!(item.get("cmd_glyph").is_none());
+
+    let out = menucli(&["list", "--app", "TextEdit", "--json"]);
+    assert_eq!(json(&out)[3]["cmd_char"], "S");
+}
+
+#[test]
+fn test_help_column_only_when_requested() {
+    let out = menucli(&["list", "--output", "table", "--fields", "path,help"]);
+    let table = String::from_utf8(out.stdout).unwrap();
+    assert!(table.contains("HELP"));
+    assert!(table.contains("Print the document"));
+
+    let out = menucli(&["list", "--output", "table"]);
+    assert!(!String::from_utf8(out.stdout).unwrap().contains("HELP"));
+
+    let out = menucli(&["state", "File::Print…", "--json"]);
+    assert_eq!(json(&out)[0]["help"], "Print the document");
+}
+
+#[test]
+fn test_selected_reported_where_known() {
+    let out = menucli(&["state", "View::Show Sidebar", "--app", "Finder", "--json"]);
+    assert_eq!(json(&out)[0]["selected"], true);
+    assert!(json(&out)[0].get("focused").is_none());
+
+    let out = menucli(&["state", "View::Show Path Bar", "--app", "Finder", "--json"]);
+    assert!(json(&out)[0].get("selected").is_none());
+}
+
+#[test]
+fn test_mixed_state_reported_and_not_toggled() {
+    let out = menucli(&[
+        "state",
+        "Darstellung::Miniaturen",
+        "--app",
+        "Vorschau",
+        "--json",
+    ]);
+    let item = &json(&out)[0];
+    assert_eq!(item["check_state"], "mixed");
+    assert_eq!(item["checked"], true);
+
+    let out = menucli(&[
+        "toggle",
+        "Darstellung::Miniaturen",
+        "--app",
+        "Vorschau",
+        "--json",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "mixed_state");
+
+    let out = menucli(&["state", "View::Show Path Bar", "--app", "Finder", "--json"]);
+    assert_eq!(json(&out)[0]["check_state"], "off");
+}
+
+#[test]
+fn test_state_multiple_paths() {
+    let list = std::env::temp_dir().join(format!("menucli-paths-{}.txt", std::process::id()));
+    std::fs::write(&list, "View::Show Path Bar\n\n").unwrap();
+    let out = menucli(&[
+        "state",
+        "View::Show Sidebar",
+        "--paths-from",
+        list.to_str().unwrap(),
+        "--app",
+        "Finder",
+        "--json",
+    ]);
+    std::fs::remove_file(&list).unwrap();
+    assert!(out.status.success());
+    let items = json(&out);
+    let paths: Vec<_> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["View::Show Sidebar", "View::Show Path Bar"]);
+    assert_eq!(items[1]["checked"], false);
+
+    // One unresolvable path fails the whole call.
+    let out = menucli(&[
+        "state",
+        "View::Show Sidebar",
+        "View::Nope",
+        "--app",
+        "Finder",
+    ]);
+    assert_eq!(out.status.code(), Some(4));
+}
+
+#[test]
+fn test_state_assert_quiet() {
+    let out = menucli(&[
+        "state",
+        "View::Show Sidebar",
+        "--app",
+        "Finder",
+        "--assert",
+        "checked,enabled",
+        "--quiet",
+    ]);
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+
+    let out = menucli(&[
+        "state",
+        "View::Show Path Bar",
+        "--app",
+        "Finder",
+        "--assert",
+        "checked",
+        "--quiet",
+    ]);
+    assert_eq!(out.status.code(), Some(6));
+    assert!(out.stdout.is_empty() && out.stderr.is_empty());
+
+    let out = menucli(&["state", "File::Print…", "--assert", "enabled", "--json"]);
+    assert_eq!(out.status.code(), Some(6));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "assertion_failed");
+
+    let out = menucli(&[
+        "state",
+        "File::Nope",
+        "--exact",
+        "--assert",
+        "exists",
+        "--quiet",
+    ]);
+    // Only a failed assertion is silent; other errors still reach stderr.
+    assert_eq!(out.status.code(), Some(4));
+    assert!(!out.stderr.is_empty());
+}
+
+#[test]
+fn test_quiet_action_commands() {
+    let out = menucli(&["-q", "click", "File::Save"]);
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+
+    let out = menucli(&[
+        "toggle",
+        "View::Show Path Bar",
+        "--app",
+        "Finder",
+        "--quiet",
+    ]);
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+
+    let out = menucli(&["--quiet", "click", "File::Nope", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    assert!(out.stdout.is_empty());
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "item_not_found");
+}
+
+#[test]
+fn test_toggle_expect() {
+    // Already on: nothing is pressed.
+    let out = menucli(&[
+        "--debug-json",
+        "toggle",
+        "View::Show Sidebar",
+        "--app",
+        "Finder",
+        "--expect",
+        "on",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert_eq!(result["checked_before"], true);
+    assert_eq!(result["checked_after"], true);
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(!stderr.contains(r#""name":"press_and_confirm""#));
+
+    let out = menucli(&[
+        "toggle",
+        "View::Show Path Bar",
+        "--app",
+        "Finder",
+        "--expect",
+        "on",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["checked_after"], true);
+
+    // An ignored press leaves the checkmark alone, so --expect presses again.
+    let path = "Edit::Spelling and Grammar::Check Grammar With Spelling";
+    let out = menucli(&["toggle", path, "--app", "Finder", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["checked_after"], false);
+    let out = menucli(&[
+        "toggle", path, "--app", "Finder", "--expect", "on", "--json",
+    ]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["checked_after"], true);
+
+    let out = menucli(&["toggle", "View::Show Path Bar", "--expect", "maybe"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn test_radio_items_reported_and_chosen_one_not_toggled() {
+    let out = menucli(&[
+        "state",
+        "Darstellung::Anzeige::Einzelseite",
+        "--app",
+        "Vorschau",
+        "--json",
+    ]);
+    let item = &json(&out)[0];
+    assert_eq!(item["is_radio"], true);
+    assert_eq!(item["group"], "Darstellung::Anzeige#1");
+
+    let out = menucli(&["state", "View::Show Path Bar", "--app", "Finder", "--json"]);
+    assert!(json(&out)[0].get("is_radio").is_none());
+
+    let out = menucli(&[
+        "toggle",
+        "Darstellung::Anzeige::Einzelseite",
+        "--app",
+        "Vorschau",
+        "--json",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "radio_selected");
+
+    // Toggling an unchosen radio item selects it.
+    let out = menucli(&[
+        "toggle",
+        "Darstellung::Anzeige::Zwei Seiten",
+        "--app",
+        "Vorschau",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["checked_after"], true);
+}
+
+#[test]
+fn test_single_checked_submenu_item_toggles_off() {
+    // The lone checkmark among submenu leaves looks like a radio choice, but
+    // nothing bullets it, so the toggle goes ahead with a warning.
+    let path = "Edit::Spelling and Grammar::Check Spelling While Typing";
+    let out = menucli(&["toggle", path, "--app", "Finder", "--json"]);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert_eq!(result["checked_before"], true);
+    assert_eq!(result["checked_after"], false);
+    assert!(String::from_utf8(out.stderr).unwrap().contains("Warning"));
+}
+
+#[test]
+fn test_completions() {
+    for shell in ["bash", "zsh", "fish"] {
+        let out = menucli(&["completions", shell]);
+        assert!(out.status.success(), "{shell}");
+        let script = String::from_utf8(out.stdout).unwrap();
+        assert!(
+            script.contains("COMPLETE="),
+            "{shell} script does not call back"
+        );
+    }
+    let out = menucli(&["completions", "nushell"]);
+    assert!(out.status.success());
+    let script = String::from_utf8(out.stdout).unwrap();
+    for word in ["search", "check-access", "ndjson"] {
+        assert!(script.contains(word), "nushell script lacks {word}");
+    }
+}
+
+#[test]
+fn test_complete_candidates() {
+    let cache = std::env::temp_dir().join(format!("menucli-complete-{}", std::process::id()));
+    // What fish's registration runs on TAB: the words so far, the current one last.
+    let complete = |words: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .args(["--", "menucli"])
+            .args(words)
+            .env("COMPLETE", "fish")
+            .env("MENUCLI_FIXTURE", FIXTURE)
+            .env("MENUCLI_CACHE_DIR", &cache)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        assert!(out.stderr.is_empty());
+        // Fish candidates are `value<TAB>help`, one per line.
+        String::from_utf8(out.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.split('\t').next().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        complete(&["state", "--app", ""]),
+        ["Finder", "TextEdit", "Vorschau"]
+    );
+    assert_eq!(complete(&["state", "--app", "te"]), ["TextEdit"]);
+    assert_eq!(
+        complete(&["click", "file::s"]),
+        ["File::Save", "File::Save As…"]
+    );
+    assert_eq!(
+        complete(&["toggle", "--app=Finder", "vi"])[..2],
+        ["View", "View::Show Sidebar"]
+    );
+    // Nothing to offer is not an error.
+    assert!(complete(&["state", "--app", "Nope", "fi"]).is_empty());
+    let _ = std::fs::remove_dir_all(&cache);
+}
+
+#[test]
+fn test_man_pages() {
+    let out = menucli(&["man"]);
+    assert!(out.status.success());
+    let page = String::from_utf8(out.stdout).unwrap();
+    assert!(page.starts_with(".ie"));
+    assert!(page.contains(".SH \"EXIT STATUS\""));
+    assert!(page.contains("\\fB124\\fR"));
+
+    let dir = std::env::temp_dir().join(format!("menucli-man-{}", std::process::id()));
+    assert!(menucli(&["man", "--out-dir", dir.to_str().unwrap()])
+        .status
+        .success());
+    for page in ["menucli.1", "menucli-click.1", "menucli-completions.1"] {
+        assert!(dir.join(page).exists(), "{page}");
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_check_access_open_settings_when_trusted() {
+    // Nothing to remediate: the flag must not open anything.
+    let out = menucli(&["check-access", "--open-settings", "--json"]);
+    assert!(out.status.success());
+    assert!(out.stderr.is_empty());
+    let json: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["ok"], true);
+}
+
+#[test]
+fn test_apps_capability_filters() {
+    // "name has_menu has_extras" per app; unprobed capabilities are absent.
+    let apps = |args: &[&str]| {
+        let out = menucli(&[&["apps", "--json"], args].concat());
+        assert!(out.status.success());
+        let json: Value = serde_json::from_slice(&out.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|app| format!("{} {} {}", app["name"], app["has_menu"], app["has_extras"]))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        apps(&[]),
+        [
+            r#""Finder" null null"#,
+            r#""TextEdit" null null"#,
+            r#""Vorschau" null null"#
+        ]
+    );
+    assert_eq!(
+        apps(&["--with-menu"]),
+        [
+            r#""Finder" true null"#,
+            r#""TextEdit" true null"#,
+            r#""Vorschau" true null"#
+        ]
+    );
+    assert_eq!(
+        apps(&["--with-menu", "--with-extras"]),
+        [r#""TextEdit" true true"#]
+    );
+
+    let out = menucli_with_stdin(
+        &["rpc"],
+        r#"{"jsonrpc":"2.0","id":1,"method":"apps","params":{"with_extras":true}}"#,
+    );
+    let reply: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(reply["result"].as_array().unwrap().len(), 1);
+    assert_eq!(reply["result"][0]["name"], "TextEdit");
+}
+
+#[test]
+fn test_apps_all_includes_background_processes() {
+    let names = |args: &[&str]| {
+        let out = menucli(&[&["apps", "--json"], args].concat());
+        assert!(out.status.success());
+        let json: Value = serde_json::from_slice(&out.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|app| format!("{} {}", app["name"], app["activation_policy"]))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        names(&[]),
+        [
+            r#""Finder" "regular""#,
+            r#""TextEdit" "regular""#,
+            r#""Vorschau" "regular""#
+        ]
+    );
+    // The nameless agent is listed by its bundle ID.
+    assert_eq!(
+        names(&["--all"]),
+        [
+            r#""Finder" "regular""#,
+            r#""TextEdit" "regular""#,
+            r#""Vorschau" "regular""#,
+            r#""com.apple.CoreServicesUIAgent" "prohibited""#
+        ]
+    );
+
+    let out = menucli_with_stdin(
+        &["rpc"],
+        r#"{"jsonrpc":"2.0","id":1,"method":"apps","params":{"all":true}}"#,
+    );
+    let reply: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(reply["result"].as_array().unwrap().len(), 4);
+}
+
+#[test]
+fn test_apps_filter_by_name_or_bundle_id() {
+    let names = |args: &[&str]| {
+        let out = menucli(&[&["apps", "--json"], args].concat());
+        assert!(out.status.success());
+        let json: Value = serde_json::from_slice(&out.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|app| app["name"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(&["--filter", "TEXT"]), ["TextEdit"]);
+    // Matched by bundle ID.
+    assert_eq!(names(&["--filter", "preview"]), ["Vorschau"]);
+    // A glob must match the whole name.
+    assert_eq!(names(&["--filter", "f*"]), ["Finder"]);
+    assert!(names(&["--filter", "ind*"]).is_empty());
+    // `?` is one character, as in `--app glob:`.
+    assert_eq!(names(&["--filter", "finde?"]), ["Finder"]);
+    assert_eq!(
+        names(&["--filter", "^(finder|textedit)$", "--regex"]),
+        ["Finder", "TextEdit"]
+    );
+    assert_eq!(
+        menucli(&["apps", "--filter", "(", "--regex"]).status.code(),
+        Some(1)
+    );
+
+    let out = menucli(&[
+        "apps", "--filter", "text", "--output", "id", "--fields", "pid",
+    ]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "101\n");
+}
+
+#[test]
+fn test_apps_sort_and_fields() {
+    let names = |args: &[&str]| {
+        let out = menucli(&[&["apps", "--all", "--json"], args].concat());
+        assert!(out.status.success());
+        let json: Value = serde_json::from_slice(&out.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|app| app["name"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        names(&["--sort", "pid"]),
+        [
+            "TextEdit",
+            "Finder",
+            "Vorschau",
+            "com.apple.CoreServicesUIAgent"
+        ]
+    );
+    assert_eq!(
+        names(&["--sort", "frontmost"]),
+        [
+            "TextEdit",
+            "Finder",
+            "Vorschau",
+            "com.apple.CoreServicesUIAgent"
+        ]
+    );
+    // Apps without a launch date go last.
+    assert_eq!(
+        names(&["--sort", "launch-date"]),
+        [
+            "TextEdit",
+            "Vorschau",
+            "Finder",
+            "com.apple.CoreServicesUIAgent"
+        ]
+    );
+
+    let out = menucli(&["apps", "--json", "--fields", "pid,launch_date"]);
+    assert_eq!(
+        json(&out)[0],
+        serde_json::json!({"schema_version": 2, "pid": 202, "launch_date": 1_700_000_000})
+    );
+
+    let out = menucli_with_stdin(
+        &["rpc"],
+        r#"{"jsonrpc":"2.0","id":1,"method":"apps","params":{"sort":"launch-date"}}"#,
+    );
+    let reply: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(reply["result"][0]["name"], "TextEdit");
+}
+
+#[test]
+fn test_launch_starts_missing_app() {
+    let out = menucli(&["state", "File::New Note", "--app", "Notes", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+
+    for app in ["Notes", "com.apple.Notes"] {
+        let args = [
+            "state",
+            "File::New Note",
+            "--app",
+            app,
+            "--launch",
+            "--json",
+        ];
+        let out = menucli(&args);
+        assert!(out.status.success(), "{app}");
+        assert_eq!(json(&out)[0]["shortcut"], "⌘N");
+    }
+    // Nothing installed by that name.
+    let out = menucli(&["state", "Save", "--app", "Pages", "--launch", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+}
+
+#[test]
+fn test_app_by_bundle_path() {
+    let args = [
+        "state",
+        "File::Save",
+        "--app",
+        "/System/Applications/TextEdit.app",
+        "--json",
+    ];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["shortcut"], "⌘S");
+
+    let notes = "/System/Applications/Notes.app";
+    let out = menucli(&["state", "File::New Note", "--app", notes, "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    let args = [
+        "state",
+        "File::New Note",
+        "--app",
+        notes,
+        "--launch",
+        "--json",
+    ];
+    let out = menucli(&args);
+    assert!(out.status.success());
+}
+
+#[test]
+fn test_several_apps_are_attributed() {
+    let out = menucli(&["list", "--app", "TextEdit", "--app", "Finder", "--json"]);
+    assert!(out.status.success());
+    let items = json(&out);
+    let items = items.as_array().unwrap();
+    assert!(items
+        .iter()
+        .any(|i| i["app_name"] == "TextEdit" && i["path"] == "File::Save"));
+    assert!(items
+        .iter()
+        .any(|i| i["app_pid"] == 202 && i["path"] == "View::Show Sidebar"));
+
+    let args = [
+        "search", "Show", "--app", "Finder", "--app", "Vorschau", "--json",
+    ];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    let results = json(&out);
+    let apps: Vec<&str> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["app_name"].as_str().unwrap())
+        .collect();
+    assert!(apps.contains(&"Finder"));
+    assert!(apps.iter().all(|a| *a == "Finder" || *a == "Vorschau"));
+
+    // Like search, state leaves out an app without the item...
+    let args = [
+        "state",
+        "File::Save",
+        "--app",
+        "TextEdit",
+        "--app",
+        "Finder",
+        "--json",
+    ];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    let items = json(&out);
+    assert_eq!(items.as_array().unwrap().len(), 1);
+    assert_eq!(items[0]["app_name"], "TextEdit");
+    // ...and fails only when no app has it.
+    let args = [
+        "state",
+        "Nope::Nothing",
+        "--app",
+        "TextEdit",
+        "--app",
+        "Finder",
+        "--json",
+    ];
+    assert_eq!(menucli(&args).status.code(), Some(4));
+
+    let args = [
+        "state",
+        "View::Show Sidebar",
+        "--app",
+        "Finder",
+        "--app",
+        "202",
+        "--json",
+    ];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    assert_eq!(json(&out).as_array().unwrap().len(), 1);
+
+    // A comma belongs to the app pattern, not between two apps.
+    let out = menucli(&[
+        "state",
+        "File::Save",
+        "--app",
+        "re:^Tex{1,2}tEdit$",
+        "--json",
+    ]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)[0]["app_name"], "TextEdit");
+
+    let out = menucli(&["list", "--app", "TextEdit", "--app", "Finder", "--activate"]);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn test_resolve_reports_strategy() {
+    let out = menucli(&["resolve", "File::Save", "--app", "TextEdit", "--json"]);
+    assert!(out.status.success());
+    let r = &json(&out)[0];
+    assert_eq!(r["path"], "File::Save");
+    assert_eq!(r["strategy"], "exact_path");
+    assert!(r.get("score").is_none());
+
+    let out = menucli(&["resolve", "sav as", "--app", "TextEdit", "--json"]);
+    assert!(out.status.success());
+    let r = &json(&out)[0];
+    assert_eq!(r["path"], "File::Save As…");
+    assert_eq!(r["strategy"], "fuzzy");
+    assert!(r["score"].as_u64().unwrap() > 0);
+
+    // Ambiguous without --all, listed best first with it.
+    let out = menucli(&["resolve", "File::*", "--app", "TextEdit", "--json"]);
+    assert_eq!(out.status.code(), Some(4));
+    let args = ["resolve", "File::*", "--app", "TextEdit", "--all", "--json"];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    assert_eq!(json(&out).as_array().unwrap().len(), 4);
+}
+
+#[test]
+fn test_action_lists_and_performs() {
+    let out = menucli(&["action", "File::Save As…", "--json"]);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert!(result.get("action").is_none());
+    let actions = result["actions"].as_array().unwrap();
+    assert!(actions.contains(&Value::from("AXCancel")));
+    assert!(!actions.contains(&Value::from("AXShowMenu")));
+
+    let args = ["action", "File::Save As…", "--name", "AXCancel", "--json"];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert_eq!(result["action"], "AXCancel");
+    assert!(result["performed_at"].is_string());
+
+    let args = ["action", "File::Save As…", "--name", "AXShowMenu", "--json"];
+    let out = menucli(&args);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "action_unsupported");
+    assert!(err["error"]["candidates"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("AXPress")));
+}
+
+#[test]
+fn test_attr_reads_any_attribute() {
+    let out = menucli(&["attr", "File::Save As…", "AXPosition", "--json"]);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert_eq!(result["attribute"], "AXPosition");
+    assert_eq!(result["value"]["x"], 112.0);
+    assert_eq!(result["value"]["y"], 86.0);
+
+    // Attributes read while building the tree need no fixture entry.
+    let args = [
+        "attr",
+        "File::Print…",
+        "--attr",
+        "AXHelp",
+        "--output",
+        "table",
+    ];
+    let out = menucli(&args);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(stdout.trim(), r#""Print the document""#);
+
+    let out = menucli(&["attr", "File::Save", "AXFrame", "--json"]);
+    assert!(out.status.success());
+    assert!(json(&out)["value"].is_null());
+}
+
+#[test]
+fn test_focused_reports_app_and_highlighted_item() {
+    let out = menucli(&["focused", "--json"]);
+    assert!(out.status.success());
+    let result = json(&out);
+    assert_eq!(result["app_name"], "TextEdit");
+    assert_eq!(result["app_pid"], 101);
+    assert_eq!(result["element"]["role"], "AXTextArea");
+    assert!(result.get("highlighted").is_none());
+
+    let out = menucli(&["focused", "--highlighted", "--json"]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["highlighted"]["path"], "Format::Font::Bold");
+}
+
+#[test]
+fn test_action_screenshot_needs_an_open_menu() {
+    let args = [
+        "action",
+        "Format::Font",
+        "--name",
+        "AXShowMenu",
+        "--screenshot",
+        "font.png",
+        "--json",
+    ];
+    // The fixture has no screen to find the opened menu on.
+    let out = menucli(&args);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "screenshot_failed");
+
+    // AXShowMenu is implied, and no other action is performed.
+    let out = menucli(&[
+        "action",
+        "Format::Font",
+        "--screenshot",
+        "font.png",
+        "--json",
+    ]);
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "screenshot_failed");
+    let out = menucli(&[
+        "action",
+        "Format::Font",
+        "--name",
+        "AXPress",
+        "--screenshot",
+        "font.png",
+        "--json",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn test_preview_panel() {
+    let dir = std::env::temp_dir().join(format!("menucli-preview-{}", std::process::id()));
+    let cache = dir.join("cache");
+    // A copy of the fixture, changed below to tell cached trees from fresh ones.
+    let fixture = dir.join("textedit.json");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(FIXTURE, &fixture).unwrap();
+    let preview = |args: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_menucli"))
+            .arg("preview")
+            .args(args)
+            .env("MENUCLI_FIXTURE", &fixture)
+            .env("MENUCLI_CACHE_DIR", &cache)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    let panel = preview(&["File::Print…"]);
+    assert!(panel.starts_with("Print…  ⌘P\nFile::Print…\n"));
+    assert!(panel.contains("enabled   no\n"));
+    assert!(panel.contains("help      Print the document\n"));
+    assert!(panel.contains("\n▸ Print…    ⌘P  (disabled)\n"));
+    assert!(panel.contains("\n  Save As…  ⇧⌘S\n"));
+    assert!(std::fs::read_dir(&cache).unwrap().any(|entry| entry
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .starts_with("com.apple.TextEdit-")));
+
+    // Later previews are served from the cache the first one left, so they
+    // miss changes to the menus until it expires.
+    let changed = std::fs::read_to_string(&fixture)
+        .unwrap()
+        .replace("Print the document", "Print it all");
+    std::fs::write(&fixture, changed).unwrap();
+    assert!(preview(&["File::Print…"]).contains("help      Print the document\n"));
+    assert!(preview(&["Format::Font::Bold"]).contains("\nFormat::Font\n"));
+    assert!(preview(&["File::Print…", "--no-cache"]).contains("help      Print it all\n"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}